@@ -0,0 +1,77 @@
+//! ```bash
+//! CDN_PKG_PATH=/pkg CDN_PATH= cargo bench --features ssr --bench markdown
+//! ```
+//!
+//! Compares rendering a description the way
+//! `markdown::compile` used to, loading the
+//! theme every time, with the shared highlighter
+//! and with the render cache.
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+use this_week_in_bevy::markdown::{
+    compile, render_with, syntect_adapter, TrustLevel,
+};
+
+const DESCRIPTION: &str = r#"hexx is an hexagonal utility library made with bevy integration in mind
+
+What's new:
+
+* A new sprite sheet example
+* A lot of improvements in mesh generation, especially with procedural UV coordinates
+
+```rust
+fn setup(mut commands: Commands) {
+    let layout = HexLayout {
+        hex_size: Vec2::splat(16.0),
+        ..default()
+    };
+    commands.spawn(Camera2dBundle::default());
+}
+```
+"#;
+
+fn bench(
+    name: &str,
+    iterations: u32,
+    f: impl Fn() -> String,
+) {
+    // warm up the statics
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    let per_iteration: Duration =
+        start.elapsed() / iterations;
+    println!("{name:<32} {per_iteration:>12.2?} / render");
+}
+
+fn main() {
+    bench(
+        "loading the theme every time",
+        20,
+        || {
+            render_with(
+                black_box(DESCRIPTION),
+                TrustLevel::Community,
+                &syntect_adapter(),
+            )
+        },
+    );
+    let adapter = syntect_adapter();
+    bench("shared highlighter", 200, || {
+        render_with(
+            black_box(DESCRIPTION),
+            TrustLevel::Community,
+            &adapter,
+        )
+    });
+    bench("render cache", 10_000, || {
+        compile(
+            black_box(DESCRIPTION),
+            TrustLevel::Community,
+        )
+    });
+}
@@ -0,0 +1,17 @@
+BEGIN;
+
+ALTER TABLE
+    issue
+ADD
+    published_at TIMESTAMP NULL
+AFTER
+    status;
+
+ALTER TABLE
+    issue
+ADD
+    published_by VARCHAR(50)
+AFTER
+    published_at;
+
+COMMIT;
@@ -0,0 +1,17 @@
+BEGIN;
+
+ALTER TABLE
+    issue
+ADD
+    publish_at TIMESTAMP NULL
+AFTER
+    status;
+
+ALTER TABLE
+    issue
+ADD
+    publish_scheduled_by VARCHAR(50)
+AFTER
+    publish_at;
+
+COMMIT;
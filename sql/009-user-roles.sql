@@ -0,0 +1,18 @@
+BEGIN;
+
+ALTER TABLE
+    github_users
+ADD
+    role VARCHAR(50) NOT NULL DEFAULT 'contributor'
+AFTER
+    username;
+
+-- the admin check used to be a hardcoded username
+UPDATE
+    github_users
+SET
+    role = 'admin'
+WHERE
+    username = 'ChristopherBiscardi';
+
+COMMIT;
@@ -0,0 +1,34 @@
+BEGIN;
+
+-- pending, approved, rejected
+-- rows created from the admin panel are approved
+-- community submissions start out pending
+ALTER TABLE
+    showcase
+ADD
+    review_status VARCHAR(50) NOT NULL DEFAULT 'approved'
+AFTER
+    submitted_by;
+
+ALTER TABLE
+    crate_release
+ADD
+    review_status VARCHAR(50) NOT NULL DEFAULT 'approved'
+AFTER
+    submitted_by;
+
+ALTER TABLE
+    devlog
+ADD
+    review_status VARCHAR(50) NOT NULL DEFAULT 'approved'
+AFTER
+    submitted_by;
+
+ALTER TABLE
+    educational
+ADD
+    review_status VARCHAR(50) NOT NULL DEFAULT 'approved'
+AFTER
+    submitted_by;
+
+COMMIT;
@@ -0,0 +1,16 @@
+BEGIN;
+
+-- used by /search
+CREATE FULLTEXT INDEX issue_search ON issue (display_name, description);
+
+CREATE FULLTEXT INDEX showcase_search ON showcase (title, description);
+
+CREATE FULLTEXT INDEX crate_release_search ON crate_release (title, description);
+
+CREATE FULLTEXT INDEX devlog_search ON devlog (title, description);
+
+CREATE FULLTEXT INDEX educational_search ON educational (title, description);
+
+CREATE FULLTEXT INDEX merged_pull_request_search ON merged_pull_request (title);
+
+COMMIT;
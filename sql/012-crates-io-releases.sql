@@ -0,0 +1,19 @@
+BEGIN;
+
+-- set for rows imported from crates.io, so
+-- re-running an import over the same dates
+-- doesn't create the same release twice
+ALTER TABLE
+    crate_release
+ADD
+    crate_name VARCHAR(100)
+AFTER
+    review_status,
+ADD
+    crate_version VARCHAR(100)
+AFTER
+    crate_name,
+ADD
+    UNIQUE (crate_name, crate_version);
+
+COMMIT;
@@ -0,0 +1,21 @@
+BEGIN;
+
+-- the primary key only allowed one contributor
+-- per issue
+ALTER TABLE
+    issue__contributors
+CHANGE
+    contributor github_username VARCHAR(100) NOT NULL,
+ADD
+    display_name VARCHAR(100) NOT NULL DEFAULT ''
+AFTER
+    github_username,
+ADD
+    role VARCHAR(50) NOT NULL DEFAULT 'writer'
+AFTER
+    display_name,
+DROP PRIMARY KEY,
+ADD
+    PRIMARY KEY (issue_id, github_username);
+
+COMMIT;
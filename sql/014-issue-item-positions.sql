@@ -0,0 +1,111 @@
+BEGIN;
+
+-- the order items are shown in on the issue page.
+-- JSON_ARRAYAGG doesn't keep any order, so this is
+-- the only thing the page can sort by.
+ALTER TABLE
+    issue__showcase
+ADD
+    position INT UNSIGNED NOT NULL DEFAULT 0
+AFTER
+    showcase_id;
+
+ALTER TABLE
+    issue__crate_release
+ADD
+    position INT UNSIGNED NOT NULL DEFAULT 0
+AFTER
+    crate_release_id;
+
+ALTER TABLE
+    issue__devlog
+ADD
+    position INT UNSIGNED NOT NULL DEFAULT 0
+AFTER
+    devlog_id;
+
+ALTER TABLE
+    issue__educational
+ADD
+    position INT UNSIGNED NOT NULL DEFAULT 0
+AFTER
+    educational_id;
+
+-- number the existing items in the order they
+-- were added. crate releases were shown newest
+-- first, so they keep that order.
+UPDATE
+    issue__showcase
+    INNER JOIN (
+        SELECT
+            issue_id,
+            showcase_id,
+            ROW_NUMBER() OVER (
+                PARTITION BY issue_id
+                ORDER BY
+                    created_at,
+                    showcase_id
+            ) AS row_num
+        FROM
+            issue__showcase
+    ) AS ordered USING (issue_id, showcase_id)
+SET
+    issue__showcase.position = ordered.row_num;
+
+UPDATE
+    issue__crate_release
+    INNER JOIN (
+        SELECT
+            issue__crate_release.issue_id,
+            issue__crate_release.crate_release_id,
+            ROW_NUMBER() OVER (
+                PARTITION BY issue__crate_release.issue_id
+                ORDER BY
+                    crate_release.posted_date DESC,
+                    issue__crate_release.created_at,
+                    issue__crate_release.crate_release_id
+            ) AS row_num
+        FROM
+            issue__crate_release
+            INNER JOIN crate_release ON crate_release.id = issue__crate_release.crate_release_id
+    ) AS ordered USING (issue_id, crate_release_id)
+SET
+    issue__crate_release.position = ordered.row_num;
+
+UPDATE
+    issue__devlog
+    INNER JOIN (
+        SELECT
+            issue_id,
+            devlog_id,
+            ROW_NUMBER() OVER (
+                PARTITION BY issue_id
+                ORDER BY
+                    created_at,
+                    devlog_id
+            ) AS row_num
+        FROM
+            issue__devlog
+    ) AS ordered USING (issue_id, devlog_id)
+SET
+    issue__devlog.position = ordered.row_num;
+
+UPDATE
+    issue__educational
+    INNER JOIN (
+        SELECT
+            issue_id,
+            educational_id,
+            ROW_NUMBER() OVER (
+                PARTITION BY issue_id
+                ORDER BY
+                    created_at,
+                    educational_id
+            ) AS row_num
+        FROM
+            issue__educational
+    ) AS ordered USING (issue_id, educational_id)
+SET
+    issue__educational.position = ordered.row_num;
+
+COMMIT;
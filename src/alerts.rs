@@ -0,0 +1,220 @@
+//! GitHub's alert syntax, rendered as the site's
+//! callouts.
+//!
+//! ```markdown
+//! > [!WARNING]
+//! > `Query::single` panics now
+//! ```
+//!
+//! The rest of the quote becomes the callout's
+//! contents. Quotes that don't start with one of
+//! the markers on a line of its own stay quotes.
+use crate::app::components::CalloutType;
+use comrak::{
+    nodes::{AstNode, NodeHtmlBlock, NodeValue},
+    Arena,
+};
+
+/// The style and title for a marker like
+/// `[!NOTE]`. GitHub ignores the case.
+fn alert(
+    marker: &str,
+) -> Option<(CalloutType, &'static str)> {
+    let kind = marker
+        .trim()
+        .strip_prefix("[!")?
+        .strip_suffix(']')?
+        .to_ascii_uppercase();
+    match kind.as_str() {
+        "NOTE" => Some((CalloutType::Info, "Note")),
+        "TIP" => Some((CalloutType::Success, "Tip")),
+        "WARNING" => {
+            Some((CalloutType::Warning, "Warning"))
+        }
+        "CAUTION" => {
+            Some((CalloutType::Caution, "Caution"))
+        }
+        _ => None,
+    }
+}
+
+/// Closes what [`callout_open`] opened
+const CALLOUT_CLOSE: &str = "</div></div></div></div>";
+
+/// The html that goes before the contents of a
+/// callout. It's the markup `CalloutInfo` renders
+/// without a link, minus the hydration markers.
+fn callout_open(kind: &CalloutType, title: &str) -> String {
+    format!(
+        r#"<div class="rounded-md p-4 {bg}"><div class="flex"><div class="flex-shrink-0"><svg class="h-5 w-5 {icon}" viewBox="0 0 20 20" fill="currentColor" aria-hidden="true"><path fill-rule="evenodd" d="{path}" clip-rule="evenodd"></path></svg></div><div class="ml-3"><h3 class="text-sm font-medium {title_class}">{title}</h3><div class="mt-2 text-sm {content} {links}">"#,
+        bg = kind.bg(),
+        icon = kind.icon(),
+        path = kind.icon_path(),
+        title_class = kind.title(),
+        content = kind.content(),
+        links = kind.child_links(),
+    )
+}
+
+fn html_block<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    literal: String,
+) -> &'a AstNode<'a> {
+    arena.alloc(
+        NodeValue::HtmlBlock(NodeHtmlBlock {
+            block_type: 6,
+            literal,
+        })
+        .into(),
+    )
+}
+
+/// The marker's text nodes and the line break
+/// after them, if `quote` starts with a marker
+fn marker<'a>(
+    quote: &'a AstNode<'a>,
+) -> Option<(
+    CalloutType,
+    &'static str,
+    Vec<&'a AstNode<'a>>,
+)> {
+    let paragraph = quote.first_child()?;
+    if !matches!(
+        paragraph.data.borrow().value,
+        NodeValue::Paragraph
+    ) {
+        return None;
+    }
+    // the parser splits `[!NOTE]` into several
+    // text nodes, since it could start a link
+    let mut text = String::new();
+    let mut nodes = vec![];
+    for node in paragraph.children() {
+        match &node.data.borrow().value {
+            NodeValue::Text(more) => text.push_str(more),
+            NodeValue::SoftBreak | NodeValue::LineBreak => {
+                nodes.push(node);
+                break;
+            }
+            _ => return None,
+        }
+        nodes.push(node);
+    }
+    let (kind, title) = alert(&text)?;
+    Some((kind, title, nodes))
+}
+
+/// Replaces every alert under `root` with a
+/// callout
+pub fn expand<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    root: &'a AstNode<'a>,
+) {
+    let quotes = root
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.data.borrow().value,
+                NodeValue::BlockQuote
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for quote in quotes {
+        let Some((kind, title, marker)) = marker(quote)
+        else {
+            continue;
+        };
+        let paragraph = quote
+            .first_child()
+            .expect("the marker to be in a paragraph");
+        for node in marker {
+            node.detach();
+        }
+        if paragraph.first_child().is_none() {
+            paragraph.detach();
+        }
+
+        quote.insert_before(html_block(
+            arena,
+            callout_open(&kind, title),
+        ));
+        while let Some(child) = quote.first_child() {
+            child.detach();
+            quote.insert_before(child);
+        }
+        quote.insert_before(html_block(
+            arena,
+            CALLOUT_CLOSE.to_string(),
+        ));
+        quote.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comrak::{format_html, parse_document, Options};
+
+    fn render(input: &str) -> String {
+        let mut options = Options::default();
+        options.render.unsafe_ = true;
+        let arena = Arena::new();
+        let root = parse_document(&arena, input, &options);
+        expand(&arena, root);
+        let mut html = vec![];
+        format_html(root, &options, &mut html).unwrap();
+        String::from_utf8(html).unwrap()
+    }
+
+    #[test]
+    fn markers() {
+        assert!(matches!(
+            alert("[!NOTE]"),
+            Some((CalloutType::Info, "Note"))
+        ));
+        assert!(matches!(
+            alert("[!caution] "),
+            Some((CalloutType::Caution, "Caution"))
+        ));
+        assert!(matches!(
+            alert("[!WARNING]"),
+            Some((CalloutType::Warning, "Warning"))
+        ));
+        assert!(alert("[!IMPORTANT]").is_none());
+        assert!(alert("[NOTE]").is_none());
+    }
+
+    #[test]
+    fn alerts_become_callouts() {
+        let open =
+            callout_open(&CalloutType::Warning, "Warning");
+        assert!(open.contains("bg-red-50"), "{open}");
+        assert!(open.contains(">Warning</h3>"), "{open}");
+        assert!(!open.contains("<!"), "{open}");
+
+        assert_eq!(
+            render(
+                "> [!WARNING]\n> `Query::single` panics now\n>\n> Use `get_single`\n"
+            ),
+            format!(
+                "{open}\n<p><code>Query::single</code> panics now</p>\n<p>Use <code>get_single</code></p>\n{CALLOUT_CLOSE}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn other_quotes_stay_quotes() {
+        for input in [
+            "> Just a quote\n",
+            "> [!NOTE] on the same line\n",
+            "> [!NOTE](https://example.com)\n",
+        ] {
+            let html = render(input);
+            assert!(
+                html.starts_with("<blockquote>"),
+                "{input} became {html}"
+            );
+        }
+    }
+}
@@ -3,19 +3,19 @@ use crate::{
         components::{AboutSection, TinyWaveFormIcon},
         routes::{
             admin::{self, AdminWrapper},
-            custom,
+            archive, custom,
             index::Home,
-            issue,
+            issue, search, submit,
         },
     },
     error_template::{AppError, ErrorTemplate},
-    Username,
+    roles::{has_admin_access, Role},
 };
 use leptos::{either::Either, logging::log, prelude::*};
 use leptos_meta::*;
 use leptos_router::{components::*, path};
-mod components;
-mod routes;
+pub mod components;
+pub(crate) mod routes;
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     // in --release, these must be provided
@@ -114,14 +114,18 @@ pub fn App() -> impl IntoView {
                 }.into_view() }>
                     <Route path=path!("") view=Home/>
                     <Route path=path!("/issue/:slug") view=issue::Issue/>
+                    <Route path=path!("/preview/:slug") view=issue::Preview/>
                     <Route path=path!("/custom/:slug") view=custom::Issue/>
                     <Route path=path!("/login") view=Login/>
+                    <Route path=path!("/archive/:year") view=archive::Archive/>
+                    <Route path=path!("/search") view=search::Search/>
+                    <Route path=path!("/submit") view=submit::Submit/>
                     <ProtectedParentRoute
                         path=path!("/admin")
                         redirect_path=|| "/login"
                         condition=move || {
-                            let logged_in_user = use_context::<Option<Username>>().flatten();
-                            Some(logged_in_user == Some(Username("ChristopherBiscardi".to_string())))
+                            let role = use_context::<Option<Role>>().flatten();
+                            Some(has_admin_access(role))
                         }
                         view=AdminWrapper
                     >
@@ -141,6 +145,8 @@ pub fn App() -> impl IntoView {
                         <Route path=path!("/educational/:id") view=admin::educational::id::Educational/>
                         <Route path=path!("/images") view=admin::image::Image/>
                         <Route path=path!("/github") view=admin::github::GitHub/>
+                        <Route path=path!("/submissions") view=admin::submissions::Submissions/>
+                        <Route path=path!("/users") view=admin::users::Users/>
                     </ProtectedParentRoute>
                 </Routes>
             </Wrapper>
@@ -292,6 +298,12 @@ fn Wrapper(children: Children) -> impl IntoView {
                         <p class="mt-3 text-lg font-medium leading-8 text-ctp-text">
                             What happened this week in the Bevy Engine ecosystem
                         </p>
+                        <p class="mt-3 text-sm font-medium leading-7 text-ctp-pink">
+                            <a href="/search">"Search past issues →"</a>
+                        </p>
+                        <p class="text-sm font-medium leading-7 text-ctp-pink">
+                            <a href="/submit">"Submit your project →"</a>
+                        </p>
                     </div>
                     <AboutSection class="mt-12 hidden lg:block"/>
                     <section class="mt-10 lg:mt-12">
@@ -330,7 +342,7 @@ fn Wrapper(children: Children) -> impl IntoView {
                             </li>
                             <li class="flex">
                                 <a
-                                    href="https://thisweekinbevy.com/feed.xml"
+                                    href="/feed.xml"
                                     class="group flex items-center"
                                     aria-label="Atom Feed"
                                 >
@@ -338,6 +350,16 @@ fn Wrapper(children: Children) -> impl IntoView {
                                     <span class="hidden sm:ml-3 sm:block">Atom Feed</span>
                                 </a>
                             </li>
+                            <li class="flex">
+                                <a
+                                    href="/feed.json"
+                                    class="group flex items-center"
+                                    aria-label="JSON Feed"
+                                >
+                                    <RSSIcon class="h-8 w-8 fill-white group-hover:fill-white"/>
+                                    <span class="hidden sm:ml-3 sm:block">JSON Feed</span>
+                                </a>
+                            </li>
                         </ul>
                     </section>
                 </div>
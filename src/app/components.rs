@@ -1,4 +1,5 @@
 use leptos::prelude::*;
+use std::ops::Not;
 
 #[component]
 pub fn AboutSection(
@@ -157,3 +158,163 @@ pub fn DividerWithDescription(
         </div>
     }
 }
+
+pub enum CalloutType {
+    Info,
+    Caution,
+    Warning,
+    Success,
+}
+impl CalloutType {
+    pub(crate) fn bg(&self) -> &str {
+        match self {
+            CalloutType::Info => {
+                "bg-blue-50 dark:bg-blue-950"
+            }
+            CalloutType::Caution => {
+                "bg-yellow-50 dark:bg-yellow-950"
+            }
+            CalloutType::Warning => {
+                "bg-red-50 dark:bg-red-950"
+            }
+            CalloutType::Success => {
+                "bg-green-50 dark:bg-green-950"
+            }
+        }
+    }
+    pub(crate) fn icon(&self) -> &str {
+        match self {
+            CalloutType::Info => "text-ctp-blue",
+            CalloutType::Caution => "text-ctp-yellow",
+            CalloutType::Warning => "text-ctp-red",
+            CalloutType::Success => "text-ctp-green",
+        }
+    }
+    /// The `d` of the icon's path, in a 20 by 20
+    /// viewbox
+    pub(crate) fn icon_path(&self) -> &'static str {
+        match self {
+            CalloutType::Info => "M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-7-4a1 1 0 11-2 0 1 1 0 012 0zM9 9a.75.75 0 000 1.5h.253a.25.25 0 01.244.304l-.459 2.066A1.75 1.75 0 0010.747 15H11a.75.75 0 000-1.5h-.253a.25.25 0 01-.244-.304l.459-2.066A1.75 1.75 0 009.253 9H9z",
+            CalloutType::Caution => "M8.485 2.495c.673-1.167 2.357-1.167 3.03 0l6.28 10.875c.673 1.167-.17 2.625-1.516 2.625H3.72c-1.347 0-2.189-1.458-1.515-2.625L8.485 2.495zM10 5a.75.75 0 01.75.75v3.5a.75.75 0 01-1.5 0v-3.5A.75.75 0 0110 5zm0 9a1 1 0 100-2 1 1 0 000 2z",
+            CalloutType::Warning => "M10 18a8 8 0 100-16 8 8 0 000 16zM8.28 7.22a.75.75 0 00-1.06 1.06L8.94 10l-1.72 1.72a.75.75 0 101.06 1.06L10 11.06l1.72 1.72a.75.75 0 101.06-1.06L11.06 10l1.72-1.72a.75.75 0 00-1.06-1.06L10 8.94 8.28 7.22z",
+            CalloutType::Success => "M10 18a8 8 0 100-16 8 8 0 000 16zm3.857-9.809a.75.75 0 00-1.214-.882l-3.483 4.79-1.88-1.88a.75.75 0 10-1.06 1.061l2.5 2.5a.75.75 0 001.137-.089l4-5.5z",
+        }
+    }
+    fn icon_svg(&self) -> impl IntoView {
+        view! {
+            <svg
+                class=format!("h-5 w-5 {}", self.icon())
+                viewBox="0 0 20 20"
+                fill="currentColor"
+                aria-hidden="true"
+            >
+                <path
+                    fill-rule="evenodd"
+                    d=self.icon_path()
+                    clip-rule="evenodd"
+                ></path>
+            </svg>
+        }
+    }
+    pub(crate) fn title(&self) -> &str {
+        match self {
+            CalloutType::Info => "text-ctp-blue",
+            CalloutType::Caution => "text-ctp-yellow",
+            CalloutType::Warning => "text-ctp-red",
+            CalloutType::Success => "text-ctp-green",
+        }
+    }
+    pub(crate) fn content(&self) -> &str {
+        match self {
+            CalloutType::Info => "text-ctp-blue",
+            CalloutType::Caution => "text-ctp-yellow",
+            CalloutType::Warning => "text-ctp-red",
+            CalloutType::Success => "text-ctp-green",
+        }
+    }
+    pub(crate) fn child_links(&self) -> &str {
+        match self {
+            CalloutType::Info => "[&_a]:font-medium [&_a]:text-blue-700 [&_a]:underline [&_a]:hover:text-blue-600",
+            CalloutType::Caution => "[&_a]:font-medium [&_a]:text-yellow-700 [&_a]:underline [&_a]:hover:text-yellow-600",
+            CalloutType::Warning => "[&_a]:font-medium [&_a]:text-red-700 [&_a]:underline [&_a]:hover:text-red-600",
+            CalloutType::Success => "[&_a]:font-medium [&_a]:text-green-700 [&_a]:underline [&_a]:hover:text-green-600",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CalloutLink {
+    pub href: String,
+    pub label: String,
+}
+
+#[component]
+pub fn CalloutInfo(
+    r#type: CalloutType,
+    #[prop(into, optional, default = "".to_string())] title: String,
+    #[prop(optional, default = None)] link: Option<
+        CalloutLink,
+    >,
+    children: Children,
+) -> impl IntoView {
+    let has_link = link.is_none();
+    view! {
+        <div class=format!("rounded-md p-4 {}", r#type.bg())>
+            <div class="flex">
+                <div class="flex-shrink-0">
+
+                    {r#type.icon_svg()}
+
+                </div>
+                <div class="ml-3">
+
+                    {title
+                        .trim()
+                        .is_empty()
+                        .not()
+                        .then_some(
+                            view! {
+                                <h3 class=format!(
+                                    "text-sm font-medium {}",
+                                    r#type.title(),
+                                )>{title}</h3>
+                            },
+                        )}
+                    <div class=if has_link {
+                        format!("mt-2 text-sm {} {}", r#type.content(), r#type.child_links())
+                    } else {
+                        format!(
+                            "ml-3 flex-1 md:flex md:justify-between {} {}",
+                            r#type.content(),
+                            r#type.child_links(),
+                        )
+                    }>
+
+                        {children()}
+                        {link
+                            .map(|CalloutLink { href, label }| {
+                                view! {
+                                    <p class="mt-3 text-sm md:ml-6 md:mt-0">
+                                        <a
+                                            href=href
+                                            class="whitespace-nowrap font-medium text-blue-700 hover:text-blue-600"
+                                        >
+                                            {label}
+                                            <span aria-hidden="true">" →"</span>
+                                        </a>
+                                    </p>
+                                }
+                            })}
+
+                    </div>
+                // <div class="mt-4">
+                // <div class="-mx-2 -my-1.5 flex">
+                // <button type="button" class="rounded-md bg-green-50 px-2 py-1.5 text-sm font-medium text-green-800 hover:bg-green-100 focus:outline-none focus:ring-2 focus:ring-green-600 focus:ring-offset-2 focus:ring-offset-green-50">View status</button>
+                // <button type="button" class="ml-3 rounded-md bg-green-50 px-2 py-1.5 text-sm font-medium text-green-800 hover:bg-green-100 focus:outline-none focus:ring-2 focus:ring-green-600 focus:ring-offset-2 focus:ring-offset-green-50">Dismiss</button>
+                // </div>
+                // </div>
+                </div>
+            </div>
+        </div>
+    }
+}
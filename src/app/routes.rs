@@ -1,8 +1,11 @@
 use leptos::prelude::*;
 pub mod admin;
+pub mod archive;
 pub mod custom;
 pub mod index;
 pub mod issue;
+pub mod search;
+pub mod submit;
 
 #[component]
 fn PauseIcon(
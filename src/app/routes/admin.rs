@@ -9,6 +9,8 @@ pub mod image;
 pub mod issue;
 pub mod issues;
 pub mod showcase;
+pub mod submissions;
+pub mod users;
 
 #[component]
 pub fn AdminHomepage() -> impl IntoView {
@@ -212,6 +214,16 @@ pub fn AdminWrapper() -> impl IntoView {
                                 GitHub
                             </A>
                         </li>
+                        <li>
+                            <A href="/admin/submissions" attr:class="active:text-blue-600">
+                                Submissions
+                            </A>
+                        </li>
+                        <li>
+                            <A href="/admin/users" attr:class="active:text-blue-600">
+                                Users
+                            </A>
+                        </li>
                     </ul>
                 </nav>
             </header>
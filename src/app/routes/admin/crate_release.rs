@@ -37,14 +37,105 @@ async fn add_crate_release(
     Ok(())
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ImportCounts {
+    pub found: usize,
+    pub imported: usize,
+}
+
+/// The crates.io import running in the
+/// background
+#[derive(Deserialize, Serialize, Clone)]
+pub enum ImportStatus {
+    Idle,
+    Running(String),
+    Finished(ImportCounts),
+    Failed(String),
+}
+
+#[cfg(feature = "ssr")]
+impl From<crate::crates_io_ingest::ImportStatus>
+    for ImportStatus
+{
+    fn from(
+        value: crate::crates_io_ingest::ImportStatus,
+    ) -> Self {
+        use crate::crates_io_ingest::ImportStatus as Status;
+        match value {
+            Status::Idle => ImportStatus::Idle,
+            Status::Running(progress) => {
+                ImportStatus::Running(progress.to_string())
+            }
+            Status::Finished(counts) => {
+                ImportStatus::Finished(ImportCounts {
+                    found: counts.found,
+                    imported: counts.imported,
+                })
+            }
+            Status::Failed(e) => ImportStatus::Failed(e),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn background_import() -> Result<
+    crate::crates_io_ingest::BackgroundImport,
+    ServerFnError,
+> {
+    use_context().ok_or_else(|| {
+        ServerFnError::ServerError(
+            "BackgroundImport missing.".into(),
+        )
+    })
+}
+
+/// Starts the import and returns straight away.
+/// crates.io is throttled to a request a second,
+/// so an import can take minutes.
+#[server]
+pub async fn import_from_crates_io(
+    start_date: time::Date,
+    end_date: time::Date,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let client =
+        crate::crates_io_ingest::CratesIoClient::new(
+            crate::sql::site_config().crates_io_api_url,
+        );
+    if !background_import()?
+        .start(pool, client, start_date, end_date)
+    {
+        return Err(ServerFnError::ServerError(
+            "an import is already running".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[server]
+pub async fn fetch_import_status(
+) -> Result<ImportStatus, ServerFnError> {
+    let _username = crate::sql::with_admin_access()?;
+    Ok(background_import()?.status().into())
+}
+
 #[component]
 pub fn CrateRelease() -> impl IntoView {
     let add_crate_release: ServerAction<AddCrateRelease> =
         ServerAction::new();
+    let import_from_crates_io: ServerAction<
+        ImportFromCratesIo,
+    > = ServerAction::new();
     let crate_releases = Resource::new(
         move || {},
         |_| join(fetch_crate_releases(), fetch_issues()),
     );
+    let import_status = Resource::new(
+        move || import_from_crates_io.version().get(),
+        |_| fetch_import_status(),
+    );
 
     view! {
         <div class="mx-auto max-w-7xl sm:px-6 lg:px-8">
@@ -118,6 +209,68 @@ pub fn CrateRelease() -> impl IntoView {
                     Add CrateRelease
                 </button>
             </ActionForm>
+            <Divider title="import from crates.io"/>
+            <ActionForm
+                action=import_from_crates_io
+                attr:class="isolate -space-y-px rounded-md shadow-sm"
+            >
+                <p class="text-sm text-gray-500">
+                    Imports releases of crates with the bevy keyword or a bevy dependency published in the date range. They show up in the submissions queue for review.
+                </p>
+                <label
+                    for="start_date"
+                    class="block text-sm font-medium leading-6 text-gray-900"
+                >
+                    Start Date
+                </label>
+                <div class="mt-2">
+                    <input type="date" required id="start_date" name="start_date" min="2024-01-01"/>
+                </div>
+                <label
+                    for="end_date"
+                    class="block text-sm font-medium leading-6 text-gray-900"
+                >
+                    End Date
+                </label>
+                <div class="mt-2">
+                    <input type="date" required id="end_date" name="end_date" min="2024-01-01"/>
+                </div>
+                <button type="submit">Import</button>
+                {move || {
+                    import_from_crates_io
+                        .value()
+                        .get()
+                        .and_then(|result| result.err())
+                        .map(|e| e.to_string())
+                }}
+                <Suspense fallback=move || ()>
+                    {move || {
+                        import_status
+                            .get()
+                            .map(|status| match status {
+                                Ok(ImportStatus::Idle) => String::new(),
+                                Ok(ImportStatus::Running(progress)) => {
+                                    format!(
+                                        "Importing: {progress}. Reload to see how it's going.",
+                                    )
+                                }
+                                Ok(ImportStatus::Finished(counts)) => {
+                                    format!(
+                                        "{} releases found, {} new",
+                                        counts.found,
+                                        counts.imported,
+                                    )
+                                }
+                                Ok(ImportStatus::Failed(e)) => {
+                                    format!("The last import failed: {e}")
+                                }
+                                Err(e) => e.to_string(),
+                            })
+                    }}
+
+                </Suspense>
+
+            </ActionForm>
             <Divider title="CrateReleases without an issue"/>
             <Suspense fallback=move || {
                 view! { <p>"Loading (Suspense Fallback)..."</p> }
@@ -272,6 +425,7 @@ FROM crate_release
 LEFT JOIN issue__crate_release
   ON crate_release.id = issue__crate_release.crate_release_id
 WHERE issue__crate_release.issue_id IS NULL
+AND crate_release.review_status = 'approved'
 ORDER BY crate_release.id"
     )
     .fetch_all(&pool)
@@ -301,17 +455,36 @@ async fn associate_crate_release_with_issue(
         .expect("a valid ulid to be returned from the form")
         .into();
 
-    sqlx::query!(
+    let mut tx = pool.begin().await?;
+    crate::issue_items::lock_issue(&mut tx, &issue_id)
+        .await?;
+
+    // pending and rejected submissions can't be
+    // added to an issue. new items go at the end.
+    let result = sqlx::query!(
         r#"
-    INSERT INTO issue__crate_release ( issue_id, crate_release_id )
-    VALUES ( ?, ? )
+    INSERT INTO issue__crate_release ( issue_id, crate_release_id, position )
+    SELECT ?, id, (
+        SELECT COALESCE(MAX(position), 0) + 1
+        FROM issue__crate_release
+        WHERE issue_id = ?
+    ) FROM crate_release
+    WHERE id = ?
+    AND review_status = 'approved'
         "#,
         issue_id.as_slice(),
+        issue_id.as_slice(),
         crate_release_id.as_slice()
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
     .expect("successful insert");
+    tx.commit().await?;
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::ServerError(
+            "crate release is not approved".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -279,6 +279,7 @@ FROM devlog
 LEFT JOIN issue__devlog
   ON devlog.id = issue__devlog.devlog_id
 WHERE issue__devlog.issue_id IS NULL
+AND devlog.review_status = 'approved'
 ORDER BY devlog.id"
     )
     .fetch_all(&pool)
@@ -305,17 +306,36 @@ async fn associate_devlog_with_issue(
         .expect("a valid ulid to be returned from the form")
         .into();
 
-    sqlx::query!(
+    let mut tx = pool.begin().await?;
+    crate::issue_items::lock_issue(&mut tx, &issue_id)
+        .await?;
+
+    // pending and rejected submissions can't be
+    // added to an issue. new items go at the end.
+    let result = sqlx::query!(
         r#"
-    INSERT INTO issue__devlog ( issue_id, devlog_id )
-    VALUES ( ?, ? )
+    INSERT INTO issue__devlog ( issue_id, devlog_id, position )
+    SELECT ?, id, (
+        SELECT COALESCE(MAX(position), 0) + 1
+        FROM issue__devlog
+        WHERE issue_id = ?
+    ) FROM devlog
+    WHERE id = ?
+    AND review_status = 'approved'
         "#,
         issue_id.as_slice(),
+        issue_id.as_slice(),
         devlog_id.as_slice()
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
     .expect("successful insert");
+    tx.commit().await?;
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::ServerError(
+            "devlog is not approved".to_string(),
+        ));
+    }
     Ok(())
 }
 
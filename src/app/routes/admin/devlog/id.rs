@@ -282,6 +282,8 @@ impl From<SqlDevlogData> for DevlogData {
                 .expect(
                     "expect valid ids from the database",
                 );
+        let backend =
+            crate::sql::site_config().image_backend();
         let images =
             serde_json::from_value::<Vec<ImgData>>(
                 value.images,
@@ -292,12 +294,6 @@ impl From<SqlDevlogData> for DevlogData {
             .unwrap_or_default()
             .into_iter()
             .map(|img_data| {
-                use cloudinary::transformation::{
-                    resize_mode::ResizeMode::ScaleByWidth,
-                    Image as CImage,
-                    Transformations::Resize,
-                };
-
                 let base_id = BASE64
                     .decode(img_data.id.as_bytes())
                     .expect("a valid id in base64 format");
@@ -307,18 +303,14 @@ impl From<SqlDevlogData> for DevlogData {
                 .expect(
                     "expect valid ids from the database",
                 );
-                let image = CImage::new(
-                    "dilgcuzda".into(),
-                    img_data.cloudinary_public_id.into(),
-                )
-                .add_transformation(Resize(ScaleByWidth {
-                    width: 300,
-                    ar: None,
-                    liquid: None,
-                }));
+                let url = backend.url(
+                    &img_data.cloudinary_public_id,
+                    Some(300),
+                    None,
+                );
                 ImgDataTransformed {
                     id: img_ulid.to_string(),
-                    url: image.to_string(),
+                    url,
                 }
             })
             .collect();
@@ -621,22 +613,16 @@ pub struct Image {
 }
 
 #[cfg(feature = "ssr")]
-impl From<SqlImage> for Image {
-    fn from(value: SqlImage) -> Self {
-        use cloudinary::transformation::{
-            resize_mode::ResizeMode::ScaleByWidth,
-            Image as CImage, Transformations::Resize,
-        };
-
-        let image = CImage::new(
-            "dilgcuzda".into(),
-            value.cloudinary_public_id.into(),
-        )
-        .add_transformation(Resize(ScaleByWidth {
-            width: 300,
-            ar: None,
-            liquid: None,
-        }));
+impl Image {
+    fn new(
+        value: SqlImage,
+        backend: &dyn crate::images::ImageBackend,
+    ) -> Self {
+        let url = backend.url(
+            &value.cloudinary_public_id,
+            Some(300),
+            None,
+        );
 
         let id_str =
             rusty_ulid::Ulid::try_from(value.id.as_slice())
@@ -646,7 +632,7 @@ impl From<SqlImage> for Image {
         Image {
             id: id_str.to_string(),
             description: value.description,
-            url: image.to_string(),
+            url,
         }
     }
 }
@@ -675,5 +661,9 @@ limit 5"#
         )
     })?;
 
-    Ok(images.into_iter().map(Image::from).collect())
+    let backend = crate::sql::site_config().image_backend();
+    Ok(images
+        .into_iter()
+        .map(|image| Image::new(image, backend.as_ref()))
+        .collect())
 }
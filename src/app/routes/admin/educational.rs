@@ -282,6 +282,7 @@ FROM educational
 LEFT JOIN issue__educational
   ON educational.id = issue__educational.educational_id
 WHERE issue__educational.issue_id IS NULL
+AND educational.review_status = 'approved'
 ORDER BY educational.id"
         )
         .fetch_all(&pool)
@@ -311,17 +312,36 @@ async fn associate_educational_with_issue(
         .expect("a valid ulid to be returned from the form")
         .into();
 
-    sqlx::query!(
+    let mut tx = pool.begin().await?;
+    crate::issue_items::lock_issue(&mut tx, &issue_id)
+        .await?;
+
+    // pending and rejected submissions can't be
+    // added to an issue. new items go at the end.
+    let result = sqlx::query!(
         r#"
-    INSERT INTO issue__educational ( issue_id, educational_id )
-    VALUES ( ?, ? )
+    INSERT INTO issue__educational ( issue_id, educational_id, position )
+    SELECT ?, id, (
+        SELECT COALESCE(MAX(position), 0) + 1
+        FROM issue__educational
+        WHERE issue_id = ?
+    ) FROM educational
+    WHERE id = ?
+    AND review_status = 'approved'
         "#,
         issue_id.as_slice(),
+        issue_id.as_slice(),
         educational_id.as_slice()
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
     .expect("successful insert");
+    tx.commit().await?;
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::ServerError(
+            "educational is not approved".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -14,6 +14,8 @@ pub fn GitHub() -> impl IntoView {
     let select_merged_pull_requests: ServerAction<
         SelectMergedPullRequests,
     > = ServerAction::new();
+    let ingest_from_github: ServerAction<IngestFromGithub> =
+        ServerAction::new();
     let issues =
         Resource::new(move || {}, |_| fetch_issues());
     view! {
@@ -28,6 +30,63 @@ pub fn GitHub() -> impl IntoView {
                     let issues_b = issues.clone();
                     EitherOf3::C(view! {
                         <div class="mx-auto max-w-7xl sm:px-6 lg:px-8">
+                            <Divider title="ingest from github"/>
+
+                            <ActionForm
+                                action=ingest_from_github
+                                attr:class="isolate -space-y-px rounded-md shadow-sm"
+                            >
+                                <p class="text-sm text-gray-500">
+                                    Fetches merged pull requests, new pull requests and new issues for bevyengine/bevy in the date range
+                                </p>
+                                <label
+                                    for="start_date"
+                                    class="block text-sm font-medium leading-6 text-gray-900"
+                                >
+                                    Start Date
+                                </label>
+                                <div class="mt-2">
+                                    <input
+                                        type="date"
+                                        id="start_date"
+                                        name="start_date"
+                                        min="2024-01-01"
+                                    />
+                                </div>
+                                <label
+                                    for="end_date"
+                                    class="block text-sm font-medium leading-6 text-gray-900"
+                                >
+                                    End Date
+                                </label>
+                                <div class="mt-2">
+                                    <input
+                                        type="date"
+                                        id="end_date"
+                                        name="end_date"
+                                        min="2024-01-01"
+                                    />
+                                </div>
+                                <button type="submit">Ingest</button>
+                                {move || {
+                                    ingest_from_github
+                                        .value()
+                                        .get()
+                                        .map(|result| match result {
+                                            Ok(counts) => {
+                                                format!(
+                                                    "{} merged pull requests, {} new pull requests, {} new issues",
+                                                    counts.merged_pull_requests,
+                                                    counts.new_pull_requests,
+                                                    counts.new_github_issues,
+                                                )
+                                            }
+                                            Err(e) => e.to_string(),
+                                        })
+                                }}
+
+                            </ActionForm>
+
                             <Divider title="new github issues"/>
 
                             <ActionForm
@@ -265,6 +324,41 @@ ORDER BY issue_date DESC"#
     Ok(issues.into_iter().map(IssueShort::from).collect())
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+pub struct IngestCounts {
+    pub merged_pull_requests: usize,
+    pub new_pull_requests: usize,
+    pub new_github_issues: usize,
+}
+
+#[server]
+pub async fn ingest_from_github(
+    start_date: time::Date,
+    end_date: time::Date,
+) -> Result<IngestCounts, ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let client =
+        crate::github_ingest::GitHubClient::from_env();
+    let counts = crate::github_ingest::ingest(
+        &pool, &client, start_date, end_date,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(?e);
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
+
+    Ok(IngestCounts {
+        merged_pull_requests: counts.merged_pull_requests,
+        new_pull_requests: counts.new_pull_requests,
+        new_github_issues: counts.new_github_issues,
+    })
+}
+
 #[server]
 pub async fn select_new_github_issues(
     issue_id: String,
@@ -4,6 +4,10 @@ use crate::app::server_fn::error::NoCustomError;
 use leptos::{
     either::{Either, EitherOf3},
     prelude::*,
+    server_fn::{
+        codec::{MultipartData, MultipartFormData},
+        ServerFn,
+    },
 };
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
@@ -16,6 +20,9 @@ pub fn Image() -> impl IntoView {
 
     view! {
         <div class="mx-auto max-w-7xl sm:px-6 lg:px-8">
+            <Divider title="upload an image"/>
+            <UploadImageForm/>
+            <Divider title="or add an already uploaded image"/>
             <ActionForm attr:class="isolate -space-y-px rounded-md shadow-sm" action=add_image>
                 <div class="relative rounded-md px-3 pb-1.5 pt-2.5 ring-1 ring-inset ring-gray-300 focus-within:z-10 focus-within:ring-2 focus-within:ring-indigo-600">
                     <label
@@ -69,6 +76,22 @@ async fn add_image(
 ) -> Result<(), ServerFnError> {
     let pool = crate::sql::pool()?;
     let _username = crate::sql::with_admin_access()?;
+
+    insert_image(
+        &pool,
+        &cloudinary_public_id,
+        &description,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn insert_image(
+    pool: &sqlx::MySqlPool,
+    cloudinary_public_id: &str,
+    description: &str,
+) -> Result<rusty_ulid::Ulid, ServerFnError> {
     let id: [u8; 16] = rusty_ulid::generate_ulid_bytes();
 
     sqlx::query!(
@@ -80,13 +103,249 @@ async fn add_image(
         cloudinary_public_id,
         description
     )
-    .execute(&pool)
+    .execute(pool)
     .await
     .map_err(|e| {
         error!(?e);
         ServerFnError::<NoCustomError>::ServerError("sql failed".to_string())
     })?;
-    Ok(())
+    Ok(rusty_ulid::Ulid::from(id))
+}
+
+/// Uploads the `file` field to Cloudinary and
+/// stores it with the `description` field,
+/// returning the new image's id.
+#[server(input = MultipartFormData)]
+async fn upload_image(
+    data: MultipartData,
+) -> Result<String, ServerFnError> {
+    use crate::cloudinary_upload::{
+        CloudinaryUploader, UploadError, MAX_UPLOAD_BYTES,
+    };
+
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+    let upload_error = |e: UploadError| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    };
+    let uploader = CloudinaryUploader::from_env(
+        &crate::sql::site_config(),
+    )
+    .map_err(upload_error)?;
+
+    let mut multipart = data.into_inner().expect(
+        "multipart data is only read on the server",
+    );
+    let mut file = None;
+    let mut description = String::new();
+    while let Some(mut field) =
+        multipart.next_field().await?
+    {
+        match field.name() {
+            Some("file") => {
+                let file_name = field
+                    .file_name()
+                    .unwrap_or("image")
+                    .to_string();
+                let content_type = field
+                    .content_type()
+                    .map(|mime| mime.to_string())
+                    .unwrap_or_default();
+                let mut bytes = vec![];
+                while let Some(chunk) =
+                    field.chunk().await?
+                {
+                    bytes.extend_from_slice(&chunk);
+                    if bytes.len() > MAX_UPLOAD_BYTES {
+                        return Err(upload_error(
+                            UploadError::TooLarge,
+                        ));
+                    }
+                }
+                file =
+                    Some((file_name, content_type, bytes));
+            }
+            Some("description") => {
+                description = field.text().await?;
+            }
+            _ => {}
+        }
+    }
+    let Some((file_name, content_type, bytes)) = file
+    else {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "choose an image to upload".to_string(),
+            ),
+        );
+    };
+
+    let uploaded = uploader
+        .upload(file_name, &content_type, bytes)
+        .await
+        .map_err(|e| {
+            error!(?e);
+            upload_error(e)
+        })?;
+    let id = insert_image(
+        &pool,
+        &uploaded.public_id,
+        &description,
+    )
+    .await?;
+    Ok(id.to_string())
+}
+
+/// Sends the form with `XMLHttpRequest`, since
+/// `fetch` can't report upload progress. Without
+/// JavaScript it's a regular form post.
+#[island]
+fn UploadImageForm() -> impl IntoView {
+    // percent uploaded, while an upload is running
+    let progress = RwSignal::new(None::<u32>);
+    let error = RwSignal::new(None::<String>);
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        #[cfg(feature = "hydrate")]
+        {
+            ev.prevent_default();
+            error.set(None);
+            progress.set(Some(0));
+            if let Err(e) =
+                upload_with_progress(&ev, progress, error)
+            {
+                progress.set(None);
+                error.set(Some(
+                    e.as_string().unwrap_or_else(|| {
+                        "upload failed".to_string()
+                    }),
+                ));
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = ev;
+    };
+
+    view! {
+        <form
+            method="post"
+            enctype="multipart/form-data"
+            action=UploadImage::PATH
+            on:submit=on_submit
+            class="space-y-4"
+        >
+            <input
+                required
+                type="file"
+                name="file"
+                accept="image/*"
+                class="block w-full text-sm text-gray-900"
+            />
+            <label
+                for="upload_description"
+                class="block text-sm font-medium leading-6 text-gray-900"
+            >
+                Add your description (markdown compatible)
+            </label>
+            <textarea
+                required
+                rows="4"
+                name="description"
+                id="upload_description"
+                class="block w-full rounded-md border-0 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 placeholder:text-gray-400 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6"
+            ></textarea>
+            <button
+                type="submit"
+                disabled=move || progress.get().is_some()
+                class="rounded-md bg-indigo-600 px-2.5 py-1.5 text-sm font-semibold text-white shadow-sm hover:bg-indigo-500 disabled:opacity-50"
+            >
+                Upload Image
+            </button>
+            {move || {
+                progress
+                    .get()
+                    .map(|percent| {
+                        view! {
+                            <div class="flex items-center gap-x-2 text-sm text-gray-500">
+                                <progress class="w-full" max="100" value=percent></progress>
+                                <span>{format!("{percent}%")}</span>
+                            </div>
+                        }
+                    })
+            }}
+            {move || error.get().map(|e| view! { <p class="text-sm text-red-600">{e}</p> })}
+        </form>
+    }
+}
+
+#[cfg(feature = "hydrate")]
+fn upload_with_progress(
+    ev: &leptos::ev::SubmitEvent,
+    progress: RwSignal<Option<u32>>,
+    error: RwSignal<Option<String>>,
+) -> Result<(), wasm_bindgen::JsValue> {
+    use leptos::server_fn::error::{
+        NoCustomError, ServerFnErrorSerde,
+    };
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let form: web_sys::HtmlFormElement = ev
+        .target()
+        .expect("submit events have a target")
+        .unchecked_into();
+    let data = web_sys::FormData::new_with_form(&form)?;
+    let xhr = web_sys::XmlHttpRequest::new()?;
+    xhr.open("POST", &form.action())?;
+    // a json response instead of a redirect back
+    // to this page
+    xhr.set_request_header("Accept", "application/json")?;
+
+    let on_progress = Closure::<dyn FnMut(_)>::new(
+        move |e: web_sys::ProgressEvent| {
+            if e.length_computable() && e.total() > 0.0 {
+                progress.set(Some(
+                    (e.loaded() / e.total() * 100.0) as u32,
+                ));
+            }
+        },
+    );
+    let upload = xhr.upload()?;
+    upload.set_onprogress(Some(
+        on_progress.as_ref().unchecked_ref(),
+    ));
+
+    // `loadend` fires exactly once, whether the
+    // upload worked, failed or was aborted, so the
+    // progress handler is freed there instead of
+    // leaking one per upload
+    let request = xhr.clone();
+    let on_loadend = Closure::once_into_js(move || {
+        upload.set_onprogress(None);
+        drop(on_progress);
+        if request.status() == Ok(200) {
+            // the image list is rendered on the
+            // server
+            let _ = window().location().reload();
+            return;
+        }
+        progress.set(None);
+        let message = request
+            .response_text()
+            .ok()
+            .flatten()
+            .filter(|body| !body.is_empty())
+            .map(|body| {
+                ServerFnError::<NoCustomError>::de(&body)
+                    .to_string()
+            })
+            .unwrap_or_else(|| "upload failed".to_string());
+        error.set(Some(message));
+    });
+    xhr.set_onloadend(Some(on_loadend.unchecked_ref()));
+
+    xhr.send_with_opt_form_data(Some(&data))
 }
 
 #[component]
@@ -170,22 +429,16 @@ pub struct Image {
 }
 
 #[cfg(feature = "ssr")]
-impl From<SqlImage> for Image {
-    fn from(value: SqlImage) -> Self {
-        use cloudinary::transformation::{
-            resize_mode::ResizeMode::ScaleByWidth,
-            Image as CImage, Transformations::Resize,
-        };
-
-        let image = CImage::new(
-            "dilgcuzda".into(),
-            value.cloudinary_public_id.into(),
-        )
-        .add_transformation(Resize(ScaleByWidth {
-            width: 300,
-            ar: None,
-            liquid: None,
-        }));
+impl Image {
+    fn new(
+        value: SqlImage,
+        backend: &dyn crate::images::ImageBackend,
+    ) -> Self {
+        let url = backend.url(
+            &value.cloudinary_public_id,
+            Some(300),
+            None,
+        );
 
         let id_str =
             rusty_ulid::Ulid::try_from(value.id.as_slice())
@@ -195,7 +448,7 @@ impl From<SqlImage> for Image {
         Image {
             id: id_str.to_string(),
             description: value.description,
-            url: image.to_string(),
+            url,
         }
     }
 }
@@ -225,5 +478,9 @@ limit 5"#
         )
     })?;
 
-    Ok(images.into_iter().map(Image::from).collect())
+    let backend = crate::sql::site_config().image_backend();
+    Ok(images
+        .into_iter()
+        .map(|image| Image::new(image, backend.as_ref()))
+        .collect())
 }
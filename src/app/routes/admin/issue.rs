@@ -1,8 +1,21 @@
-use crate::app::components::Divider;
+use crate::{
+    app::components::Divider,
+    contributors::{Contributor, ContributorRole},
+};
 use leptos::{either::Either, prelude::*};
 use leptos_router::hooks::use_params_map;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ssr")]
+use crate::app::server_fn::error::NoCustomError;
+#[cfg(feature = "ssr")]
+use crate::contributors::SqlContributor;
+#[cfg(feature = "ssr")]
+use crate::issue_items::Direction;
+#[cfg(feature = "ssr")]
+use crate::submissions::SubmissionKind;
+use leptos::server_fn::ServerFn;
+
 #[component]
 pub fn Issue() -> impl IntoView {
     let params = use_params_map();
@@ -41,6 +54,8 @@ pub fn Issue() -> impl IntoView {
             <Devlogs/>
             <Divider title="Educationals"/>
             <Educationals/>
+            <Divider title="Contributors"/>
+            <Contributors/>
         </div>
     }
 }
@@ -53,6 +68,10 @@ struct SqlIssueData {
     issue_date: time::Date,
     cloudinary_public_id: String,
     status: String,
+    publish_at: Option<time::OffsetDateTime>,
+    publish_scheduled_by: Option<String>,
+    published_at: Option<time::OffsetDateTime>,
+    published_by: Option<String>,
     display_name: String,
     description: String,
     youtube_id: String,
@@ -65,6 +84,10 @@ pub struct IssueData {
     pub issue_date: time::Date,
     pub cloudinary_public_id: String,
     pub status: String,
+    pub publish_at: Option<String>,
+    pub publish_scheduled_by: Option<String>,
+    pub published_at: Option<String>,
+    pub published_by: Option<String>,
     pub display_name: String,
     pub description: String,
     pub youtube_id: String,
@@ -85,6 +108,27 @@ impl From<SqlIssueData> for IssueData {
             cloudinary_public_id: value
                 .cloudinary_public_id,
             status: value.status,
+            publish_at: value.publish_at.and_then(
+                |publish_at| {
+                    publish_at
+                        .format(
+                            crate::issue_date::TIMESTAMP_FORMAT,
+                        )
+                        .ok()
+                },
+            ),
+            publish_scheduled_by: value
+                .publish_scheduled_by,
+            published_at: value.published_at.and_then(
+                |published_at| {
+                    published_at
+                        .format(
+                            crate::issue_date::TIMESTAMP_FORMAT,
+                        )
+                        .ok()
+                },
+            ),
+            published_by: value.published_by,
             display_name: value.display_name,
             description: value.description,
             youtube_id: value.youtube_id,
@@ -112,6 +156,10 @@ pub async fn fetch_issue(
             issue_date,
             cloudinary_public_id,
             status,
+            publish_at,
+            publish_scheduled_by,
+            published_at,
+            published_by,
             display_name,
             description,
             youtube_id
@@ -169,6 +217,294 @@ pub async fn update_issue_metadata(
     Ok(())
 }
 
+/// Publishing makes an issue visible on the
+/// public site. The slug has to start with the
+/// `issue_date`, since that's what the public
+/// routes and the feed key off of.
+#[server]
+pub async fn publish_issue(
+    issue_id: String,
+    confirm: Option<String>,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let username = crate::sql::with_admin_access()?;
+
+    let id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+
+    if confirm.is_none() {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "confirm publishing before submitting"
+                    .to_string(),
+            ),
+        );
+    }
+
+    let issue = sqlx::query!(
+        r#"
+    SELECT slug, issue_date
+    FROM issue
+    WHERE id = ?
+        "#,
+        id.as_slice()
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError(
+            "issue not found".to_string(),
+        )
+    })?;
+
+    let slug_date =
+        crate::issue_date::parse_issue_date_from_slug(
+            &issue.slug,
+        );
+    if slug_date != Some(issue.issue_date) {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            format!(
+                "slug `{}` must start with the issue date {}",
+                issue.slug, issue.issue_date
+            ),
+        ));
+    }
+
+    sqlx::query!(
+        r#"
+    UPDATE issue
+    SET
+        status = 'publish',
+        published_at = NOW(),
+        published_by = ?,
+        publish_at = NULL,
+        publish_scheduled_by = NULL
+    WHERE id = ?
+        "#,
+        username.0,
+        id.as_slice()
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
+
+    Ok(())
+}
+
+#[server]
+pub async fn unpublish_issue(
+    issue_id: String,
+    confirm: Option<String>,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+
+    if confirm.is_none() {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "confirm unpublishing before submitting"
+                    .to_string(),
+            ),
+        );
+    }
+
+    let result = sqlx::query!(
+        r#"
+    UPDATE issue
+    SET
+        status = 'draft',
+        published_at = NULL,
+        published_by = NULL
+    WHERE id = ?
+        "#,
+        id.as_slice()
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "issue not found".to_string(),
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+/// Schedule a draft to be published by the
+/// scheduler task once `publish_at` (UTC) has
+/// passed.
+#[server]
+pub async fn schedule_issue(
+    issue_id: String,
+    publish_at: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let username = crate::sql::with_admin_access()?;
+
+    let id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+
+    let publish_at =
+        crate::issue_date::parse_datetime_local(
+            &publish_at,
+        )
+        .ok_or_else(|| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid publish_at time"
+                    .to_string(),
+            )
+        })?;
+
+    if publish_at <= time::OffsetDateTime::now_utc() {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "publish_at must be in the future"
+                    .to_string(),
+            ),
+        );
+    }
+
+    let issue = sqlx::query!(
+        r#"
+    SELECT slug, issue_date
+    FROM issue
+    WHERE id = ?
+        "#,
+        id.as_slice()
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError(
+            "issue not found".to_string(),
+        )
+    })?;
+
+    let slug_date =
+        crate::issue_date::parse_issue_date_from_slug(
+            &issue.slug,
+        );
+    if slug_date != Some(issue.issue_date) {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            format!(
+                "slug `{}` must start with the issue date {}",
+                issue.slug, issue.issue_date
+            ),
+        ));
+    }
+
+    let result = sqlx::query!(
+        r#"
+    UPDATE issue
+    SET
+        publish_at = ?,
+        publish_scheduled_by = ?
+    WHERE id = ?
+    AND status = 'draft'
+        "#,
+        publish_at,
+        username.0,
+        id.as_slice()
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
+
+    // deleted or published since it was loaded
+    if result.rows_affected() == 0 {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "only draft issues can be scheduled"
+                    .to_string(),
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+#[server]
+pub async fn cancel_scheduled_publish(
+    issue_id: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+
+    let result = sqlx::query!(
+        r#"
+    UPDATE issue
+    SET
+        publish_at = NULL,
+        publish_scheduled_by = NULL
+    WHERE id = ?
+        "#,
+        id.as_slice()
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "issue not found".to_string(),
+            ),
+        );
+    }
+
+    Ok(())
+}
+
 #[component]
 fn IssueForm(issue: IssueData) -> impl IntoView {
     let update_issue_metadata: ServerAction<
@@ -180,8 +516,41 @@ fn IssueForm(issue: IssueData) -> impl IntoView {
                 <h2 class="text-3xl font-bold tracking-tight text-gray-900 sm:text-4xl">
                     {issue.issue_date.to_string()}
                 </h2>
-                <p class="mt-2 text-lg leading-8 text-gray-600">{issue.status}</p>
+                <p class="mt-2 text-lg leading-8 text-gray-600">{issue.status.clone()}</p>
+                {issue
+                    .published_at
+                    .clone()
+                    .map(|published_at| {
+                        view! {
+                            <p class="mt-1 text-sm leading-6 text-gray-500">
+                                "published by "
+                                {issue.published_by.clone().unwrap_or_default()} " at "
+                                {published_at}
+                            </p>
+                        }
+                    })}
+
             </div>
+            <PublishControls issue_id=issue.id.clone() status=issue.status.clone()/>
+            <p class="mx-auto mt-4 max-w-xl text-sm leading-6">
+                <a
+                    href=format!("/preview/{}", issue.slug)
+                    class="font-semibold text-indigo-600 hover:text-indigo-500"
+                >
+                    "Preview and share →"
+                </a>
+            </p>
+            {(issue.status != "publish")
+                .then(|| {
+                    view! {
+                        <ScheduleControls
+                            issue_id=issue.id.clone()
+                            publish_at=issue.publish_at.clone()
+                            publish_scheduled_by=issue.publish_scheduled_by.clone()
+                        />
+                    }
+                })}
+
             <ActionForm attr:class="mx-auto mt-16 max-w-xl sm:mt-20" action=update_issue_metadata>
                 <input type="hidden" name="issue_id" id="issue_id" value=issue.id/>
                 <div class="grid grid-cols-1 gap-x-8 gap-y-6 sm:grid-cols-2">
@@ -286,6 +655,255 @@ fn IssueForm(issue: IssueData) -> impl IntoView {
     }
 }
 
+#[component]
+fn PublishControls(
+    issue_id: String,
+    status: String,
+) -> impl IntoView {
+    let publish_issue: ServerAction<PublishIssue> =
+        ServerAction::new();
+    let unpublish_issue: ServerAction<UnpublishIssue> =
+        ServerAction::new();
+
+    if status == "publish" {
+        Either::Left(view! {
+            <ActionForm
+                attr:class="mx-auto mt-8 flex max-w-xl items-center justify-between gap-x-6 rounded-md bg-yellow-50 px-4 py-3"
+                action=unpublish_issue
+            >
+                <input type="hidden" name="issue_id" value=issue_id/>
+                <label class="flex items-center gap-x-2 text-sm leading-6 text-gray-900">
+                    <input
+                        type="checkbox"
+                        name="confirm"
+                        value="true"
+                        required
+                        class="h-4 w-4 rounded border-gray-300 text-yellow-600 focus:ring-yellow-600"
+                    />
+                    "I want to take this issue off the public site"
+                </label>
+                <button
+                    type="submit"
+                    class="rounded-md bg-yellow-600 px-3 py-2 text-sm font-semibold text-white shadow-sm hover:bg-yellow-500"
+                >
+                    Unpublish
+                </button>
+            </ActionForm>
+        })
+    } else {
+        Either::Right(view! {
+            <ActionForm
+                attr:class="mx-auto mt-8 flex max-w-xl items-center justify-between gap-x-6 rounded-md bg-green-50 px-4 py-3"
+                action=publish_issue
+            >
+                <input type="hidden" name="issue_id" value=issue_id/>
+                <label class="flex items-center gap-x-2 text-sm leading-6 text-gray-900">
+                    <input
+                        type="checkbox"
+                        name="confirm"
+                        value="true"
+                        required
+                        class="h-4 w-4 rounded border-gray-300 text-green-600 focus:ring-green-600"
+                    />
+                    "I've reviewed this issue and want to publish it"
+                </label>
+                <button
+                    type="submit"
+                    class="rounded-md bg-green-600 px-3 py-2 text-sm font-semibold text-white shadow-sm hover:bg-green-500"
+                >
+                    Publish
+                </button>
+            </ActionForm>
+        })
+    }
+}
+
+#[component]
+fn ScheduleControls(
+    issue_id: String,
+    publish_at: Option<String>,
+    publish_scheduled_by: Option<String>,
+) -> impl IntoView {
+    let schedule_issue: ServerAction<ScheduleIssue> =
+        ServerAction::new();
+    let cancel_scheduled_publish: ServerAction<
+        CancelScheduledPublish,
+    > = ServerAction::new();
+
+    match publish_at {
+        Some(publish_at) => Either::Left(view! {
+            <ActionForm
+                attr:class="mx-auto mt-4 flex max-w-xl items-center justify-between gap-x-6 rounded-md bg-blue-50 px-4 py-3"
+                action=cancel_scheduled_publish
+            >
+                <input type="hidden" name="issue_id" value=issue_id/>
+                <p class="text-sm leading-6 text-gray-900">
+                    "scheduled to publish at " {publish_at} " by "
+                    {publish_scheduled_by.unwrap_or_default()}
+                </p>
+                <button
+                    type="submit"
+                    class="rounded-md bg-white px-3 py-2 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50"
+                >
+                    Cancel
+                </button>
+            </ActionForm>
+        }),
+        None => Either::Right(view! {
+            <ActionForm
+                attr:class="mx-auto mt-4 flex max-w-xl items-center justify-between gap-x-6 rounded-md bg-blue-50 px-4 py-3"
+                action=schedule_issue
+            >
+                <input type="hidden" name="issue_id" value=issue_id/>
+                <label
+                    for="publish_at"
+                    class="text-sm leading-6 text-gray-900"
+                >
+                    "publish at (UTC)"
+                </label>
+                <input
+                    type="datetime-local"
+                    name="publish_at"
+                    id="publish_at"
+                    required
+                    class="block rounded-md border-0 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6"
+                />
+                <button
+                    type="submit"
+                    class="rounded-md bg-blue-600 px-3 py-2 text-sm font-semibold text-white shadow-sm hover:bg-blue-500"
+                >
+                    Schedule
+                </button>
+            </ActionForm>
+        }),
+    }
+}
+
+/// Arrows to move an item within its section and
+/// a button to take it out of the issue. These
+/// are plain forms, so the page reloads with the
+/// new order once the server function is done.
+#[component]
+fn ItemControls(
+    /// the server function's argument for the
+    /// item's id, ex: `showcase_id`
+    item_field: &'static str,
+    item_id: String,
+    is_first: bool,
+    is_last: bool,
+    move_url: &'static str,
+    remove_url: &'static str,
+) -> impl IntoView {
+    let params = use_params_map();
+    let issue_id = params.with_untracked(|p| {
+        p.get("id").unwrap_or_default()
+    });
+    let button = "rounded-md bg-white px-2 py-1 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50 disabled:opacity-30";
+    view! {
+        <div class="relative z-10 flex items-center gap-x-2">
+            <form method="post" action=move_url class="flex gap-x-1">
+                <input type="hidden" name="issue_id" value=issue_id.clone()/>
+                <input type="hidden" name=item_field value=item_id.clone()/>
+                <button
+                    type="submit"
+                    name="direction"
+                    value="up"
+                    disabled=is_first
+                    title="Move up"
+                    class=button
+                >
+                    "↑"
+                </button>
+                <button
+                    type="submit"
+                    name="direction"
+                    value="down"
+                    disabled=is_last
+                    title="Move down"
+                    class=button
+                >
+                    "↓"
+                </button>
+            </form>
+            <form method="post" action=remove_url>
+                <input type="hidden" name="issue_id" value=issue_id/>
+                <input type="hidden" name=item_field value=item_id/>
+                <button type="submit" class=button>
+                    Remove
+                </button>
+            </form>
+        </div>
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn parse_issue_item_id(
+    id: &str,
+) -> Result<[u8; 16], ServerFnError> {
+    id.parse::<rusty_ulid::Ulid>()
+        .map(<[u8; 16]>::from)
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                format!("`{id}` is not a valid id"),
+            )
+        })
+}
+
+#[cfg(feature = "ssr")]
+async fn move_issue_item(
+    section: SubmissionKind,
+    issue_id: String,
+    item_id: String,
+    direction: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let direction: Direction =
+        direction.parse().map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(
+                format!("{e}"),
+            )
+        })?;
+    // moving the first item up or the last item
+    // down does nothing
+    crate::issue_items::move_item(
+        &pool,
+        section,
+        &parse_issue_item_id(&issue_id)?,
+        &parse_issue_item_id(&item_id)?,
+        direction,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn remove_issue_item(
+    section: SubmissionKind,
+    issue_id: String,
+    item_id: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let removed = crate::issue_items::remove_item(
+        &pool,
+        section,
+        &parse_issue_item_id(&issue_id)?,
+        &parse_issue_item_id(&item_id)?,
+    )
+    .await?;
+    if !removed {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "that item isn't in this issue".to_string(),
+            ),
+        );
+    }
+    Ok(())
+}
+
 #[component]
 fn Showcases() -> impl IntoView {
     let params = use_params_map();
@@ -311,10 +929,18 @@ fn Showcases() -> impl IntoView {
                         .map(|data| match data {
                             Err(e) => Either::Left(view! { <pre>{e.to_string()}</pre> }),
                             Ok(showcases) => {
+                                let count = showcases.len();
                                 Either::Right(showcases
                                     .iter()
-                                    .map(|showcase| {
-                                        view! { <ShowcaseLi showcase=showcase.clone()/> }
+                                    .enumerate()
+                                    .map(|(index, showcase)| {
+                                        view! {
+                                            <ShowcaseLi
+                                                showcase=showcase.clone()
+                                                is_first=index == 0
+                                                is_last=index + 1 == count
+                                            />
+                                        }
                                     })
                                     .collect_view())
                             }
@@ -327,7 +953,11 @@ fn Showcases() -> impl IntoView {
 }
 
 #[component]
-fn ShowcaseLi(showcase: ShowcaseData) -> impl IntoView {
+fn ShowcaseLi(
+    showcase: ShowcaseData,
+    is_first: bool,
+    is_last: bool,
+) -> impl IntoView {
     view! {
         <li class="relative flex justify-between gap-x-6 px-4 py-5 hover:bg-gray-50 sm:px-6">
             <div class="flex min-w-0 gap-x-4">
@@ -354,6 +984,14 @@ fn ShowcaseLi(showcase: ShowcaseData) -> impl IntoView {
                 </div>
             </div>
             <div class="flex shrink-0 items-center gap-x-4">
+                <ItemControls
+                    item_field="showcase_id"
+                    item_id=showcase.id.clone()
+                    is_first=is_first
+                    is_last=is_last
+                    move_url=MoveShowcaseInIssue::PATH
+                    remove_url=RemoveShowcaseFromIssue::PATH
+                />
                 <div class="hidden sm:flex sm:flex-col sm:items-end">
                     <p class="text-sm leading-6 text-gray-900">{showcase.image_count} images</p>
                 // <p class="mt-1 text-xs leading-5 text-gray-500">Last seen <time datetime="2023-01-23T13:23Z">3h ago</time></p>
@@ -440,7 +1078,7 @@ LEFT JOIN (
     GROUP BY showcase__image.showcase_id
 ) AS si ON si.showcase_id = showcase.id
 WHERE issue__showcase.issue_id = ?
-ORDER BY showcase.posted_date",
+ORDER BY issue__showcase.position",
 issue_id.as_slice()
     )
     .fetch_all(&pool)
@@ -452,6 +1090,34 @@ issue_id.as_slice()
         .collect())
 }
 
+#[server]
+pub async fn move_showcase_in_issue(
+    issue_id: String,
+    showcase_id: String,
+    direction: String,
+) -> Result<(), ServerFnError> {
+    move_issue_item(
+        SubmissionKind::Showcase,
+        issue_id,
+        showcase_id,
+        direction,
+    )
+    .await
+}
+
+#[server]
+pub async fn remove_showcase_from_issue(
+    issue_id: String,
+    showcase_id: String,
+) -> Result<(), ServerFnError> {
+    remove_issue_item(
+        SubmissionKind::Showcase,
+        issue_id,
+        showcase_id,
+    )
+    .await
+}
+
 // crate_releases
 
 #[component]
@@ -479,11 +1145,17 @@ fn CrateReleases() -> impl IntoView {
                         .map(|data| match data {
                             Err(e) => Either::Left(view! { <pre>{e.to_string()}</pre> }),
                             Ok(crate_releases) => {
+                                let count = crate_releases.len();
                                 Either::Right(crate_releases
                                     .iter()
-                                    .map(|crate_release| {
+                                    .enumerate()
+                                    .map(|(index, crate_release)| {
                                         view! {
-                                            <CrateReleaseLi crate_release=crate_release.clone()/>
+                                            <CrateReleaseLi
+                                                crate_release=crate_release.clone()
+                                                is_first=index == 0
+                                                is_last=index + 1 == count
+                                            />
                                         }
                                     })
                                     .collect_view())
@@ -499,6 +1171,8 @@ fn CrateReleases() -> impl IntoView {
 #[component]
 fn CrateReleaseLi(
     crate_release: CrateReleaseData,
+    is_first: bool,
+    is_last: bool,
 ) -> impl IntoView {
     view! {
         <li class="relative flex justify-between gap-x-6 px-4 py-5 hover:bg-gray-50 sm:px-6">
@@ -526,6 +1200,14 @@ fn CrateReleaseLi(
                 </div>
             </div>
             <div class="flex shrink-0 items-center gap-x-4">
+                <ItemControls
+                    item_field="crate_release_id"
+                    item_id=crate_release.id.clone()
+                    is_first=is_first
+                    is_last=is_last
+                    move_url=MoveCrateReleaseInIssue::PATH
+                    remove_url=RemoveCrateReleaseFromIssue::PATH
+                />
                 <div class="hidden sm:flex sm:flex-col sm:items-end">
                     <p class="text-sm leading-6 text-gray-900">
                         {crate_release.image_count} images
@@ -614,7 +1296,7 @@ LEFT JOIN (
     GROUP BY crate_release__image.crate_release_id
 ) AS si ON si.crate_release_id = crate_release.id
 WHERE issue__crate_release.issue_id = ?
-ORDER BY crate_release.posted_date",
+ORDER BY issue__crate_release.position",
 issue_id.as_slice()
     )
     .fetch_all(&pool)
@@ -626,6 +1308,34 @@ issue_id.as_slice()
         .collect())
 }
 
+#[server]
+pub async fn move_crate_release_in_issue(
+    issue_id: String,
+    crate_release_id: String,
+    direction: String,
+) -> Result<(), ServerFnError> {
+    move_issue_item(
+        SubmissionKind::CrateRelease,
+        issue_id,
+        crate_release_id,
+        direction,
+    )
+    .await
+}
+
+#[server]
+pub async fn remove_crate_release_from_issue(
+    issue_id: String,
+    crate_release_id: String,
+) -> Result<(), ServerFnError> {
+    remove_issue_item(
+        SubmissionKind::CrateRelease,
+        issue_id,
+        crate_release_id,
+    )
+    .await
+}
+
 // devlogs
 
 #[component]
@@ -653,10 +1363,18 @@ fn Devlogs() -> impl IntoView {
                         .map(|data| match data {
                             Err(e) => Either::Left(view! { <pre>{e.to_string()}</pre> }),
                             Ok(devlogs) => {
+                                let count = devlogs.len();
                                 Either::Right(devlogs
                                     .iter()
-                                    .map(|devlog| {
-                                        view! { <DevlogLi devlog=devlog.clone()/> }
+                                    .enumerate()
+                                    .map(|(index, devlog)| {
+                                        view! {
+                                            <DevlogLi
+                                                devlog=devlog.clone()
+                                                is_first=index == 0
+                                                is_last=index + 1 == count
+                                            />
+                                        }
                                     })
                                     .collect_view())
                             }
@@ -669,7 +1387,11 @@ fn Devlogs() -> impl IntoView {
 }
 
 #[component]
-fn DevlogLi(devlog: DevlogData) -> impl IntoView {
+fn DevlogLi(
+    devlog: DevlogData,
+    is_first: bool,
+    is_last: bool,
+) -> impl IntoView {
     view! {
         <li class="relative flex justify-between gap-x-6 px-4 py-5 hover:bg-gray-50 sm:px-6">
             <div class="flex min-w-0 gap-x-4">
@@ -696,6 +1418,14 @@ fn DevlogLi(devlog: DevlogData) -> impl IntoView {
                 </div>
             </div>
             <div class="flex shrink-0 items-center gap-x-4">
+                <ItemControls
+                    item_field="devlog_id"
+                    item_id=devlog.id.clone()
+                    is_first=is_first
+                    is_last=is_last
+                    move_url=MoveDevlogInIssue::PATH
+                    remove_url=RemoveDevlogFromIssue::PATH
+                />
                 <div class="hidden sm:flex sm:flex-col sm:items-end">
                     <p class="text-sm leading-6 text-gray-900">{devlog.image_count} images</p>
                 // <p class="mt-1 text-xs leading-5 text-gray-500">Last seen <time datetime="2023-01-23T13:23Z">3h ago</time></p>
@@ -782,7 +1512,7 @@ LEFT JOIN (
     GROUP BY devlog__image.devlog_id
 ) AS si ON si.devlog_id = devlog.id
 WHERE issue__devlog.issue_id = ?
-ORDER BY devlog.posted_date",
+ORDER BY issue__devlog.position",
         issue_id.as_slice()
     )
     .fetch_all(&pool)
@@ -791,6 +1521,34 @@ ORDER BY devlog.posted_date",
     Ok(devlogs.into_iter().map(DevlogData::from).collect())
 }
 
+#[server]
+pub async fn move_devlog_in_issue(
+    issue_id: String,
+    devlog_id: String,
+    direction: String,
+) -> Result<(), ServerFnError> {
+    move_issue_item(
+        SubmissionKind::Devlog,
+        issue_id,
+        devlog_id,
+        direction,
+    )
+    .await
+}
+
+#[server]
+pub async fn remove_devlog_from_issue(
+    issue_id: String,
+    devlog_id: String,
+) -> Result<(), ServerFnError> {
+    remove_issue_item(
+        SubmissionKind::Devlog,
+        issue_id,
+        devlog_id,
+    )
+    .await
+}
+
 // educationals
 
 #[component]
@@ -818,10 +1576,18 @@ fn Educationals() -> impl IntoView {
                         .map(|data| match data {
                             Err(e) => Either::Left(view! { <pre>{e.to_string()}</pre> }),
                             Ok(educationals) => {
+                                let count = educationals.len();
                                 Either::Right(educationals
                                     .iter()
-                                    .map(|educational| {
-                                        view! { <EducationalLi educational=educational.clone()/> }
+                                    .enumerate()
+                                    .map(|(index, educational)| {
+                                        view! {
+                                            <EducationalLi
+                                                educational=educational.clone()
+                                                is_first=index == 0
+                                                is_last=index + 1 == count
+                                            />
+                                        }
                                     })
                                     .collect_view())
                             }
@@ -836,6 +1602,8 @@ fn Educationals() -> impl IntoView {
 #[component]
 fn EducationalLi(
     educational: EducationalData,
+    is_first: bool,
+    is_last: bool,
 ) -> impl IntoView {
     view! {
         <li class="relative flex justify-between gap-x-6 px-4 py-5 hover:bg-gray-50 sm:px-6">
@@ -863,6 +1631,14 @@ fn EducationalLi(
                 </div>
             </div>
             <div class="flex shrink-0 items-center gap-x-4">
+                <ItemControls
+                    item_field="educational_id"
+                    item_id=educational.id.clone()
+                    is_first=is_first
+                    is_last=is_last
+                    move_url=MoveEducationalInIssue::PATH
+                    remove_url=RemoveEducationalFromIssue::PATH
+                />
                 <div class="hidden sm:flex sm:flex-col sm:items-end">
                     <p class="text-sm leading-6 text-gray-900">{educational.image_count} images</p>
                 // <p class="mt-1 text-xs leading-5 text-gray-500">Last seen <time datetime="2023-01-23T13:23Z">3h ago</time></p>
@@ -949,7 +1725,7 @@ LEFT JOIN (
     GROUP BY educational__image.educational_id
 ) AS si ON si.educational_id = educational.id
 WHERE issue__educational.issue_id = ?
-ORDER BY educational.posted_date",
+ORDER BY issue__educational.position",
         issue_id.as_slice()
     )
     .fetch_all(&pool)
@@ -960,3 +1736,283 @@ ORDER BY educational.posted_date",
         .map(EducationalData::from)
         .collect())
 }
+
+#[server]
+pub async fn move_educational_in_issue(
+    issue_id: String,
+    educational_id: String,
+    direction: String,
+) -> Result<(), ServerFnError> {
+    move_issue_item(
+        SubmissionKind::Educational,
+        issue_id,
+        educational_id,
+        direction,
+    )
+    .await
+}
+
+#[server]
+pub async fn remove_educational_from_issue(
+    issue_id: String,
+    educational_id: String,
+) -> Result<(), ServerFnError> {
+    remove_issue_item(
+        SubmissionKind::Educational,
+        issue_id,
+        educational_id,
+    )
+    .await
+}
+
+#[component]
+fn Contributors() -> impl IntoView {
+    let params = use_params_map();
+    let add_contributor: ServerAction<
+        AddContributorToIssue,
+    > = ServerAction::new();
+    let remove_contributor: ServerAction<
+        RemoveContributorFromIssue,
+    > = ServerAction::new();
+
+    let contributors = Resource::new(
+        move || {
+            (
+                params.with(|p| {
+                    p.get("id").unwrap_or_default()
+                }),
+                add_contributor.version().get(),
+                remove_contributor.version().get(),
+            )
+        },
+        |(issue_id, _, _)| {
+            fetch_contributors_for_issue_id(issue_id)
+        },
+    );
+
+    view! {
+        {move || {
+            add_contributor
+                .value()
+                .get()
+                .and_then(|result| result.err())
+                .or_else(|| {
+                    remove_contributor.value().get().and_then(|result| result.err())
+                })
+                .map(|e| view! { <p class="text-sm text-red-600">{e.to_string()}</p> })
+        }}
+        <ul
+            role="list"
+            class="divide-y divide-gray-100 overflow-hidden bg-white shadow-sm ring-1 ring-gray-900/5 sm:rounded-xl"
+        >
+            <Suspense fallback=move || {
+                view! { <p>"Loading (Suspense Fallback)..."</p> }
+            }>
+                {move || {
+                    contributors
+                        .get()
+                        .map(|data| match data {
+                            Err(e) => Either::Left(view! { <pre>{e.to_string()}</pre> }),
+                            Ok(contributors) => {
+                                Either::Right(contributors
+                                    .into_iter()
+                                    .map(|contributor| {
+                                        view! {
+                                            <ContributorLi
+                                                contributor=contributor
+                                                remove_contributor=remove_contributor
+                                            />
+                                        }
+                                    })
+                                    .collect_view())
+                            }
+                        })
+                }}
+
+            </Suspense>
+        </ul>
+        <ActionForm action=add_contributor attr:class="mt-4 flex gap-x-2">
+            <input
+                type="hidden"
+                name="issue_id"
+                value=move || params.with(|p| p.get("id").unwrap_or_default())
+            />
+            <input
+                type="text"
+                name="github_username"
+                required
+                placeholder="GitHub username"
+                class="block rounded-md border-0 px-3.5 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 placeholder:text-gray-400 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6"
+            />
+            <input
+                type="text"
+                name="display_name"
+                placeholder="Display name (optional)"
+                class="block rounded-md border-0 px-3.5 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 placeholder:text-gray-400 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6"
+            />
+            <select
+                name="role"
+                class="block rounded-md border-0 py-1.5 pl-3 pr-10 text-gray-900 ring-1 ring-inset ring-gray-300 focus:ring-2 focus:ring-indigo-600 sm:text-sm sm:leading-6"
+            >
+                {ContributorRole::ALL
+                    .iter()
+                    .map(|role| view! { <option value=role.as_str()>{role.label()}</option> })
+                    .collect_view()}
+            </select>
+            <button
+                type="submit"
+                class="rounded-md bg-indigo-600 px-3 py-1.5 text-sm font-semibold text-white shadow-sm hover:bg-indigo-500"
+            >
+                Add
+            </button>
+        </ActionForm>
+    }
+}
+
+#[component]
+fn ContributorLi(
+    contributor: Contributor,
+    remove_contributor: ServerAction<
+        RemoveContributorFromIssue,
+    >,
+) -> impl IntoView {
+    let params = use_params_map();
+    view! {
+        <li class="flex items-center justify-between gap-x-6 px-4 py-5 sm:px-6">
+            <div class="min-w-0">
+                <p class="text-sm font-semibold leading-6 text-gray-900">
+                    <a href=contributor.github_url()>{contributor.name().to_string()}</a>
+                </p>
+                <p class="mt-1 text-xs leading-5 text-gray-500">
+                    {format!("@{} · {}", contributor.github_username, contributor.role.label())}
+                </p>
+            </div>
+            <ActionForm action=remove_contributor>
+                <input
+                    type="hidden"
+                    name="issue_id"
+                    value=move || params.with(|p| p.get("id").unwrap_or_default())
+                />
+                <input type="hidden" name="github_username" value=contributor.github_username/>
+                <button
+                    type="submit"
+                    class="rounded-md bg-white px-2.5 py-1.5 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50"
+                >
+                    Remove
+                </button>
+            </ActionForm>
+        </li>
+    }
+}
+
+#[server]
+pub async fn fetch_contributors_for_issue_id(
+    issue_id: String,
+) -> Result<Vec<Contributor>, ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let issue_id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .expect("a valid ulid to be returned from the form")
+        .into();
+
+    let contributors: Vec<SqlContributor> =
+        sqlx::query_as!(
+            SqlContributor,
+            "SELECT github_username, display_name, role
+FROM issue__contributors
+WHERE issue_id = ?
+ORDER BY created_at",
+            issue_id.as_slice()
+        )
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(contributors
+        .into_iter()
+        .map(Contributor::from)
+        .collect())
+}
+
+/// Adding someone who is already credited on the
+/// issue updates their name and role instead.
+#[server]
+pub async fn add_contributor_to_issue(
+    issue_id: String,
+    github_username: String,
+    display_name: String,
+    role: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let issue_id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+    let github_username =
+        crate::contributors::parse_github_username(
+            &github_username,
+        )
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(
+                e.to_string(),
+            )
+        })?;
+    let role: ContributorRole =
+        role.parse().map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(
+                format!("{e}"),
+            )
+        })?;
+
+    sqlx::query!(
+        "INSERT INTO issue__contributors (issue_id, github_username, display_name, role)
+VALUES (?, ?, ?, ?)
+ON DUPLICATE KEY UPDATE
+    display_name = VALUES(display_name),
+    role = VALUES(role)",
+        issue_id.as_slice(),
+        github_username,
+        display_name.trim(),
+        role.as_str()
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+#[server]
+pub async fn remove_contributor_from_issue(
+    issue_id: String,
+    github_username: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let issue_id: [u8; 16] = issue_id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid issue id".to_string(),
+            )
+        })?
+        .into();
+
+    sqlx::query!(
+        "DELETE FROM issue__contributors
+WHERE issue_id = ? AND github_username = ?",
+        issue_id.as_slice(),
+        github_username
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
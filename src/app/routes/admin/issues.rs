@@ -41,6 +41,21 @@ pub fn Issues() -> impl IntoView {
                 </div>
             </div>
             <Suspense fallback=move || view! { <p>"Loading (Suspense Fallback)..."</p> }>
+                {move || {
+                    issues
+                        .get()
+                        .and_then(Result::ok)
+                        .map(|issues| {
+                            let scheduled = issues
+                                .into_iter()
+                                .filter(|issue| issue.publish_at.is_some())
+                                .collect::<Vec<_>>();
+                            (!scheduled.is_empty())
+                                .then(|| {
+                                    view! { <ScheduledIssues issues=scheduled/> }
+                                })
+                        })
+                }}
                 <ul role="list" class="divide-y-4 divide-ctp-mantle">
                     {move || {
                         issues
@@ -75,7 +90,12 @@ pub fn Issues() -> impl IntoView {
                                                                 .issue_date
                                                                 .to_string()>{issue.issue_date.to_string()}</time>
                                                             <p class="flex-none text-xs text-gray-600">
-                                                                something here
+                                                                {issue
+                                                                    .publish_at
+                                                                    .clone()
+                                                                    .map(|publish_at| {
+                                                                        format!("scheduled for {publish_at}")
+                                                                    })}
                                                             </p>
                                                         </div>
                                                     </div>
@@ -93,6 +113,43 @@ pub fn Issues() -> impl IntoView {
     }
 }
 
+/// Drafts that the scheduler will publish when
+/// their `publish_at` time arrives
+#[component]
+fn ScheduledIssues(
+    issues: Vec<IssueShort>,
+) -> impl IntoView {
+    view! {
+        <div class="mt-8 bg-blue-50 shadow sm:rounded-lg">
+            <div class="px-4 py-5 sm:p-6">
+                <h3 class="text-base font-semibold leading-6 text-gray-900">
+                    Pending Schedules
+                </h3>
+                <ul role="list" class="mt-2 divide-y divide-blue-100">
+                    {issues
+                        .into_iter()
+                        .map(|issue| {
+                            view! {
+                                <li class="flex items-baseline justify-between gap-x-4 py-2">
+                                    <a
+                                        href=format!("/admin/issue/{}", &issue.id)
+                                        class="text-sm font-semibold leading-6 text-gray-900"
+                                    >
+                                        {issue.display_name}
+                                    </a>
+                                    <p class="flex-none text-xs text-gray-600">
+                                        {issue.publish_at}
+                                    </p>
+                                </li>
+                            }
+                        })
+                        .collect_view()}
+                </ul>
+            </div>
+        </div>
+    }
+}
+
 #[cfg(feature = "ssr")]
 #[derive(Debug, sqlx::FromRow)]
 struct SqlIssueShort {
@@ -100,6 +157,7 @@ struct SqlIssueShort {
     display_name: String,
     status: String,
     issue_date: time::Date,
+    publish_at: Option<time::OffsetDateTime>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -108,6 +166,7 @@ pub struct IssueShort {
     display_name: String,
     status: String,
     issue_date: time::Date,
+    publish_at: Option<String>,
 }
 
 #[cfg(feature = "ssr")]
@@ -125,6 +184,15 @@ impl From<SqlIssueShort> for IssueShort {
             display_name: value.display_name,
             status: value.status,
             issue_date: value.issue_date,
+            publish_at: value.publish_at.and_then(
+                |publish_at| {
+                    publish_at
+                        .format(
+                            crate::issue_date::TIMESTAMP_FORMAT,
+                        )
+                        .ok()
+                },
+            ),
         }
     }
 }
@@ -141,7 +209,8 @@ pub async fn fetch_issues(
         id,
         display_name,
         status,
-        issue_date
+        issue_date,
+        publish_at
 FROM issue
 ORDER BY status, issue_date DESC"
     )
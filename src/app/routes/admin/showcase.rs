@@ -264,6 +264,7 @@ FROM showcase
 LEFT JOIN issue__showcase
   ON showcase.id = issue__showcase.showcase_id
 WHERE issue__showcase.issue_id IS NULL
+AND showcase.review_status = 'approved'
 ORDER BY showcase.id"
     )
     .fetch_all(&pool)
@@ -293,17 +294,36 @@ async fn associate_showcase_with_issue(
         .expect("a valid ulid to be returned from the form")
         .into();
 
-    sqlx::query!(
+    let mut tx = pool.begin().await?;
+    crate::issue_items::lock_issue(&mut tx, &issue_id)
+        .await?;
+
+    // pending and rejected submissions can't be
+    // added to an issue. new items go at the end.
+    let result = sqlx::query!(
         r#"
-    INSERT INTO issue__showcase ( issue_id, showcase_id )
-    VALUES ( ?, ? )
+    INSERT INTO issue__showcase ( issue_id, showcase_id, position )
+    SELECT ?, id, (
+        SELECT COALESCE(MAX(position), 0) + 1
+        FROM issue__showcase
+        WHERE issue_id = ?
+    ) FROM showcase
+    WHERE id = ?
+    AND review_status = 'approved'
         "#,
         issue_id.as_slice(),
+        issue_id.as_slice(),
         showcase_id.as_slice()
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
     .expect("successful insert");
+    tx.commit().await?;
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::ServerError(
+            "showcase is not approved".to_string(),
+        ));
+    }
     Ok(())
 }
 
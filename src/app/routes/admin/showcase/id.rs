@@ -6,9 +6,60 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
 use crate::app::server_fn::error::NoCustomError;
 
+#[cfg(feature = "ssr")]
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateShowcaseError {
+    #[error("showcase {0} does not exist")]
+    NotFound(rusty_ulid::Ulid),
+
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+}
+
+#[cfg(feature = "ssr")]
+pub async fn update_showcase_row(
+    pool: &sqlx::MySqlPool,
+    id: rusty_ulid::Ulid,
+    title: &str,
+    url: &str,
+    discord_url: &str,
+    description: &str,
+    posted_date: time::Date,
+) -> Result<(), UpdateShowcaseError> {
+    let id_bytes: [u8; 16] = id.into();
+
+    // the connection reports matched rows rather
+    // than changed rows, so saving without any
+    // edits still counts as 1.
+    let result = sqlx::query!(
+        r#"
+    UPDATE showcase
+    SET
+        title = ?,
+        url = ?,
+        discord_url = ?,
+        description = ?,
+        posted_date = ?
+    WHERE id = ?
+        "#,
+        title,
+        url,
+        discord_url,
+        description,
+        posted_date,
+        id_bytes.as_slice()
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(UpdateShowcaseError::NotFound(id));
+    }
+    Ok(())
+}
+
 #[server]
-#[allow(unused_variables)]
-async fn update_showcase(
+pub async fn update_showcase(
     showcase_id: String,
     title: String,
     url: String,
@@ -16,41 +67,41 @@ async fn update_showcase(
     description: String,
     posted_date: String,
 ) -> Result<(), ServerFnError> {
-    let _pool = crate::sql::pool()?;
+    let pool = crate::sql::pool()?;
     let _username = crate::sql::with_admin_access()?;
 
-    let _id: [u8; 16] = showcase_id
+    let id = showcase_id
         .parse::<rusty_ulid::Ulid>()
         .map_err(|_| {
             ServerFnError::<NoCustomError>::ServerError(
                 "expected a valid showcase id".to_string(),
             )
-        })?
-        .into();
+        })?;
+
+    let posted_date =
+        crate::issue_date::parse_issue_date(&posted_date)
+            .ok_or_else(|| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid posted_date".to_string(),
+            )
+        })?;
+
+    update_showcase_row(
+        &pool,
+        id,
+        &title,
+        &url,
+        &discord_url,
+        &description,
+        posted_date,
+    )
+    .await
+    .map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            e.to_string(),
+        )
+    })?;
 
-    dbg!(title);
-    // sqlx::query!(
-    //     r#"
-    // UPDATE showcase
-    //   SET
-    //   id = ?
-    //   title = ?
-    //   url = ?
-    //   discord_url = ?
-    //   posted_date = ?
-    //   description
-    // VALUES ( ?, ?, ?, ?, ?, ? )
-    //     "#,
-    //     id.as_slice(),
-    //     title,
-    //     url,
-    //     discord_url,
-    //     posted_date,
-    //     description
-    // )
-    // .execute(&pool)
-    // .await
-    // .expect("successful insert");
     Ok(())
 }
 
@@ -233,12 +284,12 @@ struct SqlShowcaseData {
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct ShowcaseData {
-    id: String,
-    title: String,
-    url: String,
-    posted_date: Option<time::Date>,
-    discord_url: String,
-    description: String,
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub posted_date: Option<time::Date>,
+    pub discord_url: String,
+    pub description: String,
     images: Vec<ImgDataTransformed>,
 }
 
@@ -264,6 +315,8 @@ impl From<SqlShowcaseData> for ShowcaseData {
                 .expect(
                     "expect valid ids from the database",
                 );
+        let backend =
+            crate::sql::site_config().image_backend();
         let images =
             serde_json::from_value::<Vec<ImgData>>(
                 value.images,
@@ -274,12 +327,6 @@ impl From<SqlShowcaseData> for ShowcaseData {
             .unwrap_or_default()
             .into_iter()
             .map(|img_data| {
-                use cloudinary::transformation::{
-                    resize_mode::ResizeMode::ScaleByWidth,
-                    Image as CImage,
-                    Transformations::Resize,
-                };
-
                 let base_id = BASE64
                     .decode(img_data.id.as_bytes())
                     .expect("a valid id in base64 format");
@@ -289,18 +336,14 @@ impl From<SqlShowcaseData> for ShowcaseData {
                 .expect(
                     "expect valid ids from the database",
                 );
-                let image = CImage::new(
-                    "dilgcuzda".into(),
-                    img_data.cloudinary_public_id.into(),
-                )
-                .add_transformation(Resize(ScaleByWidth {
-                    width: 300,
-                    ar: None,
-                    liquid: None,
-                }));
+                let url = backend.url(
+                    &img_data.cloudinary_public_id,
+                    Some(300),
+                    None,
+                );
                 ImgDataTransformed {
                     id: img_ulid.to_string(),
-                    url: image.to_string(),
+                    url,
                 }
             })
             .collect();
@@ -601,22 +644,16 @@ pub struct Image {
 }
 
 #[cfg(feature = "ssr")]
-impl From<SqlImage> for Image {
-    fn from(value: SqlImage) -> Self {
-        use cloudinary::transformation::{
-            resize_mode::ResizeMode::ScaleByWidth,
-            Image as CImage, Transformations::Resize,
-        };
-
-        let image = CImage::new(
-            "dilgcuzda".into(),
-            value.cloudinary_public_id.into(),
-        )
-        .add_transformation(Resize(ScaleByWidth {
-            width: 300,
-            ar: None,
-            liquid: None,
-        }));
+impl Image {
+    fn new(
+        value: SqlImage,
+        backend: &dyn crate::images::ImageBackend,
+    ) -> Self {
+        let url = backend.url(
+            &value.cloudinary_public_id,
+            Some(300),
+            None,
+        );
 
         let id_str =
             rusty_ulid::Ulid::try_from(value.id.as_slice())
@@ -626,7 +663,7 @@ impl From<SqlImage> for Image {
         Image {
             id: id_str.to_string(),
             description: value.description,
-            url: image.to_string(),
+            url,
         }
     }
 }
@@ -655,5 +692,104 @@ limit 5"#
         )
     })?;
 
-    Ok(images.into_iter().map(Image::from).collect())
+    let backend = crate::sql::site_config().image_backend();
+    Ok(images
+        .into_iter()
+        .map(|image| Image::new(image, backend.as_ref()))
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+#[cfg(test)]
+mod tests_server {
+    use super::*;
+    use crate::{
+        roles::Role, sql::test_db::DisposableSchema,
+        Username,
+    };
+    use time::macros::date;
+
+    async fn insert_showcase(
+        pool: &sqlx::MySqlPool,
+    ) -> rusty_ulid::Ulid {
+        let id = rusty_ulid::Ulid::generate();
+        let id_bytes: [u8; 16] = id.into();
+        sqlx::query(
+            "INSERT INTO showcase ( id, title, url, discord_url, posted_date, description )
+VALUES ( ?, 'before', 'https://example.com', '', '2024-02-01', 'old' )",
+        )
+        .bind(id_bytes.as_slice())
+        .execute(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn update_showcase_round_trips() {
+        let schema = DisposableSchema::new().await;
+        let id = insert_showcase(&schema.pool).await;
+
+        let owner = Owner::new();
+        owner.set();
+        provide_context(schema.pool.clone());
+        provide_context(Some(Username(
+            "ChristopherBiscardi".to_string(),
+        )));
+        provide_context(Some(Role::Editor));
+
+        update_showcase(
+            id.to_string(),
+            "after".to_string(),
+            "https://bevyengine.org".to_string(),
+            "https://discord.com/channels/1".to_string(),
+            "new description".to_string(),
+            "2024-02-10".to_string(),
+        )
+        .await
+        .unwrap();
+
+        let showcase = fetch_showcase_by_id(id.to_string())
+            .await
+            .unwrap()
+            .expect("the showcase to exist");
+        assert_eq!("after", showcase.title);
+        assert_eq!("https://bevyengine.org", showcase.url);
+        assert_eq!(
+            "https://discord.com/channels/1",
+            showcase.discord_url
+        );
+        assert_eq!("new description", showcase.description);
+        assert_eq!(
+            Some(date!(2024 - 02 - 10)),
+            showcase.posted_date
+        );
+
+        schema.drop().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn update_missing_showcase_is_not_found() {
+        let schema = DisposableSchema::new().await;
+        let id = rusty_ulid::Ulid::generate();
+
+        let result = update_showcase_row(
+            &schema.pool,
+            id,
+            "title",
+            "https://bevyengine.org",
+            "",
+            "",
+            date!(2024 - 02 - 10),
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(UpdateShowcaseError::NotFound(missing)) if missing == id
+        ));
+
+        schema.drop().await;
+    }
 }
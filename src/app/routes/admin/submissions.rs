@@ -0,0 +1,312 @@
+#[cfg(feature = "ssr")]
+use crate::app::server_fn::error::NoCustomError;
+use crate::{
+    app::components::Divider,
+    submissions::{ReviewStatus, SubmissionKind},
+};
+use leptos::{either::Either, prelude::*};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use tracing::error;
+
+#[component]
+pub fn Submissions() -> impl IntoView {
+    let review_submission: ServerAction<ReviewSubmission> =
+        ServerAction::new();
+    let submissions = Resource::new(
+        move || review_submission.version().get(),
+        |_| fetch_pending_submissions(),
+    );
+
+    view! {
+        <div class="mx-auto max-w-7xl sm:px-6 lg:px-8">
+            <Divider title="pending submissions"/>
+            <p class="text-sm text-gray-500">
+                "Approved submissions show up on their content type's page, ready to be added to an issue."
+            </p>
+            {move || {
+                review_submission
+                    .value()
+                    .get()
+                    .and_then(|result| result.err())
+                    .map(|e| view! { <p class="text-sm text-red-600">{e.to_string()}</p> })
+            }}
+            <Suspense fallback=move || {
+                view! { <p>"Loading (Suspense Fallback)..."</p> }
+            }>
+                {move || {
+                    submissions
+                        .get()
+                        .map(|data| match data {
+                            Err(e) => Either::Left(view! { <div>{e.to_string()}</div> }),
+                            Ok(submissions) => {
+                                Either::Right(view! {
+                                    <ul role="list" class="divide-y divide-gray-100">
+                                        <For
+                                            each=move || submissions.clone()
+                                            key=|submission| submission.id.clone()
+                                            let:submission
+                                        >
+                                            <SubmissionLi
+                                                submission=submission
+                                                review_submission=review_submission
+                                            />
+                                        </For>
+                                    </ul>
+                                })
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn SubmissionLi(
+    submission: PendingSubmission,
+    review_submission: ServerAction<ReviewSubmission>,
+) -> impl IntoView {
+    let edit_path =
+        submission.kind.admin_path(&submission.id);
+    let kind = submission.kind;
+    let id = submission.id.clone();
+    let review_button =
+        move |status: ReviewStatus, label: &'static str| {
+            let id = id.clone();
+            view! {
+                <ActionForm action=review_submission>
+                    <input type="hidden" name="kind" value=kind.as_str()/>
+                    <input type="hidden" name="id" value=id/>
+                    <input type="hidden" name="status" value=status.as_str()/>
+                    <button
+                        type="submit"
+                        class="rounded-md bg-white px-2.5 py-1.5 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50"
+                    >
+                        {label}
+                    </button>
+                </ActionForm>
+            }
+        };
+
+    view! {
+        <li class="flex items-center justify-between gap-x-6 py-5">
+            <div class="min-w-0">
+                <div class="flex items-start gap-x-3">
+                    <p class="text-sm font-semibold leading-6 text-gray-900">
+                        {submission.title.clone()}
+                    </p>
+                    <p class="rounded-md bg-gray-50 px-1.5 py-0.5 text-xs font-medium text-gray-600 ring-1 ring-inset ring-gray-500/10">
+                        {submission.kind.display_name()}
+                    </p>
+                </div>
+                <div class="mt-1 flex items-center gap-x-2 text-xs leading-5 text-gray-500">
+                    <p class="truncate">
+                        "Submitted by " {submission.submitted_by.clone().unwrap_or_default()}
+                    </p>
+                    <svg viewBox="0 0 2 2" class="h-0.5 w-0.5 fill-current">
+                        <circle cx="1" cy="1" r="1"></circle>
+                    </svg>
+                    <a href=submission.url.clone() class="truncate" rel="nofollow noopener">
+                        {submission.url.clone()}
+                    </a>
+                </div>
+                <p class="mt-1 text-sm text-gray-700">{submission.description.clone()}</p>
+            </div>
+            <div class="flex flex-none items-center gap-x-4">
+                <a
+                    href=edit_path
+                    class="rounded-md bg-white px-2.5 py-1.5 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50"
+                >
+                    Edit
+                </a>
+                {review_button(ReviewStatus::Approved, "Approve")}
+                {review_button(ReviewStatus::Rejected, "Reject")}
+            </div>
+        </li>
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PendingSubmission {
+    pub kind: SubmissionKind,
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub description: String,
+    pub submitted_by: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, sqlx::FromRow)]
+struct SqlPendingSubmission {
+    kind: String,
+    id: Vec<u8>,
+    title: String,
+    url: String,
+    description: String,
+    submitted_by: Option<String>,
+}
+
+#[server]
+async fn fetch_pending_submissions(
+) -> Result<Vec<PendingSubmission>, ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let submissions: Vec<SqlPendingSubmission> =
+        sqlx::query_as!(
+            SqlPendingSubmission,
+            r#"SELECT
+    'showcase' as "kind!: String",
+    id, title, url, description, submitted_by
+FROM showcase
+WHERE review_status = 'pending'
+UNION ALL
+SELECT
+    'crate_release', id, title, url, description, submitted_by
+FROM crate_release
+WHERE review_status = 'pending'
+UNION ALL
+SELECT
+    'devlog', id, title, post_url, description, submitted_by
+FROM devlog
+WHERE review_status = 'pending'
+UNION ALL
+SELECT
+    'educational', id, title, post_url, description, submitted_by
+FROM educational
+WHERE review_status = 'pending'
+ORDER BY id"#
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            error!(?e);
+            ServerFnError::<NoCustomError>::ServerError(
+                "sql failed".to_string(),
+            )
+        })?;
+
+    submissions
+        .into_iter()
+        .map(|submission| {
+            let id = rusty_ulid::Ulid::try_from(
+                submission.id.as_slice(),
+            )
+            .expect("expect valid ids from the database");
+            Ok(PendingSubmission {
+                kind: submission.kind.parse().map_err(
+                    |e: crate::submissions::UnknownSubmissionKind| {
+                        ServerFnError::<NoCustomError>::ServerError(
+                            e.to_string(),
+                        )
+                    },
+                )?,
+                id: id.to_string(),
+                title: submission.title,
+                url: submission.url,
+                description: submission.description,
+                submitted_by: submission.submitted_by,
+            })
+        })
+        .collect()
+}
+
+#[server]
+async fn review_submission(
+    kind: String,
+    id: String,
+    status: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username = crate::sql::with_admin_access()?;
+
+    let to_server_error =
+        |e: crate::submissions::SubmissionError| {
+            ServerFnError::<NoCustomError>::ServerError(
+                e.to_string(),
+            )
+        };
+    let kind = kind
+        .parse::<SubmissionKind>()
+        .map_err(|e| to_server_error(e.into()))?;
+    let status = status
+        .parse::<ReviewStatus>()
+        .map_err(|e| to_server_error(e.into()))?;
+    let id: [u8; 16] = id
+        .parse::<rusty_ulid::Ulid>()
+        .map_err(|_| {
+            ServerFnError::<NoCustomError>::ServerError(
+                "expected a valid ulid".to_string(),
+            )
+        })?
+        .into();
+
+    let result = match kind {
+        SubmissionKind::Showcase => {
+            sqlx::query!(
+                "UPDATE showcase
+SET review_status = ?
+WHERE id = ?
+AND review_status = 'pending'",
+                status.as_str(),
+                id.as_slice()
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::CrateRelease => {
+            sqlx::query!(
+                "UPDATE crate_release
+SET review_status = ?
+WHERE id = ?
+AND review_status = 'pending'",
+                status.as_str(),
+                id.as_slice()
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::Devlog => {
+            sqlx::query!(
+                "UPDATE devlog
+SET review_status = ?
+WHERE id = ?
+AND review_status = 'pending'",
+                status.as_str(),
+                id.as_slice()
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::Educational => {
+            sqlx::query!(
+                "UPDATE educational
+SET review_status = ?
+WHERE id = ?
+AND review_status = 'pending'",
+                status.as_str(),
+                id.as_slice()
+            )
+            .execute(&pool)
+            .await
+        }
+    }
+    .map_err(|e| {
+        error!(?e);
+        ServerFnError::<NoCustomError>::ServerError(
+            "sql failed".to_string(),
+        )
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "submission not found or already reviewed"
+                    .to_string(),
+            ),
+        );
+    }
+    Ok(())
+}
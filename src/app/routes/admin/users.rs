@@ -0,0 +1,244 @@
+#[cfg(feature = "ssr")]
+use crate::app::server_fn::error::NoCustomError;
+use crate::{app::components::Divider, roles::Role};
+use leptos::{either::Either, prelude::*};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use tracing::error;
+
+#[component]
+pub fn Users() -> impl IntoView {
+    let set_user_role: ServerAction<SetUserRole> =
+        ServerAction::new();
+    let users = Resource::new(
+        move || set_user_role.version().get(),
+        |_| fetch_users(),
+    );
+    let session_cleanup =
+        Resource::new(|| (), |_| fetch_session_cleanup());
+
+    view! {
+        <div class="mx-auto max-w-7xl sm:px-6 lg:px-8">
+            <Divider title="users"/>
+            <p class="text-sm text-gray-500">
+                "Editors can use the admin panel. Admins can also change roles."
+            </p>
+            {move || {
+                set_user_role
+                    .value()
+                    .get()
+                    .and_then(|result| result.err())
+                    .map(|e| view! { <p class="text-sm text-red-600">{e.to_string()}</p> })
+            }}
+            <Suspense fallback=move || {
+                view! { <p>"Loading (Suspense Fallback)..."</p> }
+            }>
+                {move || {
+                    users
+                        .get()
+                        .map(|data| match data {
+                            Err(e) => Either::Left(view! { <div>{e.to_string()}</div> }),
+                            Ok(users) => {
+                                Either::Right(view! {
+                                    <ul role="list" class="divide-y divide-gray-100">
+                                        <For
+                                            each=move || users.clone()
+                                            key=|user| (user.github_id.clone(), user.role)
+                                            let:user
+                                        >
+                                            <UserLi user=user set_user_role=set_user_role/>
+                                        </For>
+                                    </ul>
+                                })
+                            }
+                        })
+                }}
+            </Suspense>
+            <Divider title="sessions"/>
+            <Suspense fallback=move || {
+                view! { <p>"Loading (Suspense Fallback)..."</p> }
+            }>
+                {move || {
+                    session_cleanup
+                        .get()
+                        .map(|data| match data {
+                            Err(e) => Either::Left(view! { <div>{e.to_string()}</div> }),
+                            Ok(cleanup) => {
+                                Either::Right(view! {
+                                    <p class="text-sm text-gray-500">
+                                        {format!(
+                                            "Since the server started, expired session cleanup has run {} times and deleted {} sessions. {} runs failed.",
+                                            cleanup.runs,
+                                            cleanup.deleted,
+                                            cleanup.failures,
+                                        )}
+                                    </p>
+                                })
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn UserLi(
+    user: UserData,
+    set_user_role: ServerAction<SetUserRole>,
+) -> impl IntoView {
+    view! {
+        <li class="flex items-center justify-between gap-x-6 py-5">
+            <p class="text-sm font-semibold leading-6 text-gray-900">{user.username}</p>
+            <ActionForm action=set_user_role attr:class="flex gap-x-2">
+                <input type="hidden" name="github_id" value=user.github_id/>
+                <select
+                    name="role"
+                    class="block rounded-md border-0 py-1.5 pl-3 pr-10 text-gray-900 ring-1 ring-inset ring-gray-300 focus:ring-2 focus:ring-indigo-600 sm:text-sm sm:leading-6"
+                >
+                    {Role::ALL
+                        .iter()
+                        .map(|role| {
+                            view! {
+                                <option value=role.as_str() selected=*role == user.role>
+                                    {role.as_str()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <button
+                    type="submit"
+                    class="rounded-md bg-white px-2.5 py-1.5 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50"
+                >
+                    Save
+                </button>
+            </ActionForm>
+        </li>
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct UserData {
+    pub github_id: String,
+    pub username: String,
+    pub role: Role,
+}
+
+/// Totals from the expired session cleanup,
+/// which reset when the server restarts
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SessionCleanup {
+    pub runs: u64,
+    pub failures: u64,
+    pub deleted: u64,
+}
+
+#[server]
+async fn fetch_session_cleanup(
+) -> Result<SessionCleanup, ServerFnError> {
+    let _username =
+        crate::sql::with_role_management_access()?;
+    let metrics = use_context::<
+        std::sync::Arc<
+            crate::session_store::CleanupMetrics,
+        >,
+    >()
+    .ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError(
+            "CleanupMetrics missing.".into(),
+        )
+    })?;
+    Ok(SessionCleanup {
+        runs: metrics.runs(),
+        failures: metrics.failures(),
+        deleted: metrics.deleted(),
+    })
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, sqlx::FromRow)]
+struct SqlUser {
+    github_id: String,
+    username: String,
+    role: String,
+}
+
+#[server]
+async fn fetch_users(
+) -> Result<Vec<UserData>, ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let _username =
+        crate::sql::with_role_management_access()?;
+
+    let users: Vec<SqlUser> = sqlx::query_as!(
+        SqlUser,
+        "SELECT github_id, username, role
+FROM github_users
+ORDER BY username"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e);
+        ServerFnError::<NoCustomError>::ServerError(
+            "sql failed".to_string(),
+        )
+    })?;
+
+    Ok(users
+        .into_iter()
+        .map(|user| UserData {
+            role: user
+                .role
+                .parse()
+                .unwrap_or(Role::Contributor),
+            github_id: user.github_id,
+            username: user.username,
+        })
+        .collect())
+}
+
+#[server]
+async fn set_user_role(
+    github_id: String,
+    role: String,
+) -> Result<(), ServerFnError> {
+    let pool = crate::sql::pool()?;
+    let username =
+        crate::sql::with_role_management_access()?;
+
+    let role: Role = role.parse().map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(
+            format!("{e}"),
+        )
+    })?;
+
+    // changing your own role could leave the
+    // site without an admin
+    let result = sqlx::query!(
+        "UPDATE github_users
+SET role = ?
+WHERE github_id = ?
+AND username != ?",
+        role.as_str(),
+        github_id,
+        username.0
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        error!(?e);
+        ServerFnError::<NoCustomError>::ServerError(
+            "sql failed".to_string(),
+        )
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "user not found, or tried to change your own role"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,392 @@
+use crate::app::components::{Container, Divider};
+use leptos::{either::Either, prelude::*};
+use leptos_meta::Title;
+use leptos_router::hooks::use_params_map;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use tracing::error;
+
+/// A published issue, as linked from the archive
+/// and the prev/next navigation on an issue
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct IssueLink {
+    pub slug: String,
+    pub issue_date: time::Date,
+    pub display_name: String,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize,
+)]
+pub struct ArchiveMonth {
+    pub month: time::Month,
+    pub issues: Vec<IssueLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archive {
+    pub year: i32,
+    /// every year with a published issue, for
+    /// navigating between years
+    pub years: Vec<i32>,
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// Group issues, already sorted by date, into
+/// their months
+pub fn group_by_month(
+    issues: Vec<IssueLink>,
+) -> Vec<ArchiveMonth> {
+    let mut months: Vec<ArchiveMonth> = vec![];
+    for issue in issues {
+        let month = issue.issue_date.month();
+        match months.last_mut() {
+            Some(last) if last.month == month => {
+                last.issues.push(issue)
+            }
+            _ => months.push(ArchiveMonth {
+                month,
+                issues: vec![issue],
+            }),
+        }
+    }
+    months
+}
+
+#[component]
+pub fn Archive() -> impl IntoView {
+    let params = use_params_map();
+    let archive = Resource::new_blocking(
+        move || {
+            params.with(|p| {
+                p.get("year").and_then(|year| {
+                    year.parse::<i32>().ok()
+                })
+            })
+        },
+        |year| async move {
+            match year {
+                Some(year) => fetch_archive(year).await,
+                None => Err(ServerFnError::ServerError(
+                    "not a year".to_string(),
+                )),
+            }
+        },
+    );
+
+    view! {
+        <Suspense fallback=move || {
+            view! { <p>"Loading..."</p> }
+        }>
+            {move || {
+                archive
+                    .get()
+                    .map(|archive| match archive {
+                        Err(e) => Either::Left(view! { <p>{e.to_string()}</p> }),
+                        Ok(archive) => Either::Right(view! { <ArchiveYear archive/> }),
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+#[component]
+fn ArchiveYear(archive: Archive) -> impl IntoView {
+    let year = archive.year;
+    view! {
+        <Title text=format!("{year} Archive | This Week in Bevy")/>
+        <div class="pb-12 pt-16 sm:pb-4 lg:pt-12">
+            <Container>
+                <h1 class="text-2xl font-bold leading-7 text-ctp-text">{year} " Archive"</h1>
+                <nav class="mt-4 flex gap-4 font-mono text-sm text-ctp-text">
+                    {archive
+                        .years
+                        .into_iter()
+                        .map(|y| {
+                            if y == year {
+                                Either::Left(view! { <span class="font-bold">{y}</span> })
+                            } else {
+                                Either::Right(
+                                    view! {
+                                        <a href=format!("/archive/{y}") class="text-ctp-pink">
+                                            {y}
+                                        </a>
+                                    },
+                                )
+                            }
+                        })
+                        .collect_view()}
+                </nav>
+                {archive.months.is_empty().then_some(view! {
+                    <p class="mt-6 text-ctp-text">"No issues this year"</p>
+                })}
+                {archive
+                    .months
+                    .into_iter()
+                    .map(|month| {
+                        view! {
+                            <Divider title=month.month.to_string()/>
+                            <ul role="list" class="mt-4 space-y-2">
+                                {month
+                                    .issues
+                                    .into_iter()
+                                    .map(|issue| {
+                                        view! {
+                                            <li class="text-ctp-text">
+                                                <span class="font-mono text-sm">
+                                                    {issue.issue_date.to_string()}
+                                                </span>
+                                                " "
+                                                <a
+                                                    href=format!("/issue/{}", issue.slug)
+                                                    class="font-bold hover:text-ctp-sky"
+                                                >
+                                                    {issue.display_name}
+                                                </a>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </ul>
+                        }
+                    })
+                    .collect_view()}
+            </Container>
+        </div>
+    }
+}
+
+/// The first day of `year` and of the year after,
+/// if `time` can represent both
+#[cfg(feature = "ssr")]
+fn year_range(
+    year: i32,
+) -> Option<(time::Date, time::Date)> {
+    use time::{Date, Month};
+
+    let first_day = |year| {
+        Date::from_calendar_date(year, Month::January, 1)
+            .ok()
+    };
+    Some((
+        first_day(year)?,
+        first_day(year.checked_add(1)?)?,
+    ))
+}
+
+#[server]
+pub async fn fetch_archive(
+    year: i32,
+) -> Result<Archive, ServerFnError> {
+    use crate::app::server_fn::error::NoCustomError;
+
+    let pool = crate::sql::pool()?;
+
+    let Some((start, end)) = year_range(year) else {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "not a year".to_string(),
+            ),
+        );
+    };
+
+    let issues = sqlx::query_as!(
+        IssueLink,
+        r#"SELECT
+    slug,
+    issue_date,
+    display_name
+FROM issue
+WHERE status = "publish"
+AND issue_date >= ?
+AND issue_date < ?
+ORDER BY issue_date"#,
+        start,
+        end
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let years = sqlx::query_scalar!(
+        r#"SELECT DISTINCT
+    CAST(YEAR(issue_date) AS SIGNED) as "year!: i64"
+FROM issue
+WHERE status = "publish"
+ORDER BY 1"#
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let (issues, years) = issues
+        .and_then(|issues| {
+            years.map(|years| (issues, years))
+        })
+        .map_err(|e| {
+            error!(?e);
+            ServerFnError::<NoCustomError>::ServerError(
+                "sql failed".to_string(),
+            )
+        })?;
+
+    Ok(Archive {
+        year,
+        years: years
+            .into_iter()
+            .filter_map(|year| i32::try_from(year).ok())
+            .collect(),
+        months: group_by_month(issues),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjacentIssues {
+    pub previous: Option<IssueLink>,
+    pub next: Option<IssueLink>,
+}
+
+/// The published issues just before and after
+/// `date`
+#[server]
+pub async fn fetch_adjacent_issues(
+    date: time::Date,
+) -> Result<AdjacentIssues, ServerFnError> {
+    let pool = crate::sql::pool()?;
+
+    let previous = sqlx::query_as!(
+        IssueLink,
+        r#"SELECT
+    slug,
+    issue_date,
+    display_name
+FROM issue
+WHERE status = "publish"
+AND issue_date < ?
+ORDER BY issue_date DESC
+LIMIT 1"#,
+        date
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    let next = sqlx::query_as!(
+        IssueLink,
+        r#"SELECT
+    slug,
+    issue_date,
+    display_name
+FROM issue
+WHERE status = "publish"
+AND issue_date > ?
+ORDER BY issue_date
+LIMIT 1"#,
+        date
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(AdjacentIssues { previous, next })
+}
+
+/// Previous and next issue links, shown at the
+/// bottom of an issue
+#[component]
+pub fn IssueNav(date: time::Date) -> impl IntoView {
+    let adjacent = Resource::new_blocking(
+        move || date,
+        fetch_adjacent_issues,
+    );
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || {
+                adjacent
+                    .get()
+                    .and_then(Result::ok)
+                    .map(|AdjacentIssues { previous, next }| {
+                        view! {
+                            <nav class="mt-12 flex justify-between gap-4 text-sm font-bold leading-6 text-ctp-pink">
+                                <div>
+                                    {previous
+                                        .map(|issue| {
+                                            view! {
+                                                <a href=format!(
+                                                    "/issue/{}",
+                                                    issue.slug,
+                                                )>"← " {issue.display_name}</a>
+                                            }
+                                        })}
+                                </div>
+                                <div class="text-right">
+                                    {next
+                                        .map(|issue| {
+                                            view! {
+                                                <a href=format!(
+                                                    "/issue/{}",
+                                                    issue.slug,
+                                                )>{issue.display_name} " →"</a>
+                                            }
+                                        })}
+                                </div>
+                            </nav>
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    fn link(issue_date: time::Date) -> IssueLink {
+        IssueLink {
+            slug: format!("{issue_date}-slug"),
+            issue_date,
+            display_name: issue_date.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_issues_by_month() {
+        let months = group_by_month(vec![
+            link(date!(2024 - 01 - 29)),
+            link(date!(2024 - 02 - 05)),
+            link(date!(2024 - 02 - 12)),
+            link(date!(2024 - 04 - 01)),
+        ]);
+        assert_eq!(
+            months
+                .iter()
+                .map(|m| (m.month, m.issues.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                (time::Month::January, 1),
+                (time::Month::February, 2),
+                (time::Month::April, 1),
+            ]
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn years_time_cant_represent_are_rejected() {
+        assert_eq!(
+            year_range(2024),
+            Some((
+                date!(2024 - 01 - 01),
+                date!(2025 - 01 - 01)
+            ))
+        );
+        assert_eq!(year_range(9999), None);
+        assert_eq!(year_range(i32::MAX), None);
+    }
+
+    #[test]
+    fn empty_year_has_no_months() {
+        assert!(group_by_month(vec![]).is_empty());
+    }
+}
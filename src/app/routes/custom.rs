@@ -124,7 +124,7 @@ struct Contributor;
 async fn fetch_issue(
     _date: time::Date,
 ) -> Result<Issue, leptos::server_fn::ServerFnError> {
-    use crate::markdown::compile;
+    use crate::markdown::{compile, TrustLevel};
     let crates = vec![CrateRelease {
             title: "Hexx 0.13.0".to_string(),
             description: compile("hexx is an hexagonal utility library made with bevy integration in mind
@@ -133,7 +133,7 @@ What's new:
 * A new sprite sheet example
 * A lot of improvements in mesh generation, especially with procedural UV coordinates
 * Quality of life improvements
-* Fixes !"),
+* Fixes !", TrustLevel::Admin),
             url: "https://discord.com/channels/691052431525675048/918591326096850974/1202285063358648350".to_string(),
             images: vec!["/test_one.png".to_string(), "/test_two.png".to_string(), "/test_three.png".to_string()],
         },
@@ -156,7 +156,7 @@ There are three things in this to pay attention to
 and a quote from a contributor
 
 > Some Quote
-> - [Contributor Person](https://github.com/bevyengine/bevy)"),
+> - [Contributor Person](https://github.com/bevyengine/bevy)", TrustLevel::Admin),
         showcases: vec![],
         crates,
         pull_requests: vec![],
@@ -1,7 +1,9 @@
 use crate::app::components::Container;
 use crate::app::issue::PROSE;
+use crate::site_config::SiteConfig;
 use leptos::{either::Either, prelude::*};
 use leptos_meta::*;
+use leptos_router::hooks::use_query_map;
 use serde::{Deserialize, Serialize};
 use std::ops::Not;
 
@@ -97,9 +99,12 @@ fn IssueEntry(issue: IssueShort) -> impl IntoView {
 
 #[component]
 pub fn Home() -> impl IntoView {
+    let config =
+        use_context::<SiteConfig>().unwrap_or_default();
+    let query = use_query_map();
     let issues = Resource::new_blocking(
-        move || {},
-        |_| fetch_issues(),
+        move || query.with(|query| query.get("before")),
+        fetch_issues,
     );
 
     view! {
@@ -110,9 +115,9 @@ pub fn Home() -> impl IntoView {
                 content="What happened this week in the Bevy Game Engine ecosystem"
             />
 
-            <Link rel="canonical" href="https://thisweekinbevy.com"/>
+            <Link rel="canonical" href=config.site_url.join("/")/>
             <Meta property="og:type" content="website"/>
-            <Meta property="og:url" content="https://thisweekinbevy.com"/>
+            <Meta property="og:url" content=config.site_url.join("/")/>
             <Meta
                 property="og:image"
                 content="https://res.cloudinary.com/dilgcuzda/image/upload/v1708310121/thisweekinbevy/this-week-in-bevyopengraph-light_zwqzqz.png"
@@ -143,14 +148,44 @@ pub fn Home() -> impl IntoView {
                         .get()
                         .map(|data| match data {
                             Err(_e) => Either::Left(view! { <div></div> }),
-                            Ok(issues) => {
+                            Ok(page) => {
+                                let archive_year = page
+                                    .issues
+                                    .first()
+                                    .and_then(|issue| issue.issue_date)
+                                    .map(|date| date.year());
                                 Either::Right(view! {
                                     <div class="divide-y-4 divide-ctp-mantle lg:border-y-4 lg:border-ctp-mantle">
-                                        {issues
+                                        {page
+                                            .issues
                                             .into_iter()
                                             .map(|issue| view! { <IssueEntry issue=issue/> })
                                             .collect::<Vec<_>>()}
                                     </div>
+                                    <Container>
+                                        <nav class="mt-8 flex justify-between text-sm font-bold leading-6 text-ctp-pink">
+                                            {query
+                                                .with(|query| query.get("before"))
+                                                .map(|_| view! { <a href="/">"← Latest issues"</a> })}
+                                            {archive_year
+                                                .map(|year| {
+                                                    view! {
+                                                        <a href=format!(
+                                                            "/archive/{year}",
+                                                        )>"Browse the " {year} " archive"</a>
+                                                    }
+                                                })}
+                                            {page
+                                                .next_cursor
+                                                .map(|before| {
+                                                    view! {
+                                                        <a href=format!(
+                                                            "/?before={before}",
+                                                        )>"Older issues →"</a>
+                                                    }
+                                                })}
+                                        </nav>
+                                    </Container>
                                 })
                             }
                         })
@@ -166,10 +201,11 @@ pub fn Home() -> impl IntoView {
 struct SqlIssueShort {
     pub id: Vec<u8>,
     pub slug: String,
-    pub issue_date: Option<time::Date>,
+    pub issue_date: time::Date,
     pub display_name: String,
     pub description: String,
     pub youtube_id: String,
+    pub status: String,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -201,7 +237,7 @@ fn markdown_trim(input: &str) -> nom::IResult<&str, &str> {
 #[cfg(feature = "ssr")]
 impl From<SqlIssueShort> for IssueShort {
     fn from(value: SqlIssueShort) -> Self {
-        use crate::markdown::compile;
+        use crate::markdown::{compile, TrustLevel};
         let id_str =
             rusty_ulid::Ulid::try_from(value.id.as_slice())
                 .expect(
@@ -213,23 +249,148 @@ impl From<SqlIssueShort> for IssueShort {
         IssueShort {
             id: id_str.to_string(),
             slug: value.slug,
-            issue_date: value.issue_date,
+            issue_date: Some(value.issue_date),
             display_name: value.display_name,
-            description: compile(summary),
+            description: compile(
+                summary,
+                TrustLevel::Admin,
+            ),
             youtube_id: value.youtube_id,
         }
     }
 }
 
+/// How many issues are shown on each page of the
+/// homepage
+#[cfg(feature = "ssr")]
+const PAGE_SIZE: usize = 10;
+
+/// A page of issues, newest first
+#[derive(Deserialize, Serialize, Clone)]
+pub struct IssuePage {
+    pub issues: Vec<IssueShort>,
+    /// pass as `before` to get the next (older)
+    /// page. `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// Where a page ends: the sort key of its last
+/// issue, written as `status.issue_date.id`.
+/// Issues that share a date are ordered by id,
+/// so none are skipped between pages.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageCursor {
+    status: String,
+    issue_date: time::Date,
+    id: rusty_ulid::Ulid,
+}
+
+#[cfg(feature = "ssr")]
+impl PageCursor {
+    fn of(issue: &SqlIssueShort) -> Self {
+        PageCursor {
+            status: issue.status.clone(),
+            issue_date: issue.issue_date,
+            id: rusty_ulid::Ulid::try_from(
+                issue.id.as_slice(),
+            )
+            .expect("expect valid ids from the database"),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl std::fmt::Display for PageCursor {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            self.status, self.issue_date, self.id
+        )
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl std::str::FromStr for PageCursor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '.');
+        let status = parts.next().ok_or(())?;
+        let issue_date = parts
+            .next()
+            .and_then(crate::issue_date::parse_issue_date)
+            .ok_or(())?;
+        let id = parts
+            .next()
+            .ok_or(())?
+            .parse()
+            .map_err(|_| ())?;
+        Ok(PageCursor {
+            status: status.to_string(),
+            issue_date,
+            id,
+        })
+    }
+}
+
+/// Split off the extra row fetched past the end
+/// of the page, returning the cursor for the
+/// next page if there is one.
+#[cfg(feature = "ssr")]
+fn paginate<T, C>(
+    mut rows: Vec<T>,
+    page_size: usize,
+    cursor: impl Fn(&T) -> C,
+) -> (Vec<T>, Option<C>) {
+    if rows.len() <= page_size {
+        return (rows, None);
+    }
+    rows.truncate(page_size);
+    let next = rows.last().map(cursor);
+    (rows, next)
+}
+
+/// `before` is the `next_cursor` of the previous
+/// page. Only issues that sort after it are
+/// returned.
 #[server]
 pub async fn fetch_issues(
-) -> Result<Vec<IssueShort>, ServerFnError> {
+    before: Option<String>,
+) -> Result<IssuePage, ServerFnError> {
     let pool = crate::sql::pool()?;
 
+    let before = before
+        .map(|before| {
+            before.parse::<PageCursor>().map_err(|_| {
+                ServerFnError::<
+                    crate::app::server_fn::error::NoCustomError,
+                >::ServerError(
+                    "invalid cursor".to_string()
+                )
+            })
+        })
+        .transpose()?;
+    let before_status =
+        before.as_ref().map(|cursor| cursor.status.clone());
+    let before_date =
+        before.as_ref().map(|cursor| cursor.issue_date);
+    let before_id = before
+        .as_ref()
+        .map(|cursor| <[u8; 16]>::from(cursor.id).to_vec());
+    // fetch one extra row to know if there's
+    // another page
+    let limit = PAGE_SIZE as i64 + 1;
+
     let issues: Vec<SqlIssueShort> =
         match crate::sql::with_admin_access() {
             Ok(_) => {
                 // logged in as admin, serve all issues
+                // with drafts first
                 sqlx::query_as!(
                     SqlIssueShort,
                     "
@@ -239,9 +400,22 @@ SELECT
     issue_date,
     display_name,
     description,
-    youtube_id
+    youtube_id,
+    status
 FROM issue
-ORDER BY status, issue_date DESC"
+WHERE (
+    ? IS NULL
+    OR status > ?
+    OR (status = ? AND (issue_date, id) < (?, ?))
+)
+ORDER BY status, issue_date DESC, id DESC
+LIMIT ?",
+                    before_status,
+                    before_status,
+                    before_status,
+                    before_date,
+                    before_id,
+                    limit
                 )
                 .fetch_all(&pool)
                 .await?
@@ -257,15 +431,90 @@ SELECT
     issue_date,
     display_name,
     description,
-    youtube_id
+    youtube_id,
+    status
 FROM issue
 WHERE status = "publish"
-ORDER BY status, issue_date DESC"#
+AND (? IS NULL OR (issue_date, id) < (?, ?))
+ORDER BY issue_date DESC, id DESC
+LIMIT ?"#,
+                    before_date,
+                    before_date,
+                    before_id,
+                    limit
                 )
                 .fetch_all(&pool)
                 .await?
             }
         };
 
-    Ok(issues.into_iter().map(IssueShort::from).collect())
+    let (issues, next_cursor) =
+        paginate(issues, PAGE_SIZE, PageCursor::of);
+
+    Ok(IssuePage {
+        issues: issues
+            .into_iter()
+            .map(IssueShort::from)
+            .collect(),
+        next_cursor: next_cursor
+            .map(|cursor| cursor.to_string()),
+    })
+}
+
+#[cfg(feature = "ssr")]
+#[cfg(test)]
+mod tests_server {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn last_page_has_no_cursor() {
+        let rows = vec![date!(2024 - 02 - 12)];
+        let (rows, next) = paginate(rows, 2, |d| *d);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn last_row_on_the_page_becomes_the_cursor() {
+        let rows = vec![
+            date!(2024 - 02 - 19),
+            date!(2024 - 02 - 12),
+            date!(2024 - 02 - 05),
+        ];
+        let (rows, next) = paginate(rows, 2, |d| *d);
+        assert_eq!(
+            rows,
+            vec![
+                date!(2024 - 02 - 19),
+                date!(2024 - 02 - 12)
+            ]
+        );
+        // the next page starts after the last
+        // issue shown
+        assert_eq!(next, Some(date!(2024 - 02 - 12)));
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        let cursor = PageCursor {
+            status: "draft".to_string(),
+            issue_date: date!(2024 - 02 - 12),
+            id: rusty_ulid::Ulid::generate(),
+        };
+        assert_eq!(cursor.to_string().parse(), Ok(cursor));
+        for invalid in [
+            "",
+            "2024-02-12",
+            "publish.2024-02-12",
+            "publish.2024-02-12.not-a-ulid",
+            "publish.yesterday.01HPN5MW1W1XZ1R0V5CN2B5VB4",
+        ] {
+            assert_eq!(
+                invalid.parse::<PageCursor>(),
+                Err(()),
+                "{invalid}"
+            );
+        }
+    }
 }
@@ -1,20 +1,48 @@
 use crate::app::components::{
-    Container, DescriptionColor, Divider,
-    DividerWithDescription,
+    CalloutInfo, CalloutType, Container, DescriptionColor,
+    Divider, DividerWithDescription,
+};
+#[cfg(feature = "ssr")]
+use crate::{
+    contributors::SqlContributor,
+    issue_content::{
+        SqlMergedPullRequest, SqlNewGhIssue, SqlNewPr,
+    },
 };
 use itertools::Itertools;
-use leptos::{either::{Either, EitherOf4}, prelude::*};
+use leptos::{either::Either, prelude::*};
 use leptos_meta::*;
-use leptos_router::hooks::use_params_map;
+use leptos_router::hooks::{use_params_map, use_query_map};
 use serde::{Deserialize, Serialize};
-#[cfg(feature = "ssr")]
-use sqlx::types::Json;
 use std::ops::Not;
 mod cards;
+use super::archive::IssueNav;
+use crate::{
+    contributors::Contributor,
+    error_template::{AppError, ErrorTemplate},
+    site_config::SiteConfig,
+};
 use cards::*;
 
 pub const PROSE: &str = r#"prose text-ctp-text dark:prose-strong:text-white prose-code:text-ctp-text prose-a:text-ctp-sky hover:prose-a:text-ctp-blue prose-blockquote:text-ctp-text [&>h2]:leading-7 [&>h2]:text-ctp-text [&>h3]:text-ctp-text [&>h2]:pl-4 [&>ul]:mt-6 [&>ul]:list-['⮡\20'] [&>ul]:pl-5"#;
 
+/// The `id` of an item on the issue page, so
+/// search results can link straight to it.
+///
+/// `anchor_id("showcase", "Hexagon Maps!")` is
+/// `showcase-hexagon-maps`
+pub fn anchor_id(type_name: &str, title: &str) -> String {
+    let mut anchor = type_name.replace('_', "-");
+    for word in title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        anchor.push('-');
+        anchor.push_str(&word.to_lowercase());
+    }
+    anchor
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Issue {
     /// The title of the issue is technically the
@@ -27,6 +55,9 @@ pub struct Issue {
     header_image: String,
     youtube_id: String,
     issue_date: time::Date,
+    /// Drafts are only shown to admins and
+    /// through signed preview links
+    published: bool,
     /// What is this issue about? Is there
     /// anything notable worth mentioning or
     /// making sure people are aware of?
@@ -100,37 +131,6 @@ struct NewIssue {
     author_url: String,
 }
 
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlNewGhIssue {
-    title: String,
-    url: String,
-    gh_created_at: String,
-    author: String,
-    author_url: String,
-}
-
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlNewPr {
-    github_id: String,
-    title: String,
-    url: String,
-    gh_created_at: String,
-    author: String,
-    author_url: String,
-}
-
 #[derive(Clone, Serialize, Deserialize)]
 struct CrateRelease {
     title: String,
@@ -141,22 +141,6 @@ struct CrateRelease {
     images: Vec<ImgDataTransformed>,
 }
 
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlCrateRelease {
-    title: String,
-    url: String,
-    discord_url: String,
-    description: String,
-    posted_date: Option<String>,
-    images: Option<Vec<ImgData>>,
-}
-
 #[derive(Clone, Serialize, Deserialize)]
 struct Devlog {
     title: String,
@@ -167,22 +151,6 @@ struct Devlog {
     images: Vec<ImgDataTransformed>,
 }
 
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlDevlog {
-    title: String,
-    post_url: String,
-    video_url: String,
-    discord_url: String,
-    description: String,
-    images: Option<Vec<ImgData>>,
-}
-
 #[derive(Clone, Serialize, Deserialize)]
 struct Educational {
     title: String,
@@ -193,22 +161,6 @@ struct Educational {
     images: Vec<ImgDataTransformed>,
 }
 
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlEducational {
-    title: String,
-    post_url: String,
-    video_url: String,
-    discord_url: String,
-    description: String,
-    images: Option<Vec<ImgData>>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Showcase {
     title: String,
@@ -229,72 +181,6 @@ struct MergedPullRequest {
     author_url: String,
 }
 
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct SqlMergedPullRequest {
-    github_id: String,
-    title: String,
-    url: String,
-    merged_at_date: String,
-    author: String,
-    author_url: String,
-}
-
-#[derive(Clone, Serialize, Deserialize)]
-struct Contributor;
-
-#[cfg(feature = "ssr")]
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-struct SqlIssue {
-    issue_date: time::Date,
-    slug: String,
-    cloudinary_public_id: String,
-    display_name: String,
-    description: String,
-    youtube_id: String,
-    showcases: Option<sqlx::types::Json<Vec<ShowcaseData>>>,
-    crate_releases:
-        Option<sqlx::types::Json<Vec<SqlCrateRelease>>>,
-    devlogs: Option<sqlx::types::Json<Vec<SqlDevlog>>>,
-    educationals:
-        Option<sqlx::types::Json<Vec<SqlEducational>>>,
-    new_github_issues:
-        Option<sqlx::types::Json<Vec<SqlNewGhIssue>>>,
-    new_pull_requests:
-        Option<sqlx::types::Json<Vec<SqlNewPr>>>,
-    merged_pull_requests: Option<
-        sqlx::types::Json<Vec<SqlMergedPullRequest>>,
-    >,
-}
-
-#[cfg(feature = "ssr")]
-#[derive(
-    Debug,
-    serde::Deserialize,
-    serde::Serialize,
-    sqlx::FromRow,
-)]
-struct ShowcaseData {
-    title: String,
-    url: String,
-    discord_url: String,
-    description: String,
-    images: Option<Vec<ImgData>>,
-}
-
-#[cfg(feature = "ssr")]
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-struct ImgData {
-    id: String,
-    description: String,
-    cloudinary_public_id: String,
-}
-
 #[derive(Debug, Deserialize, Serialize, Clone)]
 struct ImgDataTransformed {
     id: String,
@@ -302,42 +188,61 @@ struct ImgDataTransformed {
     url: String,
 }
 
-#[server]
-async fn fetch_issue(
+/// The issue for `date`. Drafts are `None`
+/// unless `drafts` is set.
+#[cfg(feature = "ssr")]
+async fn load_issue(
     date: time::Date,
+    drafts: bool,
 ) -> Result<Option<Issue>, leptos::prelude::ServerFnError> {
-    use crate::markdown::compile;
-    use cloudinary::transformation::{
-        resize_mode::ResizeMode::ScaleByWidth,
-        Image as CImage, Transformations::Resize,
+    use crate::{
+        images::ImageFormat,
+        markdown::{compile, TrustLevel},
     };
     use data_encoding::BASE64;
 
     let pool = crate::sql::pool()?;
+    let images = crate::sql::site_config().image_backend();
 
-    let showcase_issue = sqlx::query_file_as!(
-        SqlIssue,
-        "src/app/routes/issue__showcase.sql",
-        date
+    let showcase_issue = crate::issue_content::fetch_issue(
+        &pool, date, drafts,
     )
-    .fetch_optional(&pool)
     .await
-    // .inspect(|v| {tracing::info!(?v);})
     .inspect_err(|e| {
         tracing::error!(?e);
     })?;
 
+    let contributors: Vec<SqlContributor> =
+        sqlx::query_as!(
+            SqlContributor,
+            "SELECT
+    issue__contributors.github_username,
+    issue__contributors.display_name,
+    issue__contributors.role
+FROM issue__contributors
+INNER JOIN issue
+  ON issue.id = issue__contributors.issue_id
+WHERE issue.issue_date = ?
+ORDER BY issue__contributors.created_at",
+            date
+        )
+        .fetch_all(&pool)
+        .await
+        .inspect_err(|e| {
+            tracing::error!(?e);
+        })?;
+
     Ok(showcase_issue.map(|issue| {
-        
         let showcases = issue.showcases
 .map(|json| json.0)
 .unwrap_or_default()
-.into_iter().map(|showcase_data_2| {
+.into_iter()
+.map(|showcase_data_2| {
     Showcase {
         title: showcase_data_2.title,
         url: showcase_data_2.url,
         discord_url: showcase_data_2.discord_url,
-        description: compile(&showcase_data_2.description),
+        description: compile(&showcase_data_2.description, TrustLevel::of_submitter(showcase_data_2.submitted_by.as_deref(), showcase_data_2.submitter_role.as_deref())),
         images: showcase_data_2.images.unwrap_or_default().into_iter()
         .map(|img_data| {
  
@@ -347,12 +252,10 @@ async fn fetch_issue(
             .expect(
                 "expect valid ids from the database",
             );
-            let image = CImage::new("dilgcuzda".into(), img_data.cloudinary_public_id.into())
-                .add_transformation(Resize(ScaleByWidth{ width:600, ar: None, liquid:None }));
             ImgDataTransformed {
                 id: img_ulid.to_string(),
                 description: img_data.description,
-                url: image.to_string()
+                url: images.url(&img_data.cloudinary_public_id, Some(600), None)
             }
         })
         .collect()
@@ -362,12 +265,13 @@ async fn fetch_issue(
 let crate_releases = issue.crate_releases
 .map(|json| json.0)
 .unwrap_or_default()
-.into_iter().map(|value| {
+.into_iter()
+.map(|value| {
     CrateRelease {
         title: value.title,
         url: value.url,
         discord_url: value.discord_url,
-        description: compile(&value.description),
+        description: compile(&value.description, TrustLevel::of_submitter(value.submitted_by.as_deref(), value.submitter_role.as_deref())),
         posted_date: value.posted_date,
         images: value.images.unwrap_or_default().into_iter()
         .map(|img_data| {
@@ -376,12 +280,10 @@ let crate_releases = issue.crate_releases
             .expect(
                 "expect valid ids from the database",
             );
-            let image = CImage::new("dilgcuzda".into(), img_data.cloudinary_public_id.into())
-                .add_transformation(Resize(ScaleByWidth{ width:600, ar: None, liquid:None }));
             ImgDataTransformed {
                 id: img_ulid.to_string(),
                 description: img_data.description,
-                url: image.to_string()
+                url: images.url(&img_data.cloudinary_public_id, Some(600), None)
             }
         })
         .collect()
@@ -391,13 +293,14 @@ let crate_releases = issue.crate_releases
 let devlogs = issue.devlogs
 .map(|json| json.0)
 .unwrap_or_default()
-.into_iter().map(|value| {
+.into_iter()
+.map(|value| {
     Devlog {
         title: value.title,
         post_url: value.post_url,
         video_url: value.video_url,
         discord_url: value.discord_url,
-        description: compile(&value.description),
+        description: compile(&value.description, TrustLevel::of_submitter(value.submitted_by.as_deref(), value.submitter_role.as_deref())),
         images: value.images.unwrap_or_default().into_iter()
         .map(|img_data| {
             let base_id = BASE64.decode(img_data.id.as_bytes()).expect("a valid id in base64 format");
@@ -405,12 +308,10 @@ let devlogs = issue.devlogs
             .expect(
                 "expect valid ids from the database",
             );
-            let image = CImage::new("dilgcuzda".into(), img_data.cloudinary_public_id.into())
-                .add_transformation(Resize(ScaleByWidth{ width:600, ar: None, liquid:None }));
             ImgDataTransformed {
                 id: img_ulid.to_string(),
                 description: img_data.description,
-                url: image.to_string()
+                url: images.url(&img_data.cloudinary_public_id, Some(600), None)
             }
         })
         .collect()
@@ -420,13 +321,14 @@ let devlogs = issue.devlogs
 let educationals = issue.educationals
 .map(|json| json.0)
 .unwrap_or_default()
-.into_iter().map(|value| {
+.into_iter()
+.map(|value| {
     Educational {
         title: value.title,
         post_url: value.post_url,
         video_url: value.video_url,
         discord_url: value.discord_url,
-        description: compile(&value.description),
+        description: compile(&value.description, TrustLevel::of_submitter(value.submitted_by.as_deref(), value.submitter_role.as_deref())),
         images: value.images.unwrap_or_default().into_iter()
         .map(|img_data| {
             let base_id = BASE64.decode(img_data.id.as_bytes()).expect("a valid id in base64 format");
@@ -434,12 +336,10 @@ let educationals = issue.educationals
             .expect(
                 "expect valid ids from the database",
             );
-            let image = CImage::new("dilgcuzda".into(), img_data.cloudinary_public_id.into())
-                .add_transformation(Resize(ScaleByWidth{ width:600, ar: None, liquid:None }));
             ImgDataTransformed {
                 id: img_ulid.to_string(),
                 description: img_data.description,
-                url: image.to_string()
+                url: images.url(&img_data.cloudinary_public_id, Some(600), None)
             }
         })
         .collect()
@@ -501,22 +401,23 @@ author_url
 
 }).collect();
 
-let opengraph_image = CImage::new("dilgcuzda".into(), (*issue.cloudinary_public_id).into());
-let header_image = CImage::new("dilgcuzda".into(), issue.cloudinary_public_id.into());
+let opengraph_image = images.url(&issue.cloudinary_public_id, None, Some(ImageFormat::Png));
+let header_image = images.url(&issue.cloudinary_public_id, None, None);
 
         Issue {
         title: issue.display_name,
         issue_date: issue.issue_date,
         slug: issue.slug,
         youtube_id: issue.youtube_id,
-        opengraph_image: opengraph_image.to_string().replace(".avif", ".png"),
-        header_image: header_image.to_string(),
-        description: compile(&issue.description),
+        published: issue.status == "publish",
+        opengraph_image,
+        header_image,
+        description: compile(&issue.description, TrustLevel::Admin),
         showcases,
         crate_releases,
         devlogs,
         merged_pull_requests,
-        contributors: vec![],
+        contributors: contributors.into_iter().map(Contributor::from).collect(),
         educationals,
         new_pull_requests,
         new_github_issues,
@@ -524,37 +425,252 @@ let header_image = CImage::new("dilgcuzda".into(), issue.cloudinary_public_id.in
     }))
 }
 
-#[component]
-pub fn Issue() -> impl IntoView {
-    let params = use_params_map();
+/// The issue for `date`, if it has been published
+#[server]
+async fn fetch_issue(
+    date: time::Date,
+) -> Result<Option<Issue>, leptos::prelude::ServerFnError> {
+    load_issue(date, false).await
+}
 
+/// The issue for `date` even if it's a draft.
+/// Admins can preview any issue, anyone else
+/// needs a signed preview link.
+#[server]
+async fn fetch_issue_preview(
+    date: time::Date,
+    expires: Option<i64>,
+    signature: Option<String>,
+) -> Result<Option<Issue>, leptos::prelude::ServerFnError> {
+    use crate::preview::PreviewKey;
+    use leptos::server_fn::error::NoCustomError;
+
+    match expires.zip(signature) {
+        Some((expires, signature)) => {
+            PreviewKey::from_env()
+                .and_then(|key| {
+                    key.verify(
+                        date,
+                        expires,
+                        &signature,
+                        time::OffsetDateTime::now_utc(),
+                    )
+                })
+                .map_err(|e| {
+                    ServerFnError::<NoCustomError>::ServerError(
+                        e.to_string(),
+                    )
+                })?;
+        }
+        None => {
+            crate::sql::with_admin_access()?;
+        }
+    }
+    load_issue(date, true).await
+}
+
+/// A preview link for the issue at `slug` that
+/// can be shared with people who can't log in
+#[server]
+async fn create_preview_link(
+    slug: String,
+    days: u32,
+) -> Result<String, leptos::prelude::ServerFnError> {
+    use crate::preview::PreviewKey;
+    use leptos::server_fn::error::NoCustomError;
+
+    crate::sql::with_admin_access()?;
+    let date =
+        crate::issue_date::parse_issue_date_from_slug(&slug)
+            .ok_or_else(|| {
+                ServerFnError::<NoCustomError>::ServerError(
+                    "expected a slug starting with the issue date"
+                        .to_string(),
+                )
+            })?;
+    let path = PreviewKey::from_env()
+        .and_then(|key| {
+            key.signed_path(
+                &slug,
+                date,
+                days,
+                time::OffsetDateTime::now_utc(),
+            )
+        })
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(
+                e.to_string(),
+            )
+        })?;
+    Ok(crate::sql::site_config().site_url.join(&path))
+}
+
+/// The date from a `:slug` route param
+fn issue_date_param() -> Memo<Option<time::Date>> {
+    let params = use_params_map();
     // slug id only cares about the date, which is the
     // unique id. the rest of the slug can be
     // changed any time.
     // 2024-02-11-the-one-before-bevy-0-13
-    let issue = Resource::new_blocking(
-        move || {
-            params.with(|p| {
-                p.get("slug").and_then(|slug|
-                crate::issue_date::parse_issue_date_from_slug(&slug)
-            )
+    Memo::new(move |_| {
+        params.with(|p| {
+            p.get("slug").and_then(|slug| {
+                crate::issue_date::parse_issue_date_from_slug(
+                    &slug,
+                )
             })
-        },
+        })
+    })
+}
+
+#[component]
+pub fn Issue() -> impl IntoView {
+    let date = issue_date_param();
+    let issue = Resource::new_blocking(
+        move || date.get(),
         |date| async move {
             let date = date?;
 
             fetch_issue(date).await.ok().flatten()
         },
     );
+    view! { <IssuePage issue/> }
+}
+
+/// A draft as it will look once it's published.
+/// Admins also get a form to create a link for
+/// people who can't log in.
+#[component]
+pub fn Preview() -> impl IntoView {
+    let date = issue_date_param();
+    let params = use_params_map();
+    let query = use_query_map();
+    let signed = move || {
+        query.with(|q| {
+            (
+                q.get("expires").and_then(|expires| {
+                    expires.parse().ok()
+                }),
+                q.get("signature"),
+            )
+        })
+    };
+    let issue = Resource::new_blocking(
+        move || (date.get(), signed()),
+        |(date, (expires, signature))| async move {
+            let date = date?;
+
+            fetch_issue_preview(date, expires, signature)
+                .await
+                .ok()
+                .flatten()
+        },
+    );
+    view! {
+        <Meta name="robots" content="noindex"/>
+        <Suspense>
+            {move || {
+                let is_admin = signed().1.is_none()
+                    && issue.with(|issue| matches!(issue, Some(Some(_))));
+                is_admin
+                    .then(|| {
+                        let slug = params.with(|p| p.get("slug").unwrap_or_default());
+                        view! { <ShareLink slug/> }
+                    })
+            }}
+        </Suspense>
+        <IssuePage issue preview=true/>
+    }
+}
+
+/// Links are created with a POST, so following or
+/// prefetching a url can't create one
+#[island]
+fn ShareLink(slug: String) -> impl IntoView {
+    let create_link: ServerAction<CreatePreviewLink> =
+        ServerAction::new();
+
+    view! {
+        <div class="mx-auto mt-4 max-w-xl rounded-md bg-blue-50 px-4 py-3 text-sm leading-6 text-gray-900">
+            <ActionForm action=create_link attr:class="flex items-center gap-x-3">
+                <input type="hidden" name="slug" value=slug/>
+                <label for="days">"Share a link that works for"</label>
+                <input
+                    type="number"
+                    name="days"
+                    id="days"
+                    min="1"
+                    max="30"
+                    value="7"
+                    class="w-16 rounded-md border-0 py-1 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300"
+                />
+                "days"
+                <button
+                    type="submit"
+                    class="rounded-md bg-blue-600 px-3 py-1 font-semibold text-white shadow-sm hover:bg-blue-500"
+                >
+                    "Create link"
+                </button>
+            </ActionForm>
+            {move || {
+                create_link
+                    .value()
+                    .get()
+                    .map(|link| match link {
+                        Ok(url) => {
+                            Either::Left(view! {
+                                <input
+                                    type="text"
+                                    readonly
+                                    value=url
+                                    class="mt-2 block w-full rounded-md border-0 py-1 text-gray-900 ring-1 ring-inset ring-gray-300"
+                                />
+                            })
+                        }
+                        Err(e) => {
+                            Either::Right(view! { <p class="mt-2 text-red-700">{e.to_string()}</p> })
+                        }
+                    })
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn IssuePage(
+    issue: Resource<Option<Issue>>,
+    /// Marks the page as a preview, which may not
+    /// be what's published
+    #[prop(optional)]
+    preview: bool,
+) -> impl IntoView {
+    let config =
+        use_context::<SiteConfig>().unwrap_or_default();
     view! {
         <Suspense fallback=move || {
             view! { <p>"Loading..."</p> }
         }>
             {move || match issue.get() {
-                None | Some(None) => Either::Left(view! { <article>"404"</article> }),
+                None | Some(None) => {
+                    let mut outside_errors = Errors::default();
+                    outside_errors.insert_with_default_key(AppError::NotFound);
+                    Either::Left(view! { <ErrorTemplate outside_errors/> })
+                }
                 Some(Some(issue)) => {
                     Either::Right(view! {
                         <article class="py-16 lg:py-16">
+                            {preview
+                                .then(|| {
+                                    view! {
+                                        <p class="mb-8 bg-ctp-yellow py-2 text-center text-sm font-semibold text-ctp-base">
+                                            {if issue.published {
+                                                "Preview of a published issue"
+                                            } else {
+                                                "Draft preview: this issue hasn't been published"
+                                            }}
+                                        </p>
+                                    }
+                                })}
                             <Title text=issue.title.clone()/>
                             <Meta
                                 name="description"
@@ -567,11 +683,11 @@ pub fn Issue() -> impl IntoView {
                             <Meta property="og:type" content="article"/>
                             <Meta
                                 property="og:url"
-                                content=format!("https://thisweekinbevy.com/issue/{}", issue.slug)
+                                content=config.site_url.join(&format!("/issue/{}", issue.slug))
                             />
                             <Link
                                 rel="canonical"
-                                href=format!("https://thisweekinbevy.com/issue/{}", issue.slug)
+                                href=config.site_url.join(&format!("/issue/{}", issue.slug))
                             />
                             <Meta property="og:image" content=issue.opengraph_image.clone()/>
                             <Meta name="twitter:card" content="summary_large_image"/>
@@ -663,8 +779,6 @@ pub fn Issue() -> impl IntoView {
                                 {issue
                                     .crate_releases
                                     .into_iter()
-                                    .sorted_by_key(|cr| cr.posted_date.clone())
-                                    .rev()
                                     .map(|crate_release| {
                                         view! {
                                             <SideBySide
@@ -769,6 +883,7 @@ pub fn Issue() -> impl IntoView {
                                                     url=&pull_request.url
                                                     title=&pull_request.title
                                                     author=&pull_request.author
+                                                    anchor=anchor_id("pull_request", &pull_request.title)
                                                 />
                                             }
                                         })
@@ -846,6 +961,37 @@ pub fn Issue() -> impl IntoView {
                                         .collect::<Vec<_>>()}
 
                                 </ul>
+                                {issue
+                                    .contributors
+                                    .is_empty()
+                                    .not()
+                                    .then(|| {
+                                        view! {
+                                            <Divider title="Credits"/>
+                                            <ul role="list" class="mt-6 flex flex-wrap gap-x-8 gap-y-4">
+                                                {issue
+                                                    .contributors
+                                                    .iter()
+                                                    .map(|contributor| {
+                                                        view! {
+                                                            <li class="text-ctp-text">
+                                                                <a
+                                                                    class="font-semibold text-ctp-sky hover:text-ctp-blue"
+                                                                    href=contributor.github_url()
+                                                                >
+                                                                    {contributor.name().to_string()}
+                                                                </a>
+                                                                <span class="ml-2 text-sm text-ctp-overlay1">
+                                                                    {contributor.role.label()}
+                                                                </span>
+                                                            </li>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </ul>
+                                        }
+                                    })}
+                                <IssueNav date=issue.issue_date/>
                             </Container>
                         </article>
                     })
@@ -872,10 +1018,13 @@ fn ActivityListItem(
     #[prop(into)] author: String,
     #[prop(default=ActivityListIcon::Default)]
     icon: ActivityListIcon,
+    /// `id` for linking to this item from search
+    #[prop(optional)]
+    anchor: Option<String>,
 ) -> impl IntoView {
     let is_dependabot = author.starts_with("dependabot");
     view! {
-        <li class="relative flex gap-x-4">
+        <li id=anchor class="relative flex gap-x-4">
             <div class="absolute left-0 top-0 flex w-6 justify-center -bottom-6">
                 <div class="w-px bg-ctp-overlay1"></div>
             </div>
@@ -1288,197 +1437,23 @@ fn URLLink(url: String) -> impl IntoView {
     }
 }
 
-#[allow(dead_code)]
-enum CalloutType {
-    Info,
-    Caution,
-    Warning,
-    Success,
-}
-impl CalloutType {
-    fn bg(&self) -> &str {
-        match self {
-            CalloutType::Info => {
-                "bg-blue-50 dark:bg-blue-950"
-            }
-            CalloutType::Caution => {
-                "bg-yellow-50 dark:bg-yellow-950"
-            }
-            CalloutType::Warning => {
-                "bg-red-50 dark:bg-red-950"
-            }
-            CalloutType::Success => {
-                "bg-green-50 dark:bg-green-950"
-            }
-        }
-    }
-    fn icon(&self) -> &str {
-        match self {
-            CalloutType::Info => "text-ctp-blue",
-            CalloutType::Caution => "text-ctp-yellow",
-            CalloutType::Warning => "text-ctp-red",
-            CalloutType::Success => "text-ctp-green",
-        }
-    }
-    fn icon_svg(&self) -> impl IntoView {
-        match self {
-            CalloutType::Info => EitherOf4::A(view! {
-                <svg
-                    class=format!("h-5 w-5 {}", self.icon())
-                    viewBox="0 0 20 20"
-                    fill="currentColor"
-                    aria-hidden="true"
-                >
-                    <path
-                        fill-rule="evenodd"
-                        d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-7-4a1 1 0 11-2 0 1 1 0 012 0zM9 9a.75.75 0 000 1.5h.253a.25.25 0 01.244.304l-.459 2.066A1.75 1.75 0 0010.747 15H11a.75.75 0 000-1.5h-.253a.25.25 0 01-.244-.304l.459-2.066A1.75 1.75 0 009.253 9H9z"
-                        clip-rule="evenodd"
-                    ></path>
-                </svg>
-            }),
-            CalloutType::Caution => EitherOf4::B(view! {
-                <svg
-                    class=format!("h-5 w-5 {}", self.icon())
-                    viewBox="0 0 20 20"
-                    fill="currentColor"
-                    aria-hidden="true"
-                >
-                    <path
-                        fill-rule="evenodd"
-                        d="M8.485 2.495c.673-1.167 2.357-1.167 3.03 0l6.28 10.875c.673 1.167-.17 2.625-1.516 2.625H3.72c-1.347 0-2.189-1.458-1.515-2.625L8.485 2.495zM10 5a.75.75 0 01.75.75v3.5a.75.75 0 01-1.5 0v-3.5A.75.75 0 0110 5zm0 9a1 1 0 100-2 1 1 0 000 2z"
-                        clip-rule="evenodd"
-                    ></path>
-                </svg>
-            }),
-            CalloutType::Warning => EitherOf4::C(view! {
-                <svg
-                    class=format!("h-5 w-5 {}", self.icon())
-                    viewBox="0 0 20 20"
-                    fill="currentColor"
-                    aria-hidden="true"
-                >
-                    <path
-                        fill-rule="evenodd"
-                        d="M10 18a8 8 0 100-16 8 8 0 000 16zM8.28 7.22a.75.75 0 00-1.06 1.06L8.94 10l-1.72 1.72a.75.75 0 101.06 1.06L10 11.06l1.72 1.72a.75.75 0 101.06-1.06L11.06 10l1.72-1.72a.75.75 0 00-1.06-1.06L10 8.94 8.28 7.22z"
-                        clip-rule="evenodd"
-                    ></path>
-                </svg>
-            }),
-            CalloutType::Success => EitherOf4::D(view! {
-                <svg
-                    class=format!("h-5 w-5 {}", self.icon())
-                    viewBox="0 0 20 20"
-                    fill="currentColor"
-                    aria-hidden="true"
-                >
-                    <path
-                        fill-rule="evenodd"
-                        d="M10 18a8 8 0 100-16 8 8 0 000 16zm3.857-9.809a.75.75 0 00-1.214-.882l-3.483 4.79-1.88-1.88a.75.75 0 10-1.06 1.061l2.5 2.5a.75.75 0 001.137-.089l4-5.5z"
-                        clip-rule="evenodd"
-                    ></path>
-                </svg>
-            }),
-        }
-    }
-    fn title(&self) -> &str {
-        match self {
-            CalloutType::Info => "text-ctp-blue",
-            CalloutType::Caution => "text-ctp-yellow",
-            CalloutType::Warning => "text-ctp-red",
-            CalloutType::Success => "text-ctp-green",
-        }
-    }
-    fn content(&self) -> &str {
-        match self {
-            CalloutType::Info => "text-ctp-blue",
-            CalloutType::Caution => "text-ctp-yellow",
-            CalloutType::Warning => "text-ctp-red",
-            CalloutType::Success => "text-ctp-green",
-        }
-    }
-    fn child_links(&self) -> &str {
-        match self {
-            CalloutType::Info => "[&_a]:font-medium [&_a]:text-blue-700 [&_a]:underline [&_a]:hover:text-blue-600",
-            CalloutType::Caution => "[&_a]:font-medium [&_a]:text-yellow-700 [&_a]:underline [&_a]:hover:text-yellow-600",
-            CalloutType::Warning => "[&_a]:font-medium [&_a]:text-red-700 [&_a]:underline [&_a]:hover:text-red-600",
-            CalloutType::Success => "[&_a]:font-medium [&_a]:text-green-700 [&_a]:underline [&_a]:hover:text-green-600",
-        }
-    }
-}
-
-#[derive(Clone)]
-struct CalloutLink {
-    href: String,
-    label: String,
-}
-
-#[component]
-fn CalloutInfo(
-    r#type: CalloutType,
-    #[prop(into, optional, default = "".to_string())] title: String,
-    #[prop(optional, default = None)] link: Option<
-        CalloutLink,
-    >,
-    children: Children,
-) -> impl IntoView {
-    let has_link = link.is_none();
-    view! {
-        <div class=format!("rounded-md p-4 {}", r#type.bg())>
-            <div class="flex">
-                <div class="flex-shrink-0">
-
-                    {r#type.icon_svg()}
-
-                </div>
-                <div class="ml-3">
-
-                    {title
-                        .trim()
-                        .is_empty()
-                        .not()
-                        .then_some(
-                            view! {
-                                <h3 class=format!(
-                                    "text-sm font-medium {}",
-                                    r#type.title(),
-                                )>{title}</h3>
-                            },
-                        )}
-                    <div class=if has_link {
-                        format!("mt-2 text-sm {} {}", r#type.content(), r#type.child_links())
-                    } else {
-                        format!(
-                            "ml-3 flex-1 md:flex md:justify-between {} {}",
-                            r#type.content(),
-                            r#type.child_links(),
-                        )
-                    }>
-
-                        {children()}
-                        {link
-                            .map(|CalloutLink { href, label }| {
-                                view! {
-                                    <p class="mt-3 text-sm md:ml-6 md:mt-0">
-                                        <a
-                                            href=href
-                                            class="whitespace-nowrap font-medium text-blue-700 hover:text-blue-600"
-                                        >
-                                            {label}
-                                            <span aria-hidden="true">" →"</span>
-                                        </a>
-                                    </p>
-                                }
-                            })}
-
-                    </div>
-                // <div class="mt-4">
-                // <div class="-mx-2 -my-1.5 flex">
-                // <button type="button" class="rounded-md bg-green-50 px-2 py-1.5 text-sm font-medium text-green-800 hover:bg-green-100 focus:outline-none focus:ring-2 focus:ring-green-600 focus:ring-offset-2 focus:ring-offset-green-50">View status</button>
-                // <button type="button" class="ml-3 rounded-md bg-green-50 px-2 py-1.5 text-sm font-medium text-green-800 hover:bg-green-100 focus:outline-none focus:ring-2 focus:ring-green-600 focus:ring-offset-2 focus:ring-offset-green-50">Dismiss</button>
-                // </div>
-                // </div>
-                </div>
-            </div>
-        </div>
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchor_ids() {
+        assert_eq!(
+            anchor_id("showcase", "Hexagon Maps!"),
+            "showcase-hexagon-maps"
+        );
+        assert_eq!(
+            anchor_id(
+                "crate_release",
+                "bevy_ecs_tilemap 0.13"
+            ),
+            "crate-release-bevy-ecs-tilemap-0-13"
+        );
+        assert_eq!(anchor_id("devlog", "  "), "devlog");
     }
 }
@@ -1,4 +1,4 @@
-use super::PROSE;
+use super::{anchor_id, PROSE};
 use crate::app::issue::ImgDataTransformed;
 use leptos::{
     either::{Either, EitherOf8},
@@ -17,8 +17,9 @@ pub fn SideBySide(
     posted_date: Option<String>,
     #[prop(optional)] video_url: String,
 ) -> impl IntoView {
+    let anchor = anchor_id(&type_name, &title);
     view! {
-        <div class="bg-ctp-base">
+        <div id=anchor class="bg-ctp-base">
             <div class="mx-auto px-4 py-16 sm:px-6 sm:py-24 lg:max-w-7xl lg:px-8">
                 <div class="lg:grid lg:grid-cols-7 lg:grid-rows-1 lg:gap-x-8 lg:gap-y-10 xl:gap-x-16">
                     {images
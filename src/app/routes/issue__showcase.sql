@@ -5,6 +5,8 @@ SELECT
     display_name,
     description,
     youtube_id,
+    status,
+    updated_at,
     showcases as "showcases: Json<Vec<ShowcaseData>>",
     crate_releases as "crate_releases: Json<Vec<SqlCrateRelease>>",
     devlogs as "devlogs: Json<Vec<SqlDevlog>>",
@@ -19,6 +21,8 @@ FROM
             issue_id,
             JSON_ARRAYAGG(
                 JSON_OBJECT(
+                    "position",
+                    issue__showcase.position,
                     "title",
                     scase.title,
                     "url",
@@ -28,7 +32,11 @@ FROM
                     "description",
                     scase.description,
                     "images",
-                    scase.images
+                    scase.images,
+                    "submitted_by",
+                    scase.submitted_by,
+                    "submitter_role",
+                    scase.submitter_role
                 )
             ) AS showcases
         FROM
@@ -40,9 +48,12 @@ FROM
                     url,
                     description,
                     discord_url,
-                    images
+                    images,
+                    submitted_by,
+                    github_users.role AS submitter_role
                 from
                     showcase
+                    LEFT JOIN github_users ON github_users.username = showcase.submitted_by
                     LEFT JOIN (
                         SELECT
                             showcase_id,
@@ -71,6 +82,8 @@ FROM
             issue_id,
             JSON_ARRAYAGG(
                 JSON_OBJECT(
+                    "position",
+                    issue__crate_release.position,
                     "title",
                     crelease.title,
                     "url",
@@ -80,7 +93,11 @@ FROM
                     "description",
                     crelease.description,
                     "images",
-                    crelease.images
+                    crelease.images,
+                    "submitted_by",
+                    crelease.submitted_by,
+                    "submitter_role",
+                    crelease.submitter_role
                 )
             ) AS crate_releases
         FROM
@@ -93,9 +110,12 @@ FROM
                     description,
                     posted_date,
                     discord_url,
-                    images
+                    images,
+                    submitted_by,
+                    github_users.role AS submitter_role
                 from
                     crate_release
+                    LEFT JOIN github_users ON github_users.username = crate_release.submitted_by
                     LEFT JOIN (
                         SELECT
                             crate_release_id,
@@ -124,6 +144,8 @@ FROM
             issue_id,
             JSON_ARRAYAGG(
                 JSON_OBJECT(
+                    "position",
+                    issue__devlog.position,
                     "title",
                     dlog.title,
                     "post_url",
@@ -135,7 +157,11 @@ FROM
                     "description",
                     dlog.description,
                     "images",
-                    dlog.images
+                    dlog.images,
+                    "submitted_by",
+                    dlog.submitted_by,
+                    "submitter_role",
+                    dlog.submitter_role
                 )
             ) AS devlogs
         FROM
@@ -148,9 +174,12 @@ FROM
                     video_url,
                     description,
                     discord_url,
-                    images
+                    images,
+                    submitted_by,
+                    github_users.role AS submitter_role
                 from
                     devlog
+                    LEFT JOIN github_users ON github_users.username = devlog.submitted_by
                     LEFT JOIN (
                         SELECT
                             devlog_id,
@@ -179,6 +208,8 @@ FROM
             issue_id,
             JSON_ARRAYAGG(
                 JSON_OBJECT(
+                    "position",
+                    issue__educational.position,
                     "title",
                     edu.title,
                     "post_url",
@@ -190,7 +221,11 @@ FROM
                     "description",
                     edu.description,
                     "images",
-                    edu.images
+                    edu.images,
+                    "submitted_by",
+                    edu.submitted_by,
+                    "submitter_role",
+                    edu.submitter_role
                 )
             ) AS educationals
         FROM
@@ -203,9 +238,12 @@ FROM
                     video_url,
                     description,
                     discord_url,
-                    images
+                    images,
+                    submitted_by,
+                    github_users.role AS submitter_role
                 from
                     educational
+                    LEFT JOIN github_users ON github_users.username = educational.submitted_by
                     LEFT JOIN (
                         SELECT
                             educational_id,
@@ -303,4 +341,9 @@ FROM
             issue_id
     ) AS merged_prs ON merged_prs.issue_id = issue.id
 WHERE
-    issue_date = ?;
\ No newline at end of file
+    (? IS NULL OR issue_date = ?)
+    AND (? OR status = "publish")
+ORDER BY
+    issue_date DESC
+LIMIT
+    ?;
\ No newline at end of file
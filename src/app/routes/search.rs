@@ -0,0 +1,403 @@
+use crate::app::components::{Container, Divider};
+use leptos::{either::Either, prelude::*};
+use leptos_meta::Title;
+use leptos_router::hooks::use_query_map;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use tracing::error;
+
+/// The most results returned for each type
+#[cfg(feature = "ssr")]
+const LIMIT: i64 = 20;
+
+#[component]
+pub fn Search() -> impl IntoView {
+    let query = use_query_map();
+    let q = move || {
+        query.with(|query| {
+            query.get("q").unwrap_or_default()
+        })
+    };
+    let results = Resource::new(q, search);
+
+    view! {
+        <Title text="Search This Week in Bevy"/>
+        <div class="pb-12 pt-16 sm:pb-4 lg:pt-12">
+            <Container>
+                <h1 class="text-2xl font-bold leading-7 text-ctp-text">Search</h1>
+                <form method="get" action="/search" class="mt-6 flex gap-x-2">
+                    <input
+                        type="search"
+                        name="q"
+                        value=q
+                        placeholder="bevy_ecs_tilemap"
+                        aria-label="Search issues"
+                        class="block w-full rounded-md border-0 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 placeholder:text-gray-400 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6"
+                    />
+                    <button
+                        type="submit"
+                        class="rounded-md bg-indigo-600 px-2.5 py-1.5 text-sm font-semibold text-white shadow-sm hover:bg-indigo-500"
+                    >
+                        Search
+                    </button>
+                </form>
+            </Container>
+            <Suspense fallback=move || {
+                view! { <p>"Loading..."</p> }
+            }>
+                {move || {
+                    results
+                        .get()
+                        .map(|results| match results {
+                            Err(e) => Either::Left(view! { <p>{e.to_string()}</p> }),
+                            Ok(results) => Either::Right(view! { <SearchResultList results/> }),
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn SearchResultList(
+    results: SearchResults,
+) -> impl IntoView {
+    if results.is_empty() {
+        return Either::Left(view! {
+            <Container>
+                <p class="mt-6 text-ctp-text">"No results"</p>
+            </Container>
+        });
+    }
+    let groups = [
+        ("Issues", results.issues),
+        ("Showcases", results.showcases),
+        ("Crate Releases", results.crate_releases),
+        ("Devlogs", results.devlogs),
+        ("Educationals", results.educationals),
+        (
+            "Merged Pull Requests",
+            results.merged_pull_requests,
+        ),
+    ];
+    Either::Right(view! {
+        <Container>
+            {groups
+                .into_iter()
+                .filter(|(_, hits)| !hits.is_empty())
+                .map(|(title, hits)| {
+                    view! {
+                        <Divider title/>
+                        <ul role="list" class="mt-4 space-y-4">
+                            {hits
+                                .into_iter()
+                                .map(|hit| {
+                                    view! {
+                                        <li>
+                                            <a
+                                                href=hit.url
+                                                class="font-bold text-ctp-text hover:text-ctp-sky"
+                                            >
+                                                {hit.title}
+                                            </a>
+                                            <p class="font-mono text-sm leading-7 text-ctp-text">
+                                                {hit.issue_date.to_string()} " - "
+                                                {hit.issue_title}
+                                            </p>
+                                        </li>
+                                    }
+                                })
+                                .collect_view()}
+                        </ul>
+                    }
+                })
+                .collect_view()}
+        </Container>
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub issue_title: String,
+    pub issue_date: time::Date,
+    /// link to the issue, with an anchor to the
+    /// matching item
+    pub url: String,
+}
+
+/// Search results, grouped by type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub issues: Vec<SearchHit>,
+    pub showcases: Vec<SearchHit>,
+    pub crate_releases: Vec<SearchHit>,
+    pub devlogs: Vec<SearchHit>,
+    pub educationals: Vec<SearchHit>,
+    pub merged_pull_requests: Vec<SearchHit>,
+}
+
+impl SearchResults {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+            && self.showcases.is_empty()
+            && self.crate_releases.is_empty()
+            && self.devlogs.is_empty()
+            && self.educationals.is_empty()
+            && self.merged_pull_requests.is_empty()
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, sqlx::FromRow)]
+struct SqlSearchHit {
+    slug: String,
+    issue_date: time::Date,
+    display_name: String,
+    title: String,
+}
+
+#[cfg(feature = "ssr")]
+impl SqlSearchHit {
+    /// `type_name` is `None` for issue hits,
+    /// which link to the top of the issue
+    fn into_hit(
+        self,
+        type_name: Option<&str>,
+    ) -> SearchHit {
+        let url = match type_name {
+            None => format!("/issue/{}", self.slug),
+            Some(type_name) => format!(
+                "/issue/{}#{}",
+                self.slug,
+                super::issue::anchor_id(
+                    type_name,
+                    &self.title
+                )
+            ),
+        };
+        SearchHit {
+            title: self.title,
+            issue_title: self.display_name,
+            issue_date: self.issue_date,
+            url,
+        }
+    }
+}
+
+/// Full-text search over published issues and
+/// the content attached to them.
+#[server]
+pub async fn search(
+    q: String,
+) -> Result<SearchResults, ServerFnError> {
+    let q = q.trim();
+    if q.is_empty() {
+        return Ok(SearchResults::default());
+    }
+    let pool = crate::sql::pool()?;
+
+    let issues = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    slug,
+    issue_date,
+    display_name,
+    display_name as title
+FROM issue
+WHERE status = 'publish'
+AND MATCH (display_name, description) AGAINST (?)
+ORDER BY MATCH (display_name, description) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let showcases = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    issue.slug,
+    issue.issue_date,
+    issue.display_name,
+    showcase.title
+FROM showcase
+INNER JOIN issue__showcase
+  ON issue__showcase.showcase_id = showcase.id
+INNER JOIN issue
+  ON issue.id = issue__showcase.issue_id
+WHERE issue.status = 'publish'
+AND MATCH (showcase.title, showcase.description) AGAINST (?)
+ORDER BY MATCH (showcase.title, showcase.description) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let crate_releases = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    issue.slug,
+    issue.issue_date,
+    issue.display_name,
+    crate_release.title
+FROM crate_release
+INNER JOIN issue__crate_release
+  ON issue__crate_release.crate_release_id = crate_release.id
+INNER JOIN issue
+  ON issue.id = issue__crate_release.issue_id
+WHERE issue.status = 'publish'
+AND MATCH (crate_release.title, crate_release.description) AGAINST (?)
+ORDER BY MATCH (crate_release.title, crate_release.description) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let devlogs = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    issue.slug,
+    issue.issue_date,
+    issue.display_name,
+    devlog.title
+FROM devlog
+INNER JOIN issue__devlog
+  ON issue__devlog.devlog_id = devlog.id
+INNER JOIN issue
+  ON issue.id = issue__devlog.issue_id
+WHERE issue.status = 'publish'
+AND MATCH (devlog.title, devlog.description) AGAINST (?)
+ORDER BY MATCH (devlog.title, devlog.description) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let educationals = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    issue.slug,
+    issue.issue_date,
+    issue.display_name,
+    educational.title
+FROM educational
+INNER JOIN issue__educational
+  ON issue__educational.educational_id = educational.id
+INNER JOIN issue
+  ON issue.id = issue__educational.issue_id
+WHERE issue.status = 'publish'
+AND MATCH (educational.title, educational.description) AGAINST (?)
+ORDER BY MATCH (educational.title, educational.description) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let merged_pull_requests = sqlx::query_as!(
+        SqlSearchHit,
+        r#"SELECT
+    issue.slug,
+    issue.issue_date,
+    issue.display_name,
+    merged_pull_request.title
+FROM merged_pull_request
+INNER JOIN issue__merged_pull_request
+  ON issue__merged_pull_request.merged_pull_request_id = merged_pull_request.id
+INNER JOIN issue
+  ON issue.id = issue__merged_pull_request.issue_id
+WHERE issue.status = 'publish'
+AND MATCH (merged_pull_request.title) AGAINST (?)
+ORDER BY MATCH (merged_pull_request.title) AGAINST (?) DESC
+LIMIT ?"#,
+        q,
+        q,
+        LIMIT
+    )
+    .fetch_all(&pool)
+    .await;
+
+    let hits =
+        |rows: Result<Vec<SqlSearchHit>, sqlx::Error>,
+         type_name: Option<&str>| {
+            rows.map(|rows| {
+                rows.into_iter()
+                    .map(|row| row.into_hit(type_name))
+                    .collect::<Vec<_>>()
+            })
+            .map_err(|e| {
+                error!(?e);
+                ServerFnError::<
+                crate::app::server_fn::error::NoCustomError,
+            >::ServerError(
+                "search failed".to_string()
+            )
+            })
+        };
+
+    Ok(SearchResults {
+        issues: hits(issues, None)?,
+        showcases: hits(showcases, Some("showcase"))?,
+        crate_releases: hits(
+            crate_releases,
+            Some("crate_release"),
+        )?,
+        devlogs: hits(devlogs, Some("devlog"))?,
+        educationals: hits(
+            educationals,
+            Some("educational"),
+        )?,
+        merged_pull_requests: hits(
+            merged_pull_requests,
+            Some("pull_request"),
+        )?,
+    })
+}
+
+#[cfg(feature = "ssr")]
+#[cfg(test)]
+mod tests_server {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn hits_link_to_anchors() {
+        let row = || SqlSearchHit {
+            slug: "2024-02-12-bevy-0-13".to_string(),
+            issue_date: date!(2024 - 02 - 12),
+            display_name: "Bevy 0.13".to_string(),
+            title: "Hexagon Maps".to_string(),
+        };
+        assert_eq!(
+            row().into_hit(None).url,
+            "/issue/2024-02-12-bevy-0-13"
+        );
+        assert_eq!(
+            row().into_hit(Some("showcase")).url,
+            "/issue/2024-02-12-bevy-0-13#showcase-hexagon-maps"
+        );
+    }
+
+    #[tokio::test]
+    async fn blank_queries_skip_the_database() {
+        // no pool in context, so this would fail if
+        // it reached the database
+        let results =
+            search("   ".to_string()).await.unwrap();
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+#[cfg(feature = "ssr")]
+use crate::app::server_fn::error::NoCustomError;
+use crate::{
+    app::components::Container,
+    submissions::SubmissionKind, Username,
+};
+use leptos::{either::Either, prelude::*};
+use leptos_meta::Title;
+#[cfg(feature = "ssr")]
+use tracing::error;
+
+const INPUT: &str = "block w-full rounded-md border-0 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 placeholder:text-gray-400 focus:ring-2 focus:ring-inset focus:ring-indigo-600 sm:text-sm sm:leading-6";
+const LABEL: &str =
+    "mt-4 block text-sm font-medium leading-6 text-ctp-text";
+
+#[component]
+pub fn Submit() -> impl IntoView {
+    let username =
+        use_context::<Option<Username>>().flatten();
+
+    view! {
+        <Title text="Submit to This Week in Bevy"/>
+        <div class="pb-12 pt-16 sm:pb-4 lg:pt-12">
+            <Container>
+                <h1 class="text-2xl font-bold leading-7 text-ctp-text">
+                    "Submit to This Week in Bevy"
+                </h1>
+                <p class="mt-2 text-base leading-7 text-ctp-text">
+                    "Built something with Bevy, released a crate, or wrote a tutorial? Submissions are reviewed before they're included in an issue."
+                </p>
+                {match username {
+                    None => Either::Left(view! { <LoginToSubmit/> }),
+                    Some(Username(username)) => {
+                        Either::Right(view! { <SubmissionForm username/> })
+                    }
+                }}
+            </Container>
+        </div>
+    }
+}
+
+#[component]
+fn LoginToSubmit() -> impl IntoView {
+    view! {
+        <form method="post" action="/login" class="mt-6">
+            <input type="hidden" name="next" value="/submit"/>
+            <button
+                type="submit"
+                class="rounded-md bg-indigo-600 px-2.5 py-1.5 text-sm font-semibold text-white shadow-sm hover:bg-indigo-500"
+            >
+                "Log in with GitHub to submit"
+            </button>
+        </form>
+    }
+}
+
+#[component]
+fn SubmissionForm(username: String) -> impl IntoView {
+    let submit_content: ServerAction<SubmitContent> =
+        ServerAction::new();
+
+    view! {
+        <p class="mt-2 text-sm text-ctp-text">"Submitting as " {username}</p>
+        <ActionForm action=submit_content attr:class="mt-6">
+            <label for="kind" class=LABEL>
+                "What are you submitting?"
+            </label>
+            <select name="kind" id="kind" class=INPUT>
+                {SubmissionKind::ALL
+                    .iter()
+                    .map(|kind| {
+                        view! { <option value=kind.as_str()>{kind.display_name()}</option> }
+                    })
+                    .collect_view()}
+            </select>
+            <label for="title" class=LABEL>
+                Title
+            </label>
+            <input required type="text" name="title" id="title" class=INPUT/>
+            <label for="url" class=LABEL>
+                "URL (project, crate or written post)"
+            </label>
+            <input type="url" name="url" id="url" class=INPUT placeholder="https://"/>
+            <label for="video_url" class=LABEL>
+                "Video URL (devlogs and educationals only)"
+            </label>
+            <input
+                type="url"
+                name="video_url"
+                id="video_url"
+                class=INPUT
+                placeholder="https://"
+            />
+            <label for="discord_url" class=LABEL>
+                "Discord URL, if there's a thread"
+            </label>
+            <input
+                type="url"
+                name="discord_url"
+                id="discord_url"
+                class=INPUT
+                placeholder="https://"
+            />
+            <label for="description" class=LABEL>
+                "Description (markdown compatible)"
+            </label>
+            <textarea required rows="6" name="description" id="description" class=INPUT></textarea>
+            <button
+                type="submit"
+                class="mt-4 rounded-md bg-indigo-600 px-2.5 py-1.5 text-sm font-semibold text-white shadow-sm hover:bg-indigo-500"
+            >
+                Submit for review
+            </button>
+            {move || {
+                submit_content
+                    .value()
+                    .get()
+                    .map(|result| match result {
+                        Ok(()) => "Thanks! Your submission is waiting for review.".to_string(),
+                        Err(e) => e.to_string(),
+                    })
+                    .map(|message| view! { <p class="mt-2 text-sm text-ctp-text">{message}</p> })
+            }}
+        </ActionForm>
+    }
+}
+
+#[server]
+async fn submit_content(
+    kind: String,
+    title: String,
+    url: String,
+    video_url: String,
+    discord_url: String,
+    description: String,
+) -> Result<(), ServerFnError> {
+    use crate::submissions::Submission;
+
+    let pool = crate::sql::pool()?;
+    let Some(Username(username)) =
+        use_context::<Option<Username>>().flatten()
+    else {
+        return Err(
+            ServerFnError::<NoCustomError>::ServerError(
+                "log in with GitHub to submit".to_string(),
+            ),
+        );
+    };
+
+    let to_server_error =
+        |e: crate::submissions::SubmissionError| {
+            ServerFnError::<NoCustomError>::ServerError(
+                e.to_string(),
+            )
+        };
+    let submission = Submission {
+        kind: kind
+            .parse::<SubmissionKind>()
+            .map_err(|e| to_server_error(e.into()))?,
+        title,
+        url,
+        video_url,
+        discord_url,
+        description,
+    }
+    .validate()
+    .map_err(to_server_error)?;
+
+    let id: [u8; 16] = rusty_ulid::generate_ulid_bytes();
+    let posted_date =
+        time::OffsetDateTime::now_utc().date();
+
+    let result = match submission.kind {
+        SubmissionKind::Showcase => {
+            sqlx::query!(
+                r#"
+    INSERT INTO showcase ( id, title, url, discord_url, posted_date, description, submitted_by, review_status )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, 'pending' )
+        "#,
+                id.as_slice(),
+                submission.title,
+                submission.url,
+                submission.discord_url,
+                posted_date,
+                submission.description,
+                username
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::CrateRelease => {
+            sqlx::query!(
+                r#"
+    INSERT INTO crate_release ( id, title, url, discord_url, posted_date, description, submitted_by, review_status )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, 'pending' )
+        "#,
+                id.as_slice(),
+                submission.title,
+                submission.url,
+                submission.discord_url,
+                posted_date,
+                submission.description,
+                username
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::Devlog => {
+            sqlx::query!(
+                r#"
+    INSERT INTO devlog ( id, title, video_url, post_url, discord_url, posted_date, description, submitted_by, review_status )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, 'pending' )
+        "#,
+                id.as_slice(),
+                submission.title,
+                submission.video_url,
+                submission.url,
+                submission.discord_url,
+                posted_date,
+                submission.description,
+                username
+            )
+            .execute(&pool)
+            .await
+        }
+        SubmissionKind::Educational => {
+            sqlx::query!(
+                r#"
+    INSERT INTO educational ( id, title, video_url, post_url, discord_url, posted_date, description, submitted_by, review_status )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, ?, 'pending' )
+        "#,
+                id.as_slice(),
+                submission.title,
+                submission.video_url,
+                submission.url,
+                submission.discord_url,
+                posted_date,
+                submission.description,
+                username
+            )
+            .execute(&pool)
+            .await
+        }
+    };
+
+    result.map_err(|e| {
+        error!(?e);
+        ServerFnError::<NoCustomError>::ServerError(
+            "sql failed".to_string(),
+        )
+    })?;
+    Ok(())
+}
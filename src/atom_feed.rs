@@ -1,159 +1,243 @@
-use crate::state::AppState;
+//! Atom feeds: every issue in full at
+//! `/feed.xml`, and the entries of each section
+//! at `/feeds/{section}.xml`.
+use crate::{
+    feed::{
+        self, FeedParams, SiteFeed, ICON_URL, LOGO_URL,
+    },
+    site_config::SiteConfig,
+    state::AppState,
+};
 use atom_syndication::*;
 use axum::{
-    extract::State, http::header, response::IntoResponse,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use time::{
+    format_description::well_known::Rfc3339, OffsetDateTime,
 };
-use serde::{Deserialize, Serialize};
+
+const CONTENT_TYPE: &str =
+    "application/atom+xml; charset=utf-8";
 
 pub async fn atom_feed(
     State(app_state): State<AppState>,
-) -> impl IntoResponse {
-    use atom_syndication::Feed;
-
-    let issues: Vec<SqlIssueShort> = sqlx::query_as!(
-        SqlIssueShort,
-        r#"
-SELECT
-id,
-slug,
-issue_date,
-display_name,
-description,
-youtube_id
-FROM issue
-WHERE status = "publish"
-ORDER BY status, issue_date DESC
-LIMIT 5"#
+    State(config): State<SiteConfig>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> Response {
+    let issues = feed::issues(
+        &app_state.pool,
+        &config,
+        params.limit(),
     )
-    .fetch_all(&app_state.pool)
-    .await
-    .unwrap();
-
-    let issues: Vec<IssueShort> =
-        issues.into_iter().map(IssueShort::from).collect();
-
-    let mut newest_issue_date = None;
-
-    let entries: Vec<Entry> = issues.into_iter().filter_map(|issue| {
-let date = issue.issue_date.and_then(|v| {
-    use atom_syndication::FixedDateTime;
-    use std::str::FromStr;
-    FixedDateTime::from_str(&format!("{v}T12:30:00Z")).ok()
-})?;
+    .await;
+    feed::respond(&headers, issues, CONTENT_TYPE, |feed| {
+        render(feed, &config.site_url.join("/feed.xml"))
+    })
+}
 
-if Some(date) > newest_issue_date {
-    newest_issue_date = Some(date);
-};
+/// `file` is the section's slug with an `.xml`
+/// extension, ex: `showcases.xml`
+pub async fn section_atom_feed(
+    State(app_state): State<AppState>,
+    State(config): State<SiteConfig>,
+    Path(file): Path<String>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(kind) = file
+        .strip_suffix(".xml")
+        .and_then(feed::section_from_slug)
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let entries = feed::section(
+        &app_state.pool,
+        &config,
+        kind,
+        params.limit(),
+    )
+    .await;
+    feed::respond(
+        &headers,
+        entries,
+        CONTENT_TYPE,
+        |feed| {
+            render(
+                feed,
+                &config
+                    .site_url
+                    .join(&format!("/feeds/{file}")),
+            )
+        },
+    )
+}
 
-Some(    EntryBuilder::default()
-    .title(issue.display_name.clone())
-    .id(format!("https://thisweekinbevy.com/issue/{}", issue.slug))
-    .updated(date)
-    .author(
-        Person {
-        name: "Chris Biscardi".to_string(),
+fn author(feed: &SiteFeed) -> Person {
+    Person {
+        name: feed.author.name.clone(),
         email: None,
-        uri: Some("https://www.christopherbiscardi.com/".to_string())
-      })
-      .link( Link {
-        href: format!("https://thisweekinbevy.com/issue/{}", issue.slug),
-        rel: "alternate".to_string(),
-        hreflang: Some("en".to_string()),
-        mime_type: Some("text/html".to_string()),
-        title: Some(issue.display_name),
-        length: None,
-    })
-    .published(date)
-    .summary(Some(Text{
-        value: issue.description,
-    base: None,
-    lang: None,
-    r#type: TextType::Html,
+        uri: Some(feed.author.url.clone()),
+    }
+}
 
-}))
-    .content(Some(Content {
-        base: None,
-        lang: Some("en".to_string()),
-        value: None,
-        src: Some(format!("https://thisweekinbevy.com/issue/{}", issue.slug)),
-        content_type: Some("text/html".to_string()),
-    })).build())
-}).collect();
-    let mut feed = Feed::default();
+fn to_fixed(date: OffsetDateTime) -> Option<FixedDateTime> {
+    let date = date.format(&Rfc3339).ok()?;
+    FixedDateTime::parse_from_rfc3339(&date).ok()
+}
 
-    feed.set_id("https://thisweekinbevy.com/".to_string());
-    feed.set_updated(newest_issue_date.expect("having an issue should mean there is a most recent date"));
-    feed.set_title("This Week in Bevy");
-    feed
-  .set_logo("https://res.cloudinary.com/dilgcuzda/image/upload/v1708481576/thisweekinbevy/this-week-in-bevylight_uddwes.avif".to_string());
-    feed.set_icon(
-        "https://cdn.thisweekinbevy.com/favicon-32x32.png"
+fn link(href: String, rel: &str, title: &str) -> Link {
+    Link {
+        href,
+        rel: rel.to_string(),
+        hreflang: Some("en".to_string()),
+        mime_type: Some(
+            if rel == "self" {
+                CONTENT_TYPE
+            } else {
+                "text/html"
+            }
             .to_string(),
-    );
-    feed.set_authors(vec![Person {
-        name: "Chris Biscardi".to_string(),
-        email: None,
-        uri: Some(
-            "https://www.christopherbiscardi.com/"
-                .to_string(),
         ),
-    }]);
-    feed.set_links(vec![Link {
-        href: "https://thisweekinbevy.com".to_string(),
-        rel: "alternate".to_string(),
-        hreflang: Some("en".to_string()),
-        mime_type: Some("text/html".to_string()),
-        title: Some("This Week in Bevy".to_string()),
+        title: Some(title.to_string()),
         length: None,
-    }]);
-    feed
-  .set_subtitle(Text::from("What happened this week in the Bevy Engine ecosystem"));
-    feed.set_entries(entries);
-
-    let headers = [(
-        header::CONTENT_TYPE,
-        "application/atom+xml; charset=utf-8".to_string(),
-    )];
-    (headers, feed.to_string())
+    }
 }
 
-#[cfg(feature = "ssr")]
-#[derive(Debug, sqlx::FromRow)]
-struct SqlIssueShort {
-    pub id: Vec<u8>,
-    pub slug: String,
-    pub issue_date: Option<time::Date>,
-    pub display_name: String,
-    pub description: String,
-    pub youtube_id: String,
-}
+/// `feed_url` is where this feed is served, for
+/// the feed's id and `self` link
+pub fn render(feed: &SiteFeed, feed_url: &str) -> String {
+    let entries: Vec<Entry> = feed
+        .items
+        .iter()
+        .filter_map(|item| {
+            Some(
+                EntryBuilder::default()
+                    .title(item.title.clone())
+                    .id(item.url.clone())
+                    .updated(to_fixed(item.updated)?)
+                    .author(author(feed))
+                    .link(link(
+                        item.url.clone(),
+                        "alternate",
+                        &item.title,
+                    ))
+                    .published(to_fixed(item.published))
+                    .summary(item.summary_html.clone().map(
+                        |value| Text {
+                            value,
+                            base: None,
+                            lang: None,
+                            r#type: TextType::Html,
+                        },
+                    ))
+                    .content(Some(Content {
+                        base: None,
+                        lang: Some("en".to_string()),
+                        value: Some(
+                            item.content_html.clone(),
+                        ),
+                        src: None,
+                        content_type: Some(
+                            "html".to_string(),
+                        ),
+                    }))
+                    .build(),
+            )
+        })
+        .collect();
 
-#[derive(Deserialize, Serialize, Clone)]
-pub struct IssueShort {
-    pub id: String,
-    pub slug: String,
-    pub issue_date: Option<time::Date>,
-    pub display_name: String,
-    pub description: String,
-    pub youtube_id: String,
+    let mut atom = Feed::default();
+    atom.set_id(feed_url.to_string());
+    // an empty feed keeps the default (epoch)
+    // updated date
+    if let Some(updated) = feed.updated().and_then(to_fixed)
+    {
+        atom.set_updated(updated);
+    }
+    atom.set_title(feed.title.clone());
+    atom.set_logo(LOGO_URL.to_string());
+    atom.set_icon(ICON_URL.to_string());
+    atom.set_authors(vec![author(feed)]);
+    atom.set_links(vec![
+        link(
+            feed.home_page_url.clone(),
+            "alternate",
+            &feed.title,
+        ),
+        link(
+            feed_url.to_string(),
+            "self",
+            &feed.title,
+        ),
+    ]);
+    atom.set_subtitle(Text::from(feed.description.clone()));
+    atom.set_entries(entries);
+    atom.to_string()
 }
 
-#[cfg(feature = "ssr")]
-impl From<SqlIssueShort> for IssueShort {
-    fn from(value: SqlIssueShort) -> Self {
-        use crate::markdown::compile;
-        let id_str =
-            rusty_ulid::Ulid::try_from(value.id.as_slice())
-                .expect(
-                    "expect valid ids from the database",
-                );
-        IssueShort {
-            id: id_str.to_string(),
-            slug: value.slug,
-            issue_date: value.issue_date,
-            display_name: value.display_name,
-            description: compile(&value.description),
-            youtube_id: value.youtube_id,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{feed::FeedItem, site_config::Author};
+    use time::macros::datetime;
+
+    fn site_feed(items: Vec<FeedItem>) -> SiteFeed {
+        SiteFeed {
+            title: "This Week in Bevy".to_string(),
+            home_page_url: "https://thisweekinbevy.com/"
+                .to_string(),
+            description: "What happened".to_string(),
+            author: Author::default(),
+            items,
         }
     }
+
+    #[test]
+    fn empty_feed_renders() {
+        let atom = render(
+            &site_feed(vec![]),
+            "https://thisweekinbevy.com/feed.xml",
+        );
+        assert!(atom
+            .contains("<title>This Week in Bevy</title>"));
+        assert!(!atom.contains("<entry>"));
+    }
+
+    #[test]
+    fn renders_items_inline() {
+        let atom = render(
+            &site_feed(vec![FeedItem {
+                url: "https://thisweekinbevy.com/issue/2024-02-12-some-slug".to_string(),
+                title: "Bevy <3".to_string(),
+                summary_html: None,
+                summary_text: None,
+                content_html: "<p>A <strong>big</strong> week</p>"
+                    .to_string(),
+                image: None,
+                published: datetime!(2024-02-12 12:30 UTC),
+                updated: datetime!(2024-02-14 09:00 UTC),
+            }]),
+            "https://thisweekinbevy.com/feed.xml",
+        );
+        let parsed: Feed = atom.parse().unwrap();
+        let entry = &parsed.entries()[0];
+        assert_eq!(entry.title().as_str(), "Bevy <3");
+        assert_eq!(
+            entry.content().and_then(|c| c.value()),
+            Some("<p>A <strong>big</strong> week</p>")
+        );
+        assert_eq!(
+            entry.updated().to_rfc3339(),
+            "2024-02-14T09:00:00+00:00"
+        );
+        assert_eq!(parsed.updated(), entry.updated());
+        assert!(parsed.links().iter().any(|link| {
+            link.rel() == "self"
+                && link.href()
+                    == "https://thisweekinbevy.com/feed.xml"
+        }));
+    }
 }
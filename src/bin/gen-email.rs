@@ -0,0 +1,68 @@
+//! Render the newsletter email for a published
+//! issue.
+//!
+//! ```bash
+//! DATABASE_URL=mysql://... cargo run --bin gen-email --features gen-email -- 2024-02-12 ./emails
+//! ```
+//!
+//! writes `2024-02-12.html` and `2024-02-12.txt`
+//! into the output directory (the current
+//! directory by default).
+use sqlx::mysql::MySqlPoolOptions;
+use std::{env, path::PathBuf};
+use this_week_in_bevy::{
+    email, issue_date::parse_issue_date,
+    site_config::SiteConfig,
+};
+
+#[tokio::main]
+async fn main() {
+    let mut args = env::args().skip(1);
+    let Some(date) = args.next() else {
+        eprintln!(
+            "usage: gen-email <issue_date> [output_dir]"
+        );
+        std::process::exit(1);
+    };
+    let date = parse_issue_date(&date)
+        .expect("issue_date should be YYYY-MM-DD");
+    let output_dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let config = SiteConfig::load().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    let pool = MySqlPoolOptions::new()
+        .connect(
+            &env::var("DATABASE_URL")
+                .expect("DATABASE_URL must be set"),
+        )
+        .await
+        .expect("Could not make pool.");
+
+    let issue = email::fetch_published_issue(&pool, date)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+    let email = email::render(&issue, &config)
+        .expect("the email template to render");
+
+    std::fs::create_dir_all(&output_dir)
+        .expect("to create the output directory");
+    let html_path = output_dir.join(format!("{date}.html"));
+    let text_path = output_dir.join(format!("{date}.txt"));
+    std::fs::write(&html_path, email.html)
+        .expect("to write the html email");
+    std::fs::write(&text_path, email.text)
+        .expect("to write the plain text email");
+
+    println!("subject: {}", email.subject);
+    println!("wrote {}", html_path.display());
+    println!("wrote {}", text_path.display());
+}
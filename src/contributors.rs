@@ -0,0 +1,199 @@
+//! People credited on an issue, stored in
+//! `issue__contributors`.
+//!
+//! These are credits, not accounts. A contributor
+//! is a GitHub username that doesn't need to have
+//! ever logged in.
+use crate::string_enum::string_enum;
+use serde::{Deserialize, Serialize};
+
+string_enum! {
+    #[derive(Serialize, Deserialize)]
+    pub enum ContributorRole {
+        /// Wrote some of the issue's text
+        Writer = "writer",
+        /// Reviewed and edited the issue
+        Editor = "editor",
+        /// Found and collected items for the issue
+        Curator = "curator",
+        /// Worked on the issue's video
+        Video = "video",
+    }
+    #[error("unknown contributor role `{0}`")]
+    pub struct UnknownContributorRole;
+}
+
+impl ContributorRole {
+    /// How the role reads in the credits
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContributorRole::Writer => "Writing",
+            ContributorRole::Editor => "Editing",
+            ContributorRole::Curator => "Curation",
+            ContributorRole::Video => "Video",
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct Contributor {
+    pub github_username: String,
+    /// Empty when the contributor should be
+    /// credited by their GitHub username
+    pub display_name: String,
+    pub role: ContributorRole,
+}
+
+impl Contributor {
+    /// The name shown in the credits
+    pub fn name(&self) -> &str {
+        if self.display_name.trim().is_empty() {
+            &self.github_username
+        } else {
+            self.display_name.trim()
+        }
+    }
+
+    pub fn github_url(&self) -> String {
+        format!(
+            "https://github.com/{}",
+            self.github_username
+        )
+    }
+}
+
+/// A row of `issue__contributors`
+#[cfg(feature = "ssr")]
+#[derive(Debug, sqlx::FromRow)]
+pub struct SqlContributor {
+    pub github_username: String,
+    pub display_name: String,
+    pub role: String,
+}
+
+#[cfg(feature = "ssr")]
+impl From<SqlContributor> for Contributor {
+    fn from(value: SqlContributor) -> Self {
+        Contributor {
+            // roles are only written from
+            // `ContributorRole::as_str`
+            role: value
+                .role
+                .parse()
+                .unwrap_or(ContributorRole::Writer),
+            github_username: value.github_username,
+            display_name: value.display_name,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("`{0}` is not a valid GitHub username")]
+pub struct InvalidGithubUsername(pub String);
+
+/// Trims a username typed into the admin form,
+/// including a leading `@`, and checks it's
+/// something GitHub would allow: up to 39
+/// letters, numbers, or hyphens that don't
+/// start or end the name.
+pub fn parse_github_username(
+    input: &str,
+) -> Result<String, InvalidGithubUsername> {
+    let username = input.trim().trim_start_matches('@');
+    let valid = !username.is_empty()
+        && username.len() <= 39
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !username.starts_with('-')
+        && !username.ends_with('-');
+    if valid {
+        Ok(username.to_string())
+    } else {
+        Err(InvalidGithubUsername(input.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_falls_back_to_the_username() {
+        let mut contributor = Contributor {
+            github_username: "ChristopherBiscardi"
+                .to_string(),
+            display_name: " ".to_string(),
+            role: ContributorRole::Writer,
+        };
+        assert_eq!(
+            contributor.name(),
+            "ChristopherBiscardi"
+        );
+
+        contributor.display_name = "Chris".to_string();
+        assert_eq!(contributor.name(), "Chris");
+        assert_eq!(
+            contributor.github_url(),
+            "https://github.com/ChristopherBiscardi"
+        );
+    }
+
+    #[test]
+    fn github_usernames() {
+        assert_eq!(
+            parse_github_username(" @alice-b "),
+            Ok("alice-b".to_string())
+        );
+        for invalid in [
+            "",
+            "-alice",
+            "alice-",
+            "alice/bob",
+            "alice bob",
+            &"a".repeat(40),
+        ] {
+            assert!(
+                parse_github_username(invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn issues_have_more_than_one_contributor() {
+        use crate::sql::test_db::DisposableSchema;
+
+        let schema = DisposableSchema::new().await;
+        let issue_id: [u8; 16] =
+            rusty_ulid::Ulid::generate().into();
+        for (username, role) in
+            [("alice", "writer"), ("bob", "video")]
+        {
+            sqlx::query(
+                "INSERT INTO issue__contributors (issue_id, github_username, role) VALUES (?, ?, ?)",
+            )
+            .bind(issue_id.as_slice())
+            .bind(username)
+            .bind(role)
+            .execute(&schema.pool)
+            .await
+            .unwrap();
+        }
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM issue__contributors WHERE issue_id = ?",
+        )
+        .bind(issue_id.as_slice())
+        .fetch_one(&schema.pool)
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+
+        schema.drop().await;
+    }
+}
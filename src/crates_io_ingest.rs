@@ -0,0 +1,793 @@
+//! Imports new releases of Bevy crates from the
+//! crates.io API as pending `crate_release` rows.
+//!
+//! A crate counts as a Bevy crate when it carries
+//! the `bevy` keyword or depends on `bevy`. Every
+//! version such a crate published in the date
+//! range becomes a row in the submissions queue,
+//! where it can be edited and approved like a
+//! community submission.
+use crate::issue_date::parse_issue_date;
+use axum::http::header::USER_AGENT;
+use serde::{de::DeserializeOwned, Deserialize};
+use sqlx::MySqlPool;
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::{error, info, instrument};
+
+/// Shown as the submitter in the submissions
+/// queue. GitHub usernames can't contain a `.`,
+/// so this never matches a real user.
+pub const IMPORTED_BY: &str = "crates.io";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Sqlx(sqlx::Error),
+
+    #[error(transparent)]
+    Reqwest(reqwest::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct CratesIoClient {
+    http: reqwest::Client,
+    base_url: String,
+    per_page: usize,
+    /// crates.io asks crawlers for at most one
+    /// request a second
+    delay: Duration,
+}
+
+impl CratesIoClient {
+    /// `base_url` is `https://crates.io/api/v1` in
+    /// production and a local fixture server in
+    /// tests.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url
+                .into()
+                .trim_end_matches('/')
+                .to_string(),
+            per_page: 100,
+            delay: Duration::from_secs(1),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, reqwest::Error> {
+        tokio::time::sleep(self.delay).await;
+        self.http
+            .get(format!("{}/{path}", self.base_url))
+            .query(query)
+            // See: https://crates.io/data-access#api
+            .header(
+                USER_AGENT.as_str(),
+                "this-week-in-bevy (https://thisweekinbevy.com)",
+            )
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Crates with the `bevy` keyword updated on
+    /// or after `start_date`. The listing is
+    /// sorted by most recently updated, so
+    /// paging stops at the first crate
+    /// updated before the range.
+    async fn keyword_crates(
+        &self,
+        start_date: time::Date,
+    ) -> Result<Vec<String>, reqwest::Error> {
+        let mut names = vec![];
+        for page in 1.. {
+            let results: ApiCrates = self
+                .get(
+                    "crates",
+                    &[
+                        ("keyword", "bevy".to_string()),
+                        (
+                            "sort",
+                            "recent-updates".to_string(),
+                        ),
+                        (
+                            "per_page",
+                            self.per_page.to_string(),
+                        ),
+                        ("page", page.to_string()),
+                    ],
+                )
+                .await?;
+            let count = results.crates.len();
+            let mut done = count < self.per_page;
+            for krate in results.crates {
+                if date(&krate.updated_at)
+                    .is_some_and(|d| d < start_date)
+                {
+                    done = true;
+                    break;
+                }
+                names.push(krate.name);
+            }
+            if done {
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    /// Crates depending on `bevy` whose latest
+    /// dependent version was published in the
+    /// range. The reverse dependencies can't be
+    /// sorted by date, so every page is read.
+    async fn dependent_crates(
+        &self,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> Result<Vec<String>, reqwest::Error> {
+        let mut names = vec![];
+        for page in 1.. {
+            let results: ApiReverseDependencies = self
+                .get(
+                    "crates/bevy/reverse_dependencies",
+                    &[
+                        (
+                            "per_page",
+                            self.per_page.to_string(),
+                        ),
+                        ("page", page.to_string()),
+                    ],
+                )
+                .await?;
+            names.extend(
+                results
+                    .versions
+                    .into_iter()
+                    .filter(|version| {
+                        date(&version.created_at)
+                            .is_some_and(|d| {
+                                in_range(
+                                    d, start_date, end_date,
+                                )
+                            })
+                    })
+                    .map(|version| version.krate),
+            );
+            if results.dependencies.len() < self.per_page {
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    /// Every version of a Bevy crate published
+    /// between `start_date` and `end_date`,
+    /// inclusive. Yanked versions are skipped.
+    #[instrument(skip(self, progress))]
+    pub async fn releases(
+        &self,
+        start_date: time::Date,
+        end_date: time::Date,
+        progress: impl Fn(ImportProgress),
+    ) -> Result<Vec<CrateReleaseItem>, ImportError> {
+        progress(ImportProgress::FindingCrates);
+        let mut names: BTreeSet<String> = self
+            .keyword_crates(start_date)
+            .await
+            .map_err(ImportError::Reqwest)?
+            .into_iter()
+            .collect();
+        names.extend(
+            self.dependent_crates(start_date, end_date)
+                .await
+                .map_err(ImportError::Reqwest)?,
+        );
+
+        let mut releases = vec![];
+        let total = names.len();
+        for (checked, name) in names.into_iter().enumerate()
+        {
+            progress(ImportProgress::CheckingCrates {
+                checked,
+                total,
+            });
+            let krate: ApiCrate = self
+                .get(&format!("crates/{name}"), &[])
+                .await
+                .map_err(ImportError::Reqwest)?;
+            releases.extend(
+                krate.releases_in(start_date, end_date),
+            );
+        }
+        Ok(releases)
+    }
+}
+
+/// One published version of a crate
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateReleaseItem {
+    pub crate_name: String,
+    pub version: String,
+    pub description: String,
+    pub published: time::Date,
+}
+
+impl CrateReleaseItem {
+    pub fn title(&self) -> String {
+        format!("{} {}", self.crate_name, self.version)
+    }
+
+    pub fn url(&self) -> String {
+        format!(
+            "https://crates.io/crates/{}/{}",
+            self.crate_name, self.version
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportCounts {
+    /// Releases found in the date range
+    pub found: usize,
+    /// Releases that weren't imported before
+    pub imported: usize,
+}
+
+/// How far along an import is. Every crate
+/// takes a request, throttled to one a second,
+/// so checking them is the slow part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportProgress {
+    FindingCrates,
+    CheckingCrates { checked: usize, total: usize },
+    Saving { releases: usize },
+}
+
+impl std::fmt::Display for ImportProgress {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            ImportProgress::FindingCrates => {
+                write!(f, "finding bevy crates")
+            }
+            ImportProgress::CheckingCrates {
+                checked,
+                total,
+            } => write!(
+                f,
+                "checked {checked} of {total} crates"
+            ),
+            ImportProgress::Saving { releases } => {
+                write!(f, "saving {releases} releases")
+            }
+        }
+    }
+}
+
+/// Fetch every release in the date range and
+/// insert the ones that haven't been imported
+/// yet. Rows that already exist are left alone,
+/// so edits made while reviewing aren't
+/// overwritten.
+#[instrument(skip(pool, client, progress))]
+pub async fn import(
+    pool: &MySqlPool,
+    client: &CratesIoClient,
+    start_date: time::Date,
+    end_date: time::Date,
+    progress: impl Fn(ImportProgress),
+) -> Result<ImportCounts, ImportError> {
+    let releases = client
+        .releases(start_date, end_date, &progress)
+        .await?;
+    progress(ImportProgress::Saving {
+        releases: releases.len(),
+    });
+    let imported = insert_releases(pool, &releases)
+        .await
+        .map_err(ImportError::Sqlx)?;
+
+    let counts = ImportCounts {
+        found: releases.len(),
+        imported,
+    };
+    info!(?counts, "imported from crates.io");
+    Ok(counts)
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum ImportStatus {
+    /// Nothing has been imported since the
+    /// server started
+    #[default]
+    Idle,
+    Running(ImportProgress),
+    Finished(ImportCounts),
+    Failed(String),
+}
+
+/// The import started from the admin panel,
+/// shared through `AppState`. Imports take
+/// minutes, so they run in the background and
+/// the admin panel shows the status. Only one
+/// runs at a time.
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundImport(Arc<Mutex<ImportStatus>>);
+
+impl BackgroundImport {
+    pub fn status(&self) -> ImportStatus {
+        self.0.lock().expect("an unpoisoned lock").clone()
+    }
+
+    fn set(&self, status: ImportStatus) {
+        *self.0.lock().expect("an unpoisoned lock") =
+            status;
+    }
+
+    /// Start importing in a background task.
+    /// Returns `false` without starting one if an
+    /// import is already running.
+    pub fn start(
+        &self,
+        pool: MySqlPool,
+        client: CratesIoClient,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> bool {
+        {
+            let mut status =
+                self.0.lock().expect("an unpoisoned lock");
+            if matches!(*status, ImportStatus::Running(_)) {
+                return false;
+            }
+            *status = ImportStatus::Running(
+                ImportProgress::FindingCrates,
+            );
+        }
+        let this = self.clone();
+        tokio::spawn(async move {
+            let result = import(
+                &pool,
+                &client,
+                start_date,
+                end_date,
+                |progress| {
+                    this.set(ImportStatus::Running(
+                        progress,
+                    ))
+                },
+            )
+            .await;
+            this.set(match result {
+                Ok(counts) => {
+                    ImportStatus::Finished(counts)
+                }
+                Err(e) => {
+                    error!(?e, "crates.io import failed");
+                    ImportStatus::Failed(e.to_string())
+                }
+            });
+        });
+        true
+    }
+}
+
+async fn insert_releases(
+    pool: &MySqlPool,
+    releases: &[CrateReleaseItem],
+) -> Result<usize, sqlx::Error> {
+    let mut imported = 0;
+    for release in releases {
+        // the connection reports a duplicate as a
+        // found row, so an existing release is
+        // looked up first to keep it out of the
+        // count
+        let existing = sqlx::query!(
+            r#"
+    SELECT id FROM crate_release
+    WHERE crate_name = ? AND crate_version = ?
+        "#,
+            release.crate_name,
+            release.version
+        )
+        .fetch_optional(pool)
+        .await?;
+        if existing.is_some() {
+            continue;
+        }
+        let id: [u8; 16] =
+            rusty_ulid::generate_ulid_bytes();
+        let description: String = release
+            .description
+            .chars()
+            .take(5000)
+            .collect();
+        sqlx::query!(
+            r#"
+    INSERT INTO crate_release ( id, title, url, description, posted_date, submitted_by, review_status, crate_name, crate_version )
+    VALUES ( ?, ?, ?, ?, ?, ?, 'pending', ?, ? )
+    ON DUPLICATE KEY UPDATE id = id
+        "#,
+            id.as_slice(),
+            release.title(),
+            release.url(),
+            description,
+            release.published,
+            IMPORTED_BY,
+            release.crate_name,
+            release.version
+        )
+        .execute(pool)
+        .await?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// crates.io timestamps look like
+/// `2024-02-12T09:30:00.123456+00:00`, and we
+/// only store the date.
+fn date(timestamp: &str) -> Option<time::Date> {
+    timestamp.get(0..10).and_then(parse_issue_date)
+}
+
+fn in_range(
+    date: time::Date,
+    start_date: time::Date,
+    end_date: time::Date,
+) -> bool {
+    start_date <= date && date <= end_date
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCrates {
+    crates: Vec<ApiCrateSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCrateSummary {
+    name: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReverseDependencies {
+    dependencies: Vec<serde_json::Value>,
+    versions: Vec<ApiDependentVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDependentVersion {
+    #[serde(rename = "crate")]
+    krate: String,
+    created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCrate {
+    #[serde(rename = "crate")]
+    krate: ApiCrateDetails,
+    versions: Vec<ApiVersion>,
+}
+
+impl ApiCrate {
+    fn releases_in(
+        self,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> Vec<CrateReleaseItem> {
+        let ApiCrate { krate, versions } = self;
+        versions
+            .into_iter()
+            .filter(|version| !version.yanked)
+            .filter_map(|version| {
+                let published = date(&version.created_at)?;
+                in_range(published, start_date, end_date)
+                    .then(|| CrateReleaseItem {
+                        crate_name: krate.name.clone(),
+                        version: version.num,
+                        description: krate
+                            .description
+                            .clone()
+                            .unwrap_or_default(),
+                        published,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCrateDetails {
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiVersion {
+    num: String,
+    created_at: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::{Path, Query},
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+    use time::macros::date;
+
+    fn version(
+        num: &str,
+        created_at: &str,
+    ) -> serde_json::Value {
+        json!({
+            "num": num,
+            "created_at": created_at,
+            "yanked": false,
+        })
+    }
+
+    /// A local crates.io with two pages of `bevy`
+    /// keyword results, one page of reverse
+    /// dependencies and the crates they mention
+    async fn fixture_crates_io() -> CratesIoClient {
+        async fn keyword(
+            Query(query): Query<HashMap<String, String>>,
+        ) -> Json<serde_json::Value> {
+            assert_eq!(query["keyword"], "bevy");
+            Json(match query["page"].as_str() {
+                "1" => json!({ "crates": [
+                    { "name": "bevy_tweening", "updated_at": "2024-02-12T10:00:00.000000+00:00" },
+                    { "name": "bevy_rapier3d", "updated_at": "2024-02-08T10:00:00.000000+00:00" },
+                ]}),
+                "2" => json!({ "crates": [
+                    { "name": "bevy_old", "updated_at": "2024-01-01T10:00:00.000000+00:00" },
+                    { "name": "bevy_never_read", "updated_at": "2024-02-12T10:00:00.000000+00:00" },
+                ]}),
+                page => {
+                    panic!("page {page} shouldn't be read")
+                }
+            })
+        }
+
+        async fn reverse_dependencies(
+            Query(query): Query<HashMap<String, String>>,
+        ) -> Json<serde_json::Value> {
+            assert_eq!(query["page"], "1");
+            Json(json!({
+                "dependencies": [{ "id": 1 }],
+                "versions": [
+                    { "crate": "leafwing-input-manager", "num": "0.13.0", "created_at": "2024-02-10T10:00:00.000000+00:00" },
+                    { "crate": "bevy_old_dependent", "num": "0.1.0", "created_at": "2023-02-10T10:00:00.000000+00:00" },
+                ],
+            }))
+        }
+
+        async fn krate(
+            Path(name): Path<String>,
+        ) -> Json<serde_json::Value> {
+            let versions = match name.as_str() {
+                "bevy_tweening" => vec![
+                    version(
+                        "0.10.0",
+                        "2024-02-12T10:00:00.000000+00:00",
+                    ),
+                    version(
+                        "0.9.0",
+                        "2023-11-12T10:00:00.000000+00:00",
+                    ),
+                ],
+                "bevy_rapier3d" => {
+                    let mut yanked = version(
+                        "0.25.1",
+                        "2024-02-09T10:00:00.000000+00:00",
+                    );
+                    yanked["yanked"] = json!(true);
+                    vec![
+                        yanked,
+                        version("0.25.0", "2024-02-08T10:00:00.000000+00:00"),
+                    ]
+                }
+                "leafwing-input-manager" => vec![version(
+                    "0.13.0",
+                    "2024-02-10T10:00:00.000000+00:00",
+                )],
+                name => panic!("{name} shouldn't be read"),
+            };
+            Json(json!({
+                "crate": {
+                    "name": name,
+                    "description": format!("{name} description"),
+                },
+                "versions": versions,
+            }))
+        }
+
+        let app = Router::new()
+            .route("/crates", get(keyword))
+            .route(
+                "/crates/bevy/reverse_dependencies",
+                get(reverse_dependencies),
+            )
+            .route("/crates/:name", get(krate));
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut client =
+            CratesIoClient::new(format!("http://{addr}"));
+        client.per_page = 2;
+        client.delay = Duration::ZERO;
+        client
+    }
+
+    #[tokio::test]
+    async fn finds_releases_in_range() {
+        let client = fixture_crates_io().await;
+
+        let progress = Mutex::new(vec![]);
+        let releases = client
+            .releases(
+                date!(2024 - 02 - 06),
+                date!(2024 - 02 - 13),
+                |p| progress.lock().unwrap().push(p),
+            )
+            .await
+            .unwrap();
+        let progress = progress.into_inner().unwrap();
+        assert_eq!(
+            progress.first(),
+            Some(&ImportProgress::FindingCrates)
+        );
+        assert_eq!(
+            progress.last().map(ToString::to_string),
+            Some(format!(
+                "checked {} of {} crates",
+                progress.len() - 2,
+                progress.len() - 1
+            ))
+        );
+
+        assert_eq!(
+            releases
+                .iter()
+                .map(CrateReleaseItem::title)
+                .collect::<Vec<_>>(),
+            vec![
+                "bevy_rapier3d 0.25.0",
+                "bevy_tweening 0.10.0",
+                "leafwing-input-manager 0.13.0",
+            ]
+        );
+        assert_eq!(
+            releases[1],
+            CrateReleaseItem {
+                crate_name: "bevy_tweening".to_string(),
+                version: "0.10.0".to_string(),
+                description: "bevy_tweening description"
+                    .to_string(),
+                published: date!(2024 - 02 - 12),
+            }
+        );
+        assert_eq!(
+            releases[1].url(),
+            "https://crates.io/crates/bevy_tweening/0.10.0"
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn importing_twice_skips_existing_releases() {
+        use crate::sql::test_db::DisposableSchema;
+
+        let schema = DisposableSchema::new().await;
+        let client = fixture_crates_io().await;
+        let range = (
+            date!(2024 - 02 - 06),
+            date!(2024 - 02 - 13),
+        );
+
+        let first = import(
+            &schema.pool,
+            &client,
+            range.0,
+            range.1,
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!((first.found, first.imported), (3, 3));
+
+        let second = import(
+            &schema.pool,
+            &client,
+            range.0,
+            range.1,
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!((second.found, second.imported), (3, 0));
+
+        let pending: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM crate_release WHERE review_status = 'pending' AND submitted_by = 'crates.io'",
+        )
+        .fetch_one(&schema.pool)
+        .await
+        .unwrap();
+        assert_eq!(pending, 3);
+
+        schema.drop().await;
+    }
+
+    #[tokio::test]
+    async fn one_background_import_at_a_time() {
+        // nothing listens here, so saving fails
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://root@127.0.0.1:1/twib")
+            .unwrap();
+        let client = fixture_crates_io().await;
+        let imports = BackgroundImport::default();
+        let range = (
+            date!(2024 - 02 - 06),
+            date!(2024 - 02 - 13),
+        );
+
+        assert!(imports.start(
+            pool.clone(),
+            client.clone(),
+            range.0,
+            range.1
+        ));
+        assert!(
+            !imports.start(pool, client, range.0, range.1)
+        );
+
+        let status = tokio::time::timeout(
+            Duration::from_secs(10),
+            async {
+                loop {
+                    match imports.status() {
+                        ImportStatus::Running(_) => {
+                            tokio::time::sleep(
+                                Duration::from_millis(10),
+                            )
+                            .await
+                        }
+                        status => return status,
+                    }
+                }
+            },
+        )
+        .await
+        .expect("the import to finish");
+        assert!(
+            matches!(status, ImportStatus::Failed(_)),
+            "{status:?}"
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Newsletter emails for published issues.
+//!
+//! Issues are loaded with the same query as the
+//! public issue page (`issue__showcase.sql`) and
+//! rendered through an MJML template into
+//! email-safe HTML, alongside a plain-text
+//! alternative for clients that don't render
+//! HTML.
+use crate::issue_content::{
+    entries, escape, fetch_issue, github_entries,
+    image_url, issue_url, SqlIssue,
+};
+use crate::markdown::{compile, TrustLevel};
+use crate::site_config::SiteConfig;
+use sqlx::MySqlPool;
+use std::fmt::Write;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmailError {
+    #[error(transparent)]
+    Sqlx(sqlx::Error),
+
+    #[error("no issue exists for {0}")]
+    NotFound(time::Date),
+
+    #[error("the issue for {0} is not published")]
+    NotPublished(time::Date),
+
+    #[error("failed to parse the mjml template: {0:?}")]
+    Parse(mrml::prelude::parser::Error),
+
+    #[error("failed to render the mjml template: {0:?}")]
+    Render(mrml::prelude::render::Error),
+}
+
+/// Load the issue for `date`, refusing drafts so
+/// unfinished issues can't be sent by accident.
+pub async fn fetch_published_issue(
+    pool: &MySqlPool,
+    date: time::Date,
+) -> Result<SqlIssue, EmailError> {
+    let status = sqlx::query_scalar!(
+        "SELECT status FROM issue WHERE issue_date = ?",
+        date
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(EmailError::Sqlx)?
+    .ok_or(EmailError::NotFound(date))?;
+
+    if status != "publish" {
+        return Err(EmailError::NotPublished(date));
+    }
+
+    fetch_issue(pool, date, true)
+        .await
+        .map_err(EmailError::Sqlx)?
+        .ok_or(EmailError::NotFound(date))
+}
+
+/// The rendered newsletter
+#[derive(Debug)]
+pub struct Email {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Render `issue` to both email-safe HTML and
+/// plain text. The configured site url is used
+/// for the "view on the web" links.
+pub fn render(
+    issue: &SqlIssue,
+    config: &SiteConfig,
+) -> Result<Email, EmailError> {
+    let root = mrml::parse(mjml(issue, config))
+        .map_err(EmailError::Parse)?;
+    let html = root
+        .render(
+            &mrml::prelude::render::RenderOptions::default(
+            ),
+        )
+        .map_err(EmailError::Render)?;
+    Ok(Email {
+        subject: format!(
+            "This Week in Bevy: {}",
+            issue.display_name
+        ),
+        html,
+        text: text(issue, config),
+    })
+}
+
+fn mjml(issue: &SqlIssue, config: &SiteConfig) -> String {
+    let web_url = issue_url(issue, config);
+    let mut body = String::new();
+
+    write!(
+        body,
+        r##"<mj-section><mj-column>
+<mj-text font-size="28px" font-weight="bold">{title}</mj-text>
+<mj-text color="#6c7086">{date}</mj-text>
+<mj-image src="{image}" alt="{title}" href="{web_url}" />
+<mj-text>{description}</mj-text>"##,
+        title = escape(&issue.display_name),
+        date = issue.issue_date,
+        image = escape(&image_url(
+            config,
+            &issue.cloudinary_public_id
+        )),
+        web_url = escape(&web_url),
+        description =
+            compile(&issue.description, TrustLevel::Admin),
+    )
+    .unwrap();
+    if !issue.youtube_id.is_empty() {
+        write!(
+            body,
+            r#"<mj-button href="https://www.youtube.com/watch?v={}">Watch on YouTube</mj-button>"#,
+            escape(&issue.youtube_id)
+        )
+        .unwrap();
+    }
+    body.push_str("</mj-column></mj-section>");
+
+    for (heading, entries) in entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        write!(
+            body,
+            r##"<mj-section><mj-column><mj-divider border-color="#6c7086" border-width="1px" /><mj-text font-size="22px" font-weight="bold">{}</mj-text></mj-column></mj-section>"##,
+            escape(heading)
+        )
+        .unwrap();
+        for entry in entries {
+            write!(
+                body,
+                r#"<mj-section><mj-column><mj-text font-size="18px" font-weight="bold"><a href="{url}">{title}</a></mj-text>"#,
+                url = escape(entry.url),
+                title = escape(entry.title),
+            )
+            .unwrap();
+            for image in entry.images {
+                write!(
+                    body,
+                    r#"<mj-image src="{}" alt="{}" />"#,
+                    escape(&image_url(
+                        config,
+                        &image.cloudinary_public_id
+                    )),
+                    escape(&image.description),
+                )
+                .unwrap();
+            }
+            write!(
+                body,
+                "<mj-text>{}</mj-text>",
+                compile(entry.description, entry.trust)
+            )
+            .unwrap();
+            let links = entry
+                .links
+                .iter()
+                .filter(|(_, url)| !url.is_empty())
+                .map(|(name, url)| {
+                    format!(
+                        r#"<a href="{}">{name}</a>"#,
+                        escape(url)
+                    )
+                })
+                .collect::<Vec<_>>();
+            if !links.is_empty() {
+                write!(
+                    body,
+                    "<mj-text>{}</mj-text>",
+                    links.join(" · ")
+                )
+                .unwrap();
+            }
+            body.push_str("</mj-column></mj-section>");
+        }
+    }
+
+    for (heading, entries) in github_entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        write!(
+            body,
+            r##"<mj-section><mj-column><mj-divider border-color="#6c7086" border-width="1px" /><mj-text font-size="22px" font-weight="bold">{}</mj-text><mj-text><ul>"##,
+            escape(heading)
+        )
+        .unwrap();
+        for entry in entries {
+            write!(
+                body,
+                r#"<li><a href="{}">{}</a> by <a href="{}">{}</a></li>"#,
+                escape(entry.url),
+                escape(entry.title),
+                escape(entry.author_url),
+                escape(entry.author),
+            )
+            .unwrap();
+        }
+        body.push_str(
+            "</ul></mj-text></mj-column></mj-section>",
+        );
+    }
+
+    format!(
+        r##"<mjml>
+<mj-head>
+<mj-title>This Week in Bevy: {title}</mj-title>
+<mj-preview>{title}</mj-preview>
+<mj-attributes><mj-all font-family="Helvetica, Arial, sans-serif" /><mj-text font-size="16px" line-height="1.5" /></mj-attributes>
+</mj-head>
+<mj-body width="600px">
+{body}
+<mj-section><mj-column><mj-text align="center" font-size="12px" color="#6c7086"><a href="{web_url}">View this issue on the web</a></mj-text></mj-column></mj-section>
+</mj-body>
+</mjml>"##,
+        title = escape(&issue.display_name),
+        web_url = escape(&web_url),
+    )
+}
+
+fn text(issue: &SqlIssue, config: &SiteConfig) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "This Week in Bevy: {}",
+        issue.display_name
+    )
+    .unwrap();
+    writeln!(out, "{}", issue.issue_date).unwrap();
+    writeln!(out, "{}\n", issue_url(issue, config))
+        .unwrap();
+    // markdown reads fine as plain text
+    writeln!(out, "{}", issue.description.trim()).unwrap();
+    if !issue.youtube_id.is_empty() {
+        writeln!(
+            out,
+            "\nWatch on YouTube: https://www.youtube.com/watch?v={}",
+            issue.youtube_id
+        )
+        .unwrap();
+    }
+
+    for (heading, entries) in entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n## {heading}").unwrap();
+        for entry in entries {
+            writeln!(
+                out,
+                "\n### {}\n{}",
+                entry.title, entry.url
+            )
+            .unwrap();
+            for (name, url) in &entry.links {
+                if !url.is_empty() {
+                    writeln!(out, "{name}: {url}").unwrap();
+                }
+            }
+            writeln!(out, "\n{}", entry.description.trim())
+                .unwrap();
+        }
+    }
+
+    for (heading, entries) in github_entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n## {heading}\n").unwrap();
+        for entry in entries {
+            writeln!(
+                out,
+                "- {} by {} ({})",
+                entry.title, entry.author, entry.url
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issue_content::tests::issue;
+
+    #[test]
+    fn renders_html_sections() {
+        let email =
+            render(&issue(), &SiteConfig::default())
+                .unwrap();
+        assert_eq!(
+            "This Week in Bevy: Bevy <3",
+            email.subject
+        );
+        assert!(email.html.contains("Bevy &lt;3"));
+        assert!(email
+            .html
+            .contains("<strong>big</strong>"));
+        assert!(email.html.contains("A Game"));
+        assert!(email.html.contains("Fix the thing"));
+        assert!(email.html.contains(
+            "https://thisweekinbevy.com/issue/2024-02-12-some-slug"
+        ));
+        // empty sections are left out
+        assert!(!email.html.contains("Devlogs"));
+    }
+
+    #[test]
+    fn descriptions_are_rendered_like_the_site() {
+        let mut issue = issue();
+        issue.description =
+            "Thanks @alice\n\n> [!NOTE]\n> Careful\n"
+                .to_string();
+        let email =
+            render(&issue, &SiteConfig::default()).unwrap();
+        assert!(email.html.contains(
+            r#"href="https://github.com/alice""#
+        ));
+        assert!(email.html.contains(">Note</h3>"));
+        assert!(!email.html.contains("[!NOTE]"));
+    }
+
+    #[test]
+    fn renders_plain_text() {
+        let email =
+            render(&issue(), &SiteConfig::default())
+                .unwrap();
+        assert!(!email.text.contains("<strong>"));
+        assert!(email.text.contains("## Showcase"));
+        assert!(email.text.contains(
+            "- Fix the thing by alice (https://github.com/bevyengine/bevy/pull/12345)"
+        ));
+    }
+}
@@ -0,0 +1,800 @@
+//! The feed model shared by every feed the site
+//! serves.
+//!
+//! Issues and the entries in each section are
+//! loaded into a [`SiteFeed`], which the Atom
+//! (`atom_feed`) and JSON Feed (`json_feed`)
+//! routes render. Responses carry an `ETag` and
+//! `Last-Modified` so readers can poll with
+//! conditional GETs.
+use crate::{
+    app::routes::issue::anchor_id,
+    issue_content::{
+        entries, escape, fetch_published_issues,
+        github_entries, image_url, issue_url, plain_text,
+        SqlIssue,
+    },
+    markdown::{compile, TrustLevel},
+    site_config::{Author, SiteConfig},
+    submissions::SubmissionKind,
+};
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use data_encoding::HEXLOWER;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use std::fmt::Write;
+use time::{
+    format_description::FormatItem,
+    macros::format_description, OffsetDateTime,
+    PrimitiveDateTime,
+};
+use tracing::error;
+
+pub const SITE_TITLE: &str = "This Week in Bevy";
+pub const SITE_DESCRIPTION: &str =
+    "What happened this week in the Bevy Engine ecosystem";
+pub const LOGO_URL: &str = "https://res.cloudinary.com/dilgcuzda/image/upload/v1708481576/thisweekinbevy/this-week-in-bevylight_uddwes.avif";
+pub const ICON_URL: &str =
+    "https://cdn.thisweekinbevy.com/favicon-32x32.png";
+
+/// The number of items in a feed when no
+/// `?limit=` is given
+const DEFAULT_LIMIT: u32 = 5;
+/// Issue feeds hold every issue in full, so keep
+/// feeds to a reasonable size
+const MAX_LIMIT: u32 = 50;
+
+/// The IMF-fixdate format used by HTTP date
+/// headers, ex: `Sun, 06 Nov 1994 08:49:37 GMT`
+const HTTP_DATE_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FeedParams {
+    limit: Option<u32>,
+}
+
+impl FeedParams {
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, MAX_LIMIT)
+    }
+}
+
+/// A feed, independent of the format it's
+/// served in
+#[derive(Debug)]
+pub struct SiteFeed {
+    pub title: String,
+    pub home_page_url: String,
+    pub description: String,
+    pub author: Author,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug)]
+pub struct FeedItem {
+    /// a permalink, which is also the item's url
+    pub url: String,
+    pub title: String,
+    pub summary_html: Option<String>,
+    /// the summary without markup, for formats
+    /// that only allow plain text
+    pub summary_text: Option<String>,
+    pub content_html: String,
+    pub image: Option<String>,
+    pub published: OffsetDateTime,
+    pub updated: OffsetDateTime,
+}
+
+impl SiteFeed {
+    /// The most recent update to any item, which
+    /// is `None` for an empty feed
+    pub fn updated(&self) -> Option<OffsetDateTime> {
+        self.items.iter().map(|item| item.updated).max()
+    }
+}
+
+/// Each section's slug in its feed url and the
+/// feed's title
+fn section_info(
+    kind: SubmissionKind,
+) -> (&'static str, &'static str) {
+    match kind {
+        SubmissionKind::Showcase => {
+            ("showcases", "Showcases")
+        }
+        SubmissionKind::CrateRelease => {
+            ("crate-releases", "Crate Releases")
+        }
+        SubmissionKind::Devlog => ("devlogs", "Devlogs"),
+        SubmissionKind::Educational => {
+            ("educational", "Educational")
+        }
+    }
+}
+
+/// The url a section's feed is served at, ex:
+/// `/feeds/crate-releases.xml`
+pub fn section_slug(kind: SubmissionKind) -> &'static str {
+    section_info(kind).0
+}
+
+pub fn section_from_slug(
+    slug: &str,
+) -> Option<SubmissionKind> {
+    SubmissionKind::ALL
+        .into_iter()
+        .find(|kind| section_slug(*kind) == slug)
+}
+
+fn section_title(kind: SubmissionKind) -> &'static str {
+    section_info(kind).1
+}
+
+/// Issues go out at 12:30 UTC on their issue date
+fn published_at(issue_date: time::Date) -> OffsetDateTime {
+    PrimitiveDateTime::new(
+        issue_date,
+        time::macros::time!(12:30),
+    )
+    .assume_utc()
+}
+
+/// The most recent published issues, in full
+pub async fn issues(
+    pool: &MySqlPool,
+    config: &SiteConfig,
+    limit: u32,
+) -> Result<SiteFeed, sqlx::Error> {
+    let items = fetch_published_issues(pool, limit)
+        .await?
+        .iter()
+        .map(|issue| issue_item(issue, config))
+        .collect();
+    Ok(SiteFeed {
+        title: SITE_TITLE.to_string(),
+        home_page_url: config.site_url.join("/"),
+        description: SITE_DESCRIPTION.to_string(),
+        author: config.author.clone(),
+        items,
+    })
+}
+
+fn issue_item(
+    issue: &SqlIssue,
+    config: &SiteConfig,
+) -> FeedItem {
+    let published = published_at(issue.issue_date);
+    FeedItem {
+        url: issue_url(issue, config),
+        title: issue.display_name.clone(),
+        summary_html: Some(compile(
+            &issue.description,
+            TrustLevel::Admin,
+        )),
+        summary_text: Some(plain_text(&issue.description)),
+        content_html: issue_content_html(issue, config),
+        image: Some(image_url(
+            config,
+            &issue.cloudinary_public_id,
+        )),
+        published,
+        // edits after publishing shouldn't move an
+        // issue back in time
+        updated: issue.updated_at.max(published),
+    }
+}
+
+#[derive(Debug)]
+struct SqlSectionEntry {
+    title: String,
+    url: String,
+    description: String,
+    updated_at: OffsetDateTime,
+    submitted_by: Option<String>,
+    submitter_role: Option<String>,
+    slug: String,
+    issue_date: time::Date,
+    display_name: String,
+}
+
+/// The most recent entries in one section of
+/// published issues, each linking back to the
+/// issue it was in
+pub async fn section(
+    pool: &MySqlPool,
+    config: &SiteConfig,
+    kind: SubmissionKind,
+    limit: u32,
+) -> Result<SiteFeed, sqlx::Error> {
+    let rows = match kind {
+        SubmissionKind::Showcase => {
+            sqlx::query_as!(
+                SqlSectionEntry,
+                r#"SELECT
+    showcase.title,
+    showcase.url,
+    showcase.description,
+    showcase.updated_at,
+    showcase.submitted_by,
+    github_users.role as submitter_role,
+    issue.slug,
+    issue.issue_date,
+    issue.display_name
+FROM showcase
+INNER JOIN issue__showcase
+  ON issue__showcase.showcase_id = showcase.id
+INNER JOIN issue
+  ON issue.id = issue__showcase.issue_id
+LEFT JOIN github_users
+  ON github_users.username = showcase.submitted_by
+WHERE issue.status = "publish"
+ORDER BY issue.issue_date DESC, showcase.title
+LIMIT ?"#,
+                limit
+            )
+            .fetch_all(pool)
+            .await
+        }
+        SubmissionKind::CrateRelease => {
+            sqlx::query_as!(
+                SqlSectionEntry,
+                r#"SELECT
+    crate_release.title,
+    crate_release.url,
+    crate_release.description,
+    crate_release.updated_at,
+    crate_release.submitted_by,
+    github_users.role as submitter_role,
+    issue.slug,
+    issue.issue_date,
+    issue.display_name
+FROM crate_release
+INNER JOIN issue__crate_release
+  ON issue__crate_release.crate_release_id = crate_release.id
+INNER JOIN issue
+  ON issue.id = issue__crate_release.issue_id
+LEFT JOIN github_users
+  ON github_users.username = crate_release.submitted_by
+WHERE issue.status = "publish"
+ORDER BY issue.issue_date DESC, crate_release.title
+LIMIT ?"#,
+                limit
+            )
+            .fetch_all(pool)
+            .await
+        }
+        SubmissionKind::Devlog => {
+            sqlx::query_as!(
+                SqlSectionEntry,
+                r#"SELECT
+    devlog.title,
+    devlog.post_url as url,
+    devlog.description,
+    devlog.updated_at,
+    devlog.submitted_by,
+    github_users.role as submitter_role,
+    issue.slug,
+    issue.issue_date,
+    issue.display_name
+FROM devlog
+INNER JOIN issue__devlog
+  ON issue__devlog.devlog_id = devlog.id
+INNER JOIN issue
+  ON issue.id = issue__devlog.issue_id
+LEFT JOIN github_users
+  ON github_users.username = devlog.submitted_by
+WHERE issue.status = "publish"
+ORDER BY issue.issue_date DESC, devlog.title
+LIMIT ?"#,
+                limit
+            )
+            .fetch_all(pool)
+            .await
+        }
+        SubmissionKind::Educational => {
+            sqlx::query_as!(
+                SqlSectionEntry,
+                r#"SELECT
+    educational.title,
+    educational.post_url as url,
+    educational.description,
+    educational.updated_at,
+    educational.submitted_by,
+    github_users.role as submitter_role,
+    issue.slug,
+    issue.issue_date,
+    issue.display_name
+FROM educational
+INNER JOIN issue__educational
+  ON issue__educational.educational_id = educational.id
+INNER JOIN issue
+  ON issue.id = issue__educational.issue_id
+LEFT JOIN github_users
+  ON github_users.username = educational.submitted_by
+WHERE issue.status = "publish"
+ORDER BY issue.issue_date DESC, educational.title
+LIMIT ?"#,
+                limit
+            )
+            .fetch_all(pool)
+            .await
+        }
+    }?;
+
+    Ok(SiteFeed {
+        title: format!(
+            "{SITE_TITLE}: {}",
+            section_title(kind)
+        ),
+        home_page_url: config.site_url.join("/"),
+        description: format!(
+            "{} from {SITE_TITLE}",
+            section_title(kind)
+        ),
+        author: config.author.clone(),
+        items: rows
+            .into_iter()
+            .map(|row| section_item(kind, row, config))
+            .collect(),
+    })
+}
+
+fn section_item(
+    kind: SubmissionKind,
+    entry: SqlSectionEntry,
+    config: &SiteConfig,
+) -> FeedItem {
+    let issue_url = config
+        .site_url
+        .join(&format!("/issue/{}", entry.slug));
+    let published = published_at(entry.issue_date);
+
+    let mut content_html = compile(
+        &entry.description,
+        TrustLevel::of_submitter(
+            entry.submitted_by.as_deref(),
+            entry.submitter_role.as_deref(),
+        ),
+    );
+    if !entry.url.is_empty() {
+        write!(
+            content_html,
+            r#"<p><a href="{}">{}</a></p>"#,
+            escape(&entry.url),
+            escape(&entry.title),
+        )
+        .unwrap();
+    }
+    write!(
+        content_html,
+        r#"<p>From <a href="{}">{}</a></p>"#,
+        escape(&issue_url),
+        escape(&entry.display_name),
+    )
+    .unwrap();
+
+    FeedItem {
+        url: format!(
+            "{issue_url}#{}",
+            anchor_id(kind.as_str(), &entry.title)
+        ),
+        title: entry.title,
+        summary_html: None,
+        summary_text: None,
+        content_html,
+        image: None,
+        published,
+        updated: entry.updated_at.max(published),
+    }
+}
+
+/// Render `feed` and answer with it, or with a
+/// `304 Not Modified` if the reader's copy is
+/// current.
+pub fn respond(
+    request_headers: &HeaderMap,
+    feed: Result<SiteFeed, sqlx::Error>,
+    content_type: &'static str,
+    render: impl FnOnce(&SiteFeed) -> String,
+) -> Response {
+    let feed = match feed {
+        Ok(feed) => feed,
+        Err(e) => {
+            error!(?e, "failed to load feed");
+            return StatusCode::INTERNAL_SERVER_ERROR
+                .into_response();
+        }
+    };
+
+    // HTTP dates only have second precision
+    let last_modified = feed
+        .updated()
+        .and_then(|date| date.replace_nanosecond(0).ok());
+    let body = render(&feed);
+    let etag = etag(&body);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(content_type),
+    );
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Some(value) = last_modified
+        .and_then(http_date)
+        .and_then(|date| HeaderValue::from_str(&date).ok())
+    {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+
+    if is_not_modified(
+        request_headers,
+        &etag,
+        last_modified,
+    ) {
+        return (StatusCode::NOT_MODIFIED, headers)
+            .into_response();
+    }
+    (headers, body).into_response()
+}
+
+/// The whole issue as HTML, in the same order as
+/// the issue page
+fn issue_content_html(
+    issue: &SqlIssue,
+    config: &SiteConfig,
+) -> String {
+    let mut html = String::new();
+
+    write!(
+        html,
+        r#"<p><img src="{}" alt="{}" /></p>{}"#,
+        escape(&image_url(
+            config,
+            &issue.cloudinary_public_id
+        )),
+        escape(&issue.display_name),
+        compile(&issue.description, TrustLevel::Admin),
+    )
+    .unwrap();
+    if !issue.youtube_id.is_empty() {
+        write!(
+            html,
+            r#"<p><a href="https://www.youtube.com/watch?v={}">Watch on YouTube</a></p>"#,
+            escape(&issue.youtube_id)
+        )
+        .unwrap();
+    }
+
+    for (heading, entries) in entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        write!(html, "<h2>{}</h2>", escape(heading))
+            .unwrap();
+        for entry in entries {
+            write!(
+                html,
+                r#"<h3><a href="{}">{}</a></h3>"#,
+                escape(entry.url),
+                escape(entry.title),
+            )
+            .unwrap();
+            for image in entry.images {
+                write!(
+                    html,
+                    r#"<p><img src="{}" alt="{}" /></p>"#,
+                    escape(&image_url(
+                        config,
+                        &image.cloudinary_public_id
+                    )),
+                    escape(&image.description),
+                )
+                .unwrap();
+            }
+            html.push_str(&compile(
+                entry.description,
+                entry.trust,
+            ));
+            let links = entry
+                .links
+                .iter()
+                .filter(|(_, url)| !url.is_empty())
+                .map(|(name, url)| {
+                    format!(
+                        r#"<a href="{}">{name}</a>"#,
+                        escape(url)
+                    )
+                })
+                .collect::<Vec<_>>();
+            if !links.is_empty() {
+                write!(
+                    html,
+                    "<p>{}</p>",
+                    links.join(" · ")
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    for (heading, entries) in github_entries(issue) {
+        if entries.is_empty() {
+            continue;
+        }
+        write!(html, "<h2>{}</h2><ul>", escape(heading))
+            .unwrap();
+        for entry in entries {
+            write!(
+                html,
+                r#"<li><a href="{}">{}</a> by <a href="{}">{}</a></li>"#,
+                escape(entry.url),
+                escape(entry.title),
+                escape(entry.author_url),
+                escape(entry.author),
+            )
+            .unwrap();
+        }
+        html.push_str("</ul>");
+    }
+    html
+}
+
+/// A strong validator for `body`. The hash has
+/// to be stable across releases and restarts,
+/// or every reader would refetch every feed
+/// after a deploy.
+fn etag(body: &str) -> String {
+    let hash = Sha256::digest(body.as_bytes());
+    format!("\"{}\"", HEXLOWER.encode(&hash[..16]))
+}
+
+fn http_date(date: OffsetDateTime) -> Option<String> {
+    date.to_offset(time::UtcOffset::UTC)
+        .format(HTTP_DATE_FORMAT)
+        .ok()
+}
+
+fn parse_http_date(date: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(date.trim(), HTTP_DATE_FORMAT)
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
+
+/// `If-None-Match` takes precedence over
+/// `If-Modified-Since`, as in RFC 9110
+fn is_not_modified(
+    headers: &HeaderMap,
+    etag: &str,
+    last_modified: Option<OffsetDateTime>,
+) -> bool {
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return if_none_match.split(',').any(|tag| {
+            let tag = tag.trim();
+            tag == "*"
+                || tag.strip_prefix("W/").unwrap_or(tag)
+                    == etag
+        });
+    }
+    let since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_http_date);
+    matches!(
+        (since, last_modified),
+        (Some(since), Some(last_modified))
+            if last_modified <= since
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issue_content::tests::issue;
+    use crate::site_config::SiteUrl;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn limit_defaults_and_clamps() {
+        let limit = |limit| FeedParams { limit }.limit();
+        assert_eq!(limit(None), DEFAULT_LIMIT);
+        assert_eq!(limit(Some(0)), 1);
+        assert_eq!(limit(Some(12)), 12);
+        assert_eq!(limit(Some(1000)), MAX_LIMIT);
+    }
+
+    #[test]
+    fn sections_round_trip_through_slugs() {
+        for kind in SubmissionKind::ALL {
+            assert_eq!(
+                section_from_slug(section_slug(kind)),
+                Some(kind)
+            );
+        }
+        assert_eq!(section_from_slug("issues"), None);
+    }
+
+    #[test]
+    fn issue_content_includes_every_section() {
+        let html = issue_content_html(
+            &issue(),
+            &SiteConfig::default(),
+        );
+        assert!(html.contains("<strong>big</strong>"));
+        assert!(html.contains("<h2>Showcase</h2>"));
+        assert!(html.contains(
+            r#"<a href="https://example.com/game">A Game</a>"#
+        ));
+        assert!(html.contains("<em>bevy</em>"));
+        assert!(html.contains("Fix the thing"));
+        assert!(html.contains(r#"alt="Bevy &lt;3""#));
+        // empty sections are left out
+        assert!(!html.contains("Devlogs"));
+    }
+
+    #[test]
+    fn issues_are_updated_when_edited() {
+        let item =
+            issue_item(&issue(), &SiteConfig::default());
+        assert_eq!(
+            item.url,
+            "https://thisweekinbevy.com/issue/2024-02-12-some-slug"
+        );
+        assert_eq!(
+            item.published,
+            datetime!(2024-02-12 12:30 UTC)
+        );
+        assert_eq!(
+            item.updated,
+            datetime!(2024-02-14 09:00 UTC)
+        );
+
+        // drafts edited before publishing
+        let item = issue_item(
+            &SqlIssue {
+                updated_at: datetime!(2024-02-10 09:00 UTC),
+                ..issue()
+            },
+            &SiteConfig::default(),
+        );
+        assert_eq!(item.updated, item.published);
+    }
+
+    #[test]
+    fn section_entries_link_to_their_issue() {
+        let item = section_item(
+            SubmissionKind::CrateRelease,
+            SqlSectionEntry {
+                title: "bevy_ecs_tilemap 0.13".to_string(),
+                url: "https://crates.io/crates/bevy_ecs_tilemap"
+                    .to_string(),
+                description: "tiles! see #123".to_string(),
+                updated_at: datetime!(2024-02-11 09:00 UTC),
+                submitted_by: Some("crates.io".to_string()),
+                submitter_role: None,
+                slug: "2024-02-12-some-slug".to_string(),
+                issue_date: date!(2024 - 02 - 12),
+                display_name: "Bevy <3".to_string(),
+            },
+            &SiteConfig {
+                site_url: SiteUrl::new(
+                    "https://staging.example.com",
+                ),
+                ..SiteConfig::default()
+            },
+        );
+        assert_eq!(
+            item.url,
+            "https://staging.example.com/issue/2024-02-12-some-slug#crate-release-bevy-ecs-tilemap-0-13"
+        );
+        assert!(item.content_html.contains(
+            r#"<a href="https://crates.io/crates/bevy_ecs_tilemap">"#
+        ));
+        assert!(item
+            .content_html
+            .contains(">Bevy &lt;3</a>"));
+        assert!(item.content_html.contains(
+            r#"<a href="https://github.com/bevyengine/bevy/pull/123">"#
+        ));
+        assert_eq!(
+            item.updated,
+            datetime!(2024-02-12 12:30 UTC)
+        );
+    }
+
+    #[test]
+    fn http_dates_round_trip() {
+        let date = datetime!(1994-11-06 08:49:37 UTC);
+        let formatted = http_date(date).unwrap();
+        assert_eq!(
+            formatted,
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert_eq!(parse_http_date(&formatted), Some(date));
+    }
+
+    #[test]
+    fn etags_are_stable_across_releases() {
+        assert_eq!(
+            etag("<feed/>"),
+            "\"189c4a8be44abcf73a70a950ceeedddf\""
+        );
+    }
+
+    #[test]
+    fn conditional_requests() {
+        let etag = etag("<feed/>");
+        let modified = datetime!(2024-02-14 09:00 UTC);
+        let headers =
+            |pairs: &[(header::HeaderName, &str)]| {
+                let mut headers = HeaderMap::new();
+                for (name, value) in pairs {
+                    headers.insert(
+                        name.clone(),
+                        HeaderValue::from_str(value)
+                            .unwrap(),
+                    );
+                }
+                headers
+            };
+
+        assert!(!is_not_modified(
+            &headers(&[]),
+            &etag,
+            Some(modified)
+        ));
+        assert!(is_not_modified(
+            &headers(&[(header::IF_NONE_MATCH, &etag)]),
+            &etag,
+            Some(modified)
+        ));
+        assert!(is_not_modified(
+            &headers(&[(
+                header::IF_NONE_MATCH,
+                &format!("\"other\", W/{etag}")
+            )]),
+            &etag,
+            Some(modified)
+        ));
+        // a stale etag wins over a matching date
+        assert!(!is_not_modified(
+            &headers(&[
+                (header::IF_NONE_MATCH, "\"other\""),
+                (
+                    header::IF_MODIFIED_SINCE,
+                    "Wed, 14 Feb 2024 09:00:00 GMT"
+                ),
+            ]),
+            &etag,
+            Some(modified)
+        ));
+        assert!(is_not_modified(
+            &headers(&[(
+                header::IF_MODIFIED_SINCE,
+                "Wed, 14 Feb 2024 09:00:00 GMT"
+            )]),
+            &etag,
+            Some(modified)
+        ));
+        assert!(!is_not_modified(
+            &headers(&[(
+                header::IF_MODIFIED_SINCE,
+                "Tue, 13 Feb 2024 09:00:00 GMT"
+            )]),
+            &etag,
+            Some(modified)
+        ));
+    }
+}
@@ -0,0 +1,696 @@
+//! Pulls merged pull requests, new pull requests
+//! and new issues for `bevyengine/bevy` from the
+//! GitHub REST API and upserts them into the
+//! `merged_pull_request`, `new_pull_request` and
+//! `new_github_issue` tables.
+//!
+//! The admin GitHub page then links those rows to
+//! an issue by date range.
+use crate::issue_date::parse_issue_date;
+use axum::http::header::{
+    ACCEPT, AUTHORIZATION, USER_AGENT,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::MySqlPool;
+use tracing::{info, instrument};
+
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+const REPO: &str = "bevyengine/bevy";
+
+#[derive(Debug, thiserror::Error)]
+pub enum IngestError {
+    #[error(transparent)]
+    Sqlx(sqlx::Error),
+
+    #[error(transparent)]
+    Reqwest(reqwest::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    per_page: usize,
+}
+
+impl GitHubClient {
+    /// `base_url` is `https://api.github.com` in
+    /// production and a local mock server in
+    /// tests.
+    pub fn new(
+        base_url: impl Into<String>,
+        token: Option<String>,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url
+                .into()
+                .trim_end_matches('/')
+                .to_string(),
+            token,
+            per_page: 100,
+        }
+    }
+
+    /// Reads `GITHUB_API_URL` (defaulting to
+    /// api.github.com) and an optional
+    /// `GITHUB_TOKEN`. Unauthenticated requests
+    /// work, but are heavily rate limited.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("GITHUB_API_URL").unwrap_or_else(
+                |_| DEFAULT_API_URL.to_string(),
+            ),
+            std::env::var("GITHUB_TOKEN").ok(),
+        )
+    }
+
+    async fn page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        page: usize,
+    ) -> Result<Vec<T>, reqwest::Error> {
+        let mut request = self
+            .http
+            .get(format!(
+                "{}/repos/{REPO}/{path}",
+                self.base_url
+            ))
+            .query(query)
+            .query(&[
+                ("per_page", self.per_page.to_string()),
+                ("page", page.to_string()),
+            ])
+            // See: https://docs.github.com/en/rest/overview/resources-in-the-rest-api?apiVersion=2022-11-28#user-agent-required
+            .header(
+                USER_AGENT.as_str(),
+                "this-week-in-bevy",
+            )
+            .header(
+                ACCEPT.as_str(),
+                "application/vnd.github+json",
+            );
+        if let Some(token) = &self.token {
+            request = request.header(
+                AUTHORIZATION.as_str(),
+                format!("Bearer {token}"),
+            );
+        }
+        request
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Pages through a listing sorted
+    /// newest-first, stopping once
+    /// `before_range` says an item is
+    /// older than the range we care about.
+    async fn collect<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        before_range: impl Fn(&T) -> bool,
+    ) -> Result<Vec<T>, reqwest::Error> {
+        let mut items = vec![];
+        for page in 1.. {
+            let results: Vec<T> =
+                self.page(path, query, page).await?;
+            let count = results.len();
+            let mut done = count < self.per_page;
+            for item in results {
+                if before_range(&item) {
+                    done = true;
+                    break;
+                }
+                items.push(item);
+            }
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// Pull requests merged between `start_date`
+    /// and `end_date`, inclusive.
+    #[instrument(skip(self))]
+    pub async fn merged_pull_requests(
+        &self,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> Result<Vec<GitHubItem>, IngestError> {
+        // there is no "sort by merged", but a merge
+        // always updates the pull request, so
+        // anything last updated before the range
+        // can't have been merged inside it.
+        let pulls: Vec<ApiPullRequest> = self
+            .collect(
+                "pulls",
+                &[
+                    ("state", "closed"),
+                    ("sort", "updated"),
+                    ("direction", "desc"),
+                ],
+                |pr: &ApiPullRequest| {
+                    date(&pr.updated_at)
+                        .is_some_and(|d| d < start_date)
+                },
+            )
+            .await
+            .map_err(IngestError::Reqwest)?;
+
+        Ok(pulls
+            .into_iter()
+            .filter_map(|pr| {
+                let merged_at =
+                    date(pr.merged_at.as_deref()?)?;
+                in_range(merged_at, start_date, end_date)
+                    .then(|| pr.into_item(merged_at))
+            })
+            .collect())
+    }
+
+    /// Pull requests opened between `start_date`
+    /// and `end_date`, inclusive.
+    #[instrument(skip(self))]
+    pub async fn new_pull_requests(
+        &self,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> Result<Vec<GitHubItem>, IngestError> {
+        let pulls: Vec<ApiPullRequest> = self
+            .collect(
+                "pulls",
+                &[
+                    ("state", "all"),
+                    ("sort", "created"),
+                    ("direction", "desc"),
+                ],
+                |pr: &ApiPullRequest| {
+                    date(&pr.created_at)
+                        .is_some_and(|d| d < start_date)
+                },
+            )
+            .await
+            .map_err(IngestError::Reqwest)?;
+
+        Ok(pulls
+            .into_iter()
+            .filter_map(|pr| {
+                let created_at = date(&pr.created_at)?;
+                in_range(created_at, start_date, end_date)
+                    .then(|| pr.into_item(created_at))
+            })
+            .collect())
+    }
+
+    /// Issues opened between `start_date` and
+    /// `end_date`, inclusive. The issues endpoint
+    /// also returns pull requests, which are
+    /// skipped here.
+    #[instrument(skip(self))]
+    pub async fn new_issues(
+        &self,
+        start_date: time::Date,
+        end_date: time::Date,
+    ) -> Result<Vec<GitHubItem>, IngestError> {
+        let since = format!("{start_date}T00:00:00Z");
+        let issues: Vec<ApiIssue> = self
+            .collect(
+                "issues",
+                &[
+                    ("state", "all"),
+                    ("sort", "created"),
+                    ("direction", "desc"),
+                    ("since", &since),
+                ],
+                |issue: &ApiIssue| {
+                    date(&issue.created_at)
+                        .is_some_and(|d| d < start_date)
+                },
+            )
+            .await
+            .map_err(IngestError::Reqwest)?;
+
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .filter_map(|issue| {
+                let created_at = date(&issue.created_at)?;
+                in_range(created_at, start_date, end_date)
+                    .then(|| issue.into_item(created_at))
+            })
+            .collect())
+    }
+}
+
+/// A pull request or issue, flattened into the
+/// shape the GitHub tables store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubItem {
+    pub github_id: String,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub author_url: String,
+    pub date: time::Date,
+    pub labels: serde_json::Value,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IngestCounts {
+    pub merged_pull_requests: usize,
+    pub new_pull_requests: usize,
+    pub new_github_issues: usize,
+}
+
+/// Fetch everything in the date range and upsert
+/// it. Re-running over the same range refreshes
+/// titles and labels rather than duplicating
+/// rows.
+#[instrument(skip(pool, client))]
+pub async fn ingest(
+    pool: &MySqlPool,
+    client: &GitHubClient,
+    start_date: time::Date,
+    end_date: time::Date,
+) -> Result<IngestCounts, IngestError> {
+    let merged = client
+        .merged_pull_requests(start_date, end_date)
+        .await?;
+    upsert_merged_pull_requests(pool, &merged)
+        .await
+        .map_err(IngestError::Sqlx)?;
+
+    let opened = client
+        .new_pull_requests(start_date, end_date)
+        .await?;
+    upsert_new_pull_requests(pool, &opened)
+        .await
+        .map_err(IngestError::Sqlx)?;
+
+    let issues =
+        client.new_issues(start_date, end_date).await?;
+    upsert_new_github_issues(pool, &issues)
+        .await
+        .map_err(IngestError::Sqlx)?;
+
+    let counts = IngestCounts {
+        merged_pull_requests: merged.len(),
+        new_pull_requests: opened.len(),
+        new_github_issues: issues.len(),
+    };
+    info!(?counts, "ingested from github");
+    Ok(counts)
+}
+
+async fn upsert_merged_pull_requests(
+    pool: &MySqlPool,
+    items: &[GitHubItem],
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        let id: [u8; 16] =
+            rusty_ulid::generate_ulid_bytes();
+        sqlx::query!(
+            r#"
+    INSERT INTO merged_pull_request ( id, github_id, title, url, author, author_url, merged_at_date, labels )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, ? )
+    ON DUPLICATE KEY UPDATE
+        title = VALUES(title),
+        url = VALUES(url),
+        author = VALUES(author),
+        author_url = VALUES(author_url),
+        merged_at_date = VALUES(merged_at_date),
+        labels = VALUES(labels)
+        "#,
+            id.as_slice(),
+            item.github_id,
+            item.title,
+            item.url,
+            item.author,
+            item.author_url,
+            item.date,
+            item.labels
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn upsert_new_pull_requests(
+    pool: &MySqlPool,
+    items: &[GitHubItem],
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        let id: [u8; 16] =
+            rusty_ulid::generate_ulid_bytes();
+        sqlx::query!(
+            r#"
+    INSERT INTO new_pull_request ( id, github_id, title, url, author, author_url, gh_created_at, labels )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, ? )
+    ON DUPLICATE KEY UPDATE
+        title = VALUES(title),
+        url = VALUES(url),
+        author = VALUES(author),
+        author_url = VALUES(author_url),
+        gh_created_at = VALUES(gh_created_at),
+        labels = VALUES(labels)
+        "#,
+            id.as_slice(),
+            item.github_id,
+            item.title,
+            item.url,
+            item.author,
+            item.author_url,
+            item.date,
+            item.labels
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn upsert_new_github_issues(
+    pool: &MySqlPool,
+    items: &[GitHubItem],
+) -> Result<(), sqlx::Error> {
+    for item in items {
+        let id: [u8; 16] =
+            rusty_ulid::generate_ulid_bytes();
+        sqlx::query!(
+            r#"
+    INSERT INTO new_github_issue ( id, github_id, title, url, author, author_url, gh_created_at, labels )
+    VALUES ( ?, ?, ?, ?, ?, ?, ?, ? )
+    ON DUPLICATE KEY UPDATE
+        title = VALUES(title),
+        url = VALUES(url),
+        author = VALUES(author),
+        author_url = VALUES(author_url),
+        gh_created_at = VALUES(gh_created_at),
+        labels = VALUES(labels)
+        "#,
+            id.as_slice(),
+            item.github_id,
+            item.title,
+            item.url,
+            item.author,
+            item.author_url,
+            item.date,
+            item.labels
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// GitHub timestamps look like
+/// `2024-02-12T09:30:00Z`, and we only store the
+/// date.
+fn date(timestamp: &str) -> Option<time::Date> {
+    timestamp.get(0..10).and_then(parse_issue_date)
+}
+
+fn in_range(
+    date: time::Date,
+    start_date: time::Date,
+    end_date: time::Date,
+) -> bool {
+    start_date <= date && date <= end_date
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ApiLabel {
+    name: String,
+    color: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    number: u64,
+    title: String,
+    html_url: String,
+    user: Option<ApiUser>,
+    #[serde(default)]
+    labels: Vec<ApiLabel>,
+    created_at: String,
+    updated_at: String,
+    merged_at: Option<String>,
+}
+
+impl ApiPullRequest {
+    fn into_item(self, date: time::Date) -> GitHubItem {
+        item(
+            self.number,
+            self.title,
+            self.html_url,
+            self.user,
+            self.labels,
+            date,
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIssue {
+    number: u64,
+    title: String,
+    html_url: String,
+    user: Option<ApiUser>,
+    #[serde(default)]
+    labels: Vec<ApiLabel>,
+    created_at: String,
+    pull_request: Option<serde_json::Value>,
+}
+
+impl ApiIssue {
+    fn into_item(self, date: time::Date) -> GitHubItem {
+        item(
+            self.number,
+            self.title,
+            self.html_url,
+            self.user,
+            self.labels,
+            date,
+        )
+    }
+}
+
+fn item(
+    number: u64,
+    title: String,
+    url: String,
+    user: Option<ApiUser>,
+    labels: Vec<ApiLabel>,
+    date: time::Date,
+) -> GitHubItem {
+    let (author, author_url) = user
+        .map(|user| (user.login, user.html_url))
+        .unwrap_or_default();
+    GitHubItem {
+        github_id: number.to_string(),
+        title,
+        url,
+        author,
+        author_url,
+        date,
+        labels: serde_json::to_value(labels)
+            .expect("labels to serialize"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::Query, routing::get, Json, Router,
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+    use time::macros::date;
+
+    fn pull(
+        number: u64,
+        created_at: &str,
+        merged_at: Option<&str>,
+    ) -> serde_json::Value {
+        json!({
+            "number": number,
+            "title": format!("PR {number}"),
+            "html_url": format!("https://github.com/bevyengine/bevy/pull/{number}"),
+            "user": {
+                "login": "alice",
+                "html_url": "https://github.com/alice"
+            },
+            "labels": [{ "name": "A-ECS", "color": "3300ff", "id": 1 }],
+            "created_at": created_at,
+            "updated_at": merged_at.unwrap_or(created_at),
+            "merged_at": merged_at
+        })
+    }
+
+    /// Serves `pages` from the `pulls` and
+    /// `issues` endpoints of a local mock
+    /// GitHub
+    async fn mock_github(
+        pulls: Vec<Vec<serde_json::Value>>,
+        issues: Vec<Vec<serde_json::Value>>,
+    ) -> GitHubClient {
+        fn paged(
+            pages: Vec<Vec<serde_json::Value>>,
+        ) -> impl Fn(
+            Query<HashMap<String, String>>,
+        ) -> std::future::Ready<
+            Json<Vec<serde_json::Value>>,
+        > + Clone {
+            move |Query(query)| {
+                let page: usize = query["page"]
+                    .parse()
+                    .expect("page to be a number");
+                std::future::ready(Json(
+                    pages
+                        .get(page - 1)
+                        .cloned()
+                        .unwrap_or_default(),
+                ))
+            }
+        }
+
+        let app = Router::new()
+            .route(
+                "/repos/bevyengine/bevy/pulls",
+                get(paged(pulls)),
+            )
+            .route(
+                "/repos/bevyengine/bevy/issues",
+                get(paged(issues)),
+            );
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut client = GitHubClient::new(
+            format!("http://{addr}"),
+            None,
+        );
+        client.per_page = 2;
+        client
+    }
+
+    #[tokio::test]
+    async fn pages_new_pull_requests_until_out_of_range() {
+        let client = mock_github(
+            vec![
+                vec![
+                    pull(3, "2024-02-14T10:00:00Z", None),
+                    pull(2, "2024-02-12T10:00:00Z", None),
+                ],
+                vec![
+                    pull(1, "2024-02-05T10:00:00Z", None),
+                    pull(0, "2024-02-04T10:00:00Z", None),
+                ],
+                vec![pull(
+                    99,
+                    "2024-02-12T10:00:00Z",
+                    None,
+                )],
+            ],
+            vec![],
+        )
+        .await;
+
+        let items = client
+            .new_pull_requests(
+                date!(2024 - 02 - 06),
+                date!(2024 - 02 - 13),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![GitHubItem {
+                github_id: "2".to_string(),
+                title: "PR 2".to_string(),
+                url: "https://github.com/bevyengine/bevy/pull/2"
+                    .to_string(),
+                author: "alice".to_string(),
+                author_url: "https://github.com/alice"
+                    .to_string(),
+                date: date!(2024 - 02 - 12),
+                labels: json!([{ "name": "A-ECS", "color": "3300ff" }]),
+            }],
+            items
+        );
+    }
+
+    #[tokio::test]
+    async fn merged_pull_requests_use_merge_date() {
+        let client = mock_github(
+            vec![vec![
+                pull(
+                    5,
+                    "2024-01-01T10:00:00Z",
+                    Some("2024-02-08T10:00:00Z"),
+                ),
+                pull(4, "2024-02-07T10:00:00Z", None),
+            ]],
+            vec![],
+        )
+        .await;
+
+        let items = client
+            .merged_pull_requests(
+                date!(2024 - 02 - 06),
+                date!(2024 - 02 - 13),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(1, items.len());
+        assert_eq!("5", items[0].github_id);
+        assert_eq!(date!(2024 - 02 - 08), items[0].date);
+    }
+
+    #[tokio::test]
+    async fn new_issues_skip_pull_requests() {
+        let mut pr = pull(7, "2024-02-08T10:00:00Z", None);
+        pr["pull_request"] = json!({ "url": "..." });
+        let client = mock_github(
+            vec![],
+            vec![vec![
+                pr,
+                pull(6, "2024-02-07T10:00:00Z", None),
+            ]],
+        )
+        .await;
+
+        let items = client
+            .new_issues(
+                date!(2024 - 02 - 06),
+                date!(2024 - 02 - 13),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(1, items.len());
+        assert_eq!("6", items[0].github_id);
+    }
+}
@@ -0,0 +1,511 @@
+//! Urls for the images attached to issues and
+//! their items.
+//!
+//! Images are stored by a public id, which is
+//! Cloudinary's name for the path of an upload.
+//! An [`ImageBackend`] turns that id, plus an
+//! optional width and format, into a url.
+//! Production uses Cloudinary, which resizes on
+//! the fly. A fork or an offline checkout can
+//! use [`LocalDisk`] instead, which serves files
+//! from a directory at `/images/*public_id`.
+use crate::site_config::SiteConfig;
+#[cfg(feature = "ssr")]
+use axum::{
+    extract::{self, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    Jpg,
+    Webp,
+    Avif,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpg => "jpg",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Avif => "avif",
+        }
+    }
+}
+
+pub trait ImageBackend: Send + Sync {
+    /// A url for the image stored as `public_id`,
+    /// scaled to `width` pixels wide and
+    /// converted to `format` when they're given.
+    fn url(
+        &self,
+        public_id: &str,
+        width: Option<u32>,
+        format: Option<ImageFormat>,
+    ) -> String;
+}
+
+/// `public_id` with the extension of its last
+/// segment swapped for `format`'s, or added if it
+/// doesn't have one
+fn with_format(
+    public_id: &str,
+    format: ImageFormat,
+) -> String {
+    let (dir, file) = match public_id.rsplit_once('/') {
+        Some((dir, file)) => (Some(dir), file),
+        None => (None, public_id),
+    };
+    let stem = file
+        .rsplit_once('.')
+        .map_or(file, |(stem, _)| stem);
+    match dir {
+        Some(dir) => {
+            format!("{dir}/{stem}.{}", format.extension())
+        }
+        None => format!("{stem}.{}", format.extension()),
+    }
+}
+
+/// Cloudinary picks the format from the
+/// extension, and resizes with a `c_scale`
+/// transformation.
+#[derive(Debug, Clone)]
+pub struct Cloudinary {
+    pub cloud_name: String,
+}
+
+impl ImageBackend for Cloudinary {
+    fn url(
+        &self,
+        public_id: &str,
+        width: Option<u32>,
+        format: Option<ImageFormat>,
+    ) -> String {
+        use cloudinary::transformation::{
+            resize_mode::ResizeMode::ScaleByWidth, Image,
+            Transformations::Resize,
+        };
+
+        let public_id = match format {
+            Some(format) => with_format(public_id, format),
+            None => public_id.to_string(),
+        };
+        let image = Image::new(
+            self.cloud_name.as_str().into(),
+            public_id.into(),
+        );
+        let image = match width {
+            Some(width) => image.add_transformation(
+                Resize(ScaleByWidth {
+                    width,
+                    ar: None,
+                    liquid: None,
+                }),
+            ),
+            None => image,
+        };
+        image.to_string()
+    }
+}
+
+/// Images in a directory on the server. Nothing
+/// is resized or converted on request, instead
+/// copies can be generated ahead of time: the
+/// image for `?w=600` is looked for at
+/// `w_600/{public_id}` in `dir`, and `?f=png`
+/// swaps the file's extension. The original is
+/// served when there's no such copy.
+#[derive(Debug, Clone)]
+pub struct LocalDisk {
+    /// ex: `https://thisweekinbevy.com/images`
+    pub url_prefix: String,
+    pub dir: PathBuf,
+}
+
+impl ImageBackend for LocalDisk {
+    fn url(
+        &self,
+        public_id: &str,
+        width: Option<u32>,
+        format: Option<ImageFormat>,
+    ) -> String {
+        let query = width
+            .map(|width| format!("w={width}"))
+            .into_iter()
+            .chain(format.map(|format| {
+                format!("f={}", format.extension())
+            }))
+            .collect::<Vec<_>>();
+        let url =
+            format!("{}/{public_id}", self.url_prefix);
+        if query.is_empty() {
+            url
+        } else {
+            format!("{url}?{}", query.join("&"))
+        }
+    }
+}
+
+impl LocalDisk {
+    pub fn new(config: &SiteConfig) -> Self {
+        LocalDisk {
+            url_prefix: config.site_url.join("/images"),
+            dir: PathBuf::from(&config.local_images_dir),
+        }
+    }
+
+    /// The files that could be served for a
+    /// request, best match first. `None` if
+    /// `public_id` would reach outside of `dir`.
+    pub fn candidates(
+        &self,
+        public_id: &str,
+        width: Option<u32>,
+        format: Option<ImageFormat>,
+    ) -> Option<Vec<PathBuf>> {
+        let is_relative = Path::new(public_id)
+            .components()
+            .all(|component| {
+                matches!(component, Component::Normal(_))
+            });
+        if public_id.is_empty()
+            || public_id.contains('\\')
+            || !is_relative
+        {
+            return None;
+        }
+
+        let files = match format {
+            Some(format) => vec![
+                with_format(public_id, format),
+                public_id.to_string(),
+            ],
+            None => vec![public_id.to_string()],
+        };
+        let mut candidates = vec![];
+        if let Some(width) = width {
+            let resized =
+                self.dir.join(format!("w_{width}"));
+            candidates.extend(
+                files.iter().map(|file| resized.join(file)),
+            );
+        }
+        candidates.extend(
+            files.iter().map(|file| self.dir.join(file)),
+        );
+        Some(candidates)
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Local images never need to load or run
+/// anything
+#[cfg(feature = "ssr")]
+const IMAGE_CSP: &str =
+    "default-src 'none'; style-src 'unsafe-inline'; sandbox";
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Deserialize)]
+pub struct ImageParams {
+    w: Option<u32>,
+    f: Option<ImageFormat>,
+}
+
+/// Serves `/images/*public_id` when the
+/// `local` image backend is configured
+#[cfg(feature = "ssr")]
+pub async fn serve_local_image(
+    State(config): State<SiteConfig>,
+    extract::Path(public_id): extract::Path<String>,
+    Query(params): Query<ImageParams>,
+) -> Response {
+    use crate::site_config::ImageBackendKind;
+
+    if config.image_backend != ImageBackendKind::Local {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let Some(candidates) = LocalDisk::new(&config)
+        .candidates(&public_id, params.w, params.f)
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    for path in candidates {
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => {
+                let content_type = content_type(&path);
+                // an svg opened on its own is a
+                // page on this origin that can run
+                // scripts. `<img>` ignores both of
+                // these, so embedding still works.
+                let disposition =
+                    if content_type == "image/svg+xml" {
+                        "attachment"
+                    } else {
+                        "inline"
+                    };
+                return (
+                    [
+                        (
+                            header::CONTENT_TYPE,
+                            content_type,
+                        ),
+                        (
+                            header::CONTENT_DISPOSITION,
+                            disposition,
+                        ),
+                        (
+                            header::CONTENT_SECURITY_POLICY,
+                            IMAGE_CSP,
+                        ),
+                        (
+                            header::X_CONTENT_TYPE_OPTIONS,
+                            "nosniff",
+                        ),
+                    ],
+                    bytes,
+                )
+                    .into_response();
+            }
+            Err(e)
+                if e.kind()
+                    == std::io::ErrorKind::NotFound =>
+            {
+                continue;
+            }
+            Err(e) => {
+                tracing::error!(
+                    ?e,
+                    ?path,
+                    "failed to read image"
+                );
+                return StatusCode::INTERNAL_SERVER_ERROR
+                    .into_response();
+            }
+        }
+    }
+    StatusCode::NOT_FOUND.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local() -> LocalDisk {
+        LocalDisk {
+            url_prefix: "http://localhost:3000/images"
+                .to_string(),
+            dir: PathBuf::from("images"),
+        }
+    }
+
+    #[test]
+    fn formats_replace_the_extension() {
+        assert_eq!(
+            with_format("twib/og.avif", ImageFormat::Png),
+            "twib/og.png"
+        );
+        assert_eq!(
+            with_format("v1.2/og", ImageFormat::Png),
+            "v1.2/og.png"
+        );
+    }
+
+    #[test]
+    fn cloudinary_urls() {
+        let cloudinary = Cloudinary {
+            cloud_name: "staging".to_string(),
+        };
+        assert_eq!(
+            cloudinary.url("thisweekinbevy/og", None, None),
+            "https://res.cloudinary.com/staging/image/upload/thisweekinbevy/og"
+        );
+        assert_eq!(
+            cloudinary.url(
+                "thisweekinbevy/og.avif",
+                Some(600),
+                Some(ImageFormat::Png)
+            ),
+            "https://res.cloudinary.com/staging/image/upload/c_scale,w_600/thisweekinbevy/og.png"
+        );
+    }
+
+    #[test]
+    fn local_urls() {
+        assert_eq!(
+            local().url("twib/og.avif", None, None),
+            "http://localhost:3000/images/twib/og.avif"
+        );
+        assert_eq!(
+            local().url(
+                "twib/og.avif",
+                Some(600),
+                Some(ImageFormat::Png)
+            ),
+            "http://localhost:3000/images/twib/og.avif?w=600&f=png"
+        );
+    }
+
+    #[test]
+    fn local_candidates_fall_back_to_the_original() {
+        assert_eq!(
+            local()
+                .candidates(
+                    "twib/og.avif",
+                    Some(600),
+                    Some(ImageFormat::Png)
+                )
+                .unwrap(),
+            [
+                "images/w_600/twib/og.png",
+                "images/w_600/twib/og.avif",
+                "images/twib/og.png",
+                "images/twib/og.avif",
+            ]
+            .map(PathBuf::from)
+        );
+        assert_eq!(
+            local()
+                .candidates("og.avif", None, None)
+                .unwrap(),
+            [PathBuf::from("images/og.avif")]
+        );
+    }
+
+    #[test]
+    fn local_candidates_stay_in_the_directory() {
+        for public_id in [
+            "",
+            "../secrets",
+            "twib/../../x",
+            "/etc/passwd",
+            "a\\b",
+        ] {
+            assert_eq!(
+                local().candidates(public_id, None, None),
+                None,
+                "{public_id} should be rejected"
+            );
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[tokio::test]
+    async fn serves_the_resized_copy_when_there_is_one() {
+        use axum::{routing::get, Router};
+
+        let dir = std::env::temp_dir().join(format!(
+            "twib-images-{}",
+            rusty_ulid::Ulid::generate()
+        ));
+        std::fs::create_dir_all(dir.join("w_600/twib"))
+            .unwrap();
+        std::fs::create_dir_all(dir.join("twib")).unwrap();
+        std::fs::write(dir.join("twib/og.png"), "original")
+            .unwrap();
+        std::fs::write(
+            dir.join("w_600/twib/og.png"),
+            "resized",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("twib/logo.svg"),
+            "<svg><script>alert(1)</script></svg>",
+        )
+        .unwrap();
+
+        let config = SiteConfig {
+            image_backend:
+                crate::site_config::ImageBackendKind::Local,
+            local_images_dir: dir.display().to_string(),
+            ..SiteConfig::default()
+        };
+        let app = Router::new()
+            .route(
+                "/images/*public_id",
+                get(serve_local_image),
+            )
+            .with_state(config);
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let get = |path: &str| {
+            reqwest::get(format!("http://{addr}{path}"))
+        };
+        let resized =
+            get("/images/twib/og.png?w=600").await.unwrap();
+        assert_eq!(
+            resized.headers()["content-type"],
+            "image/png"
+        );
+        assert_eq!(
+            resized.text().await.unwrap(),
+            "resized"
+        );
+        assert_eq!(
+            get("/images/twib/og.png?w=300")
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap(),
+            "original"
+        );
+        let svg =
+            get("/images/twib/logo.svg").await.unwrap();
+        assert_eq!(
+            svg.headers()["content-disposition"],
+            "attachment"
+        );
+        assert_eq!(
+            svg.headers()["content-security-policy"],
+            IMAGE_CSP
+        );
+        assert_eq!(
+            get("/images/twib/missing.png")
+                .await
+                .unwrap()
+                .status(),
+            404
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,463 @@
+//! The full content of an issue, as loaded by
+//! `issue__showcase.sql`, shared by the issue
+//! page, the newsletter email and the feeds.
+use crate::{
+    sanitize::TrustLevel, site_config::SiteConfig,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, MySqlPool};
+
+// The names of these structs are referenced by
+// the type overrides in `issue__showcase.sql`.
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct SqlIssue {
+    pub issue_date: time::Date,
+    pub slug: String,
+    pub cloudinary_public_id: String,
+    pub display_name: String,
+    pub description: String,
+    pub youtube_id: String,
+    /// `draft` or `publish`
+    pub status: String,
+    pub updated_at: time::OffsetDateTime,
+    pub showcases: Option<Json<Vec<ShowcaseData>>>,
+    pub crate_releases: Option<Json<Vec<SqlCrateRelease>>>,
+    pub devlogs: Option<Json<Vec<SqlDevlog>>>,
+    pub educationals: Option<Json<Vec<SqlEducational>>>,
+    pub new_github_issues: Option<Json<Vec<SqlNewGhIssue>>>,
+    pub new_pull_requests: Option<Json<Vec<SqlNewPr>>>,
+    pub merged_pull_requests:
+        Option<Json<Vec<SqlMergedPullRequest>>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShowcaseData {
+    pub position: u32,
+    pub title: String,
+    pub url: String,
+    pub discord_url: String,
+    pub description: String,
+    pub images: Option<Vec<ImgData>>,
+    pub submitted_by: Option<String>,
+    /// the submitter's role in `github_users`
+    pub submitter_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlCrateRelease {
+    pub position: u32,
+    pub title: String,
+    pub url: String,
+    pub discord_url: String,
+    pub description: String,
+    pub posted_date: Option<String>,
+    pub images: Option<Vec<ImgData>>,
+    pub submitted_by: Option<String>,
+    /// the submitter's role in `github_users`
+    pub submitter_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlDevlog {
+    pub position: u32,
+    pub title: String,
+    pub post_url: String,
+    pub video_url: String,
+    pub discord_url: String,
+    pub description: String,
+    pub images: Option<Vec<ImgData>>,
+    pub submitted_by: Option<String>,
+    /// the submitter's role in `github_users`
+    pub submitter_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlEducational {
+    pub position: u32,
+    pub title: String,
+    pub post_url: String,
+    pub video_url: String,
+    pub discord_url: String,
+    pub description: String,
+    pub images: Option<Vec<ImgData>>,
+    pub submitted_by: Option<String>,
+    /// the submitter's role in `github_users`
+    pub submitter_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlNewGhIssue {
+    pub title: String,
+    pub url: String,
+    pub gh_created_at: String,
+    pub author: String,
+    pub author_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlNewPr {
+    pub github_id: String,
+    pub title: String,
+    pub url: String,
+    pub gh_created_at: String,
+    pub author: String,
+    pub author_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SqlMergedPullRequest {
+    pub github_id: String,
+    pub title: String,
+    pub url: String,
+    pub merged_at_date: String,
+    pub author: String,
+    pub author_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImgData {
+    pub id: String,
+    pub description: String,
+    pub cloudinary_public_id: String,
+}
+
+impl SqlIssue {
+    /// `JSON_ARRAYAGG` doesn't keep any order, so
+    /// each item carries its `position` in the
+    /// issue to be sorted by here.
+    fn sort_by_position(&mut self) {
+        if let Some(Json(items)) = &mut self.showcases {
+            items.sort_by_key(|item| item.position);
+        }
+        if let Some(Json(items)) = &mut self.crate_releases
+        {
+            items.sort_by_key(|item| item.position);
+        }
+        if let Some(Json(items)) = &mut self.devlogs {
+            items.sort_by_key(|item| item.position);
+        }
+        if let Some(Json(items)) = &mut self.educationals {
+            items.sort_by_key(|item| item.position);
+        }
+    }
+}
+
+/// Issues newest first, with every section
+/// loaded. `date` picks a single issue and
+/// `drafts` includes unpublished ones.
+async fn query(
+    pool: &MySqlPool,
+    date: Option<time::Date>,
+    drafts: bool,
+    limit: u32,
+) -> Result<Vec<SqlIssue>, sqlx::Error> {
+    let mut issues = sqlx::query_file_as!(
+        SqlIssue,
+        "src/app/routes/issue__showcase.sql",
+        date,
+        date,
+        drafts,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+    for issue in &mut issues {
+        issue.sort_by_position();
+    }
+    Ok(issues)
+}
+
+/// Load every section of the issue for `date`.
+/// Drafts are `None` unless `drafts` is set.
+pub async fn fetch_issue(
+    pool: &MySqlPool,
+    date: time::Date,
+    drafts: bool,
+) -> Result<Option<SqlIssue>, sqlx::Error> {
+    Ok(query(pool, Some(date), drafts, 1).await?.pop())
+}
+
+/// The `limit` most recent published issues, in
+/// full
+pub async fn fetch_published_issues(
+    pool: &MySqlPool,
+    limit: u32,
+) -> Result<Vec<SqlIssue>, sqlx::Error> {
+    query(pool, None, false, limit).await
+}
+
+pub fn issue_url(
+    issue: &SqlIssue,
+    config: &SiteConfig,
+) -> String {
+    config.site_url.join(&format!("/issue/{}", issue.slug))
+}
+
+pub fn image_url(
+    config: &SiteConfig,
+    cloudinary_public_id: &str,
+) -> String {
+    config.image_backend().url(
+        cloudinary_public_id,
+        Some(600),
+        None,
+    )
+}
+
+/// The words of a markdown description without
+/// any markup, for places that only take plain
+/// text
+pub fn plain_text(input: &str) -> String {
+    use comrak::nodes::{NodeCode, NodeValue};
+
+    let arena = comrak::Arena::new();
+    let root = comrak::parse_document(
+        &arena,
+        input,
+        &comrak::Options::default(),
+    );
+    let mut out = String::new();
+    for node in root.descendants() {
+        match &node.data.borrow().value {
+            NodeValue::Text(text)
+            | NodeValue::Code(NodeCode {
+                literal: text,
+                ..
+            }) => out.push_str(text),
+            // blocks and line breaks separate words
+            NodeValue::SoftBreak
+            | NodeValue::LineBreak
+            | NodeValue::Paragraph
+            | NodeValue::Heading(_)
+            | NodeValue::Item(_) => out.push(' '),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A titled, linked entry with a markdown
+/// description, used for every content section.
+pub struct Entry<'a> {
+    pub title: &'a str,
+    pub url: &'a str,
+    pub links: Vec<(&'static str, &'a str)>,
+    pub description: &'a str,
+    /// how the description is sanitized, from
+    /// who submitted the entry
+    pub trust: TrustLevel,
+    pub images: &'a [ImgData],
+}
+
+/// A single line in the GitHub sections
+pub struct GitHubEntry<'a> {
+    pub title: &'a str,
+    pub url: &'a str,
+    pub author: &'a str,
+    pub author_url: &'a str,
+}
+
+pub fn entries(
+    issue: &SqlIssue,
+) -> Vec<(&'static str, Vec<Entry<'_>>)> {
+    let showcases = issue
+        .showcases
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| Entry {
+            title: &v.title,
+            url: &v.url,
+            links: vec![("discord", &v.discord_url)],
+            description: &v.description,
+            trust: TrustLevel::of_submitter(
+                v.submitted_by.as_deref(),
+                v.submitter_role.as_deref(),
+            ),
+            images: v.images.as_deref().unwrap_or_default(),
+        })
+        .collect();
+    let crate_releases = issue
+        .crate_releases
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| Entry {
+            title: &v.title,
+            url: &v.url,
+            links: vec![("discord", &v.discord_url)],
+            description: &v.description,
+            trust: TrustLevel::of_submitter(
+                v.submitted_by.as_deref(),
+                v.submitter_role.as_deref(),
+            ),
+            images: v.images.as_deref().unwrap_or_default(),
+        })
+        .collect();
+    let devlogs = issue
+        .devlogs
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| Entry {
+            title: &v.title,
+            url: &v.post_url,
+            links: vec![
+                ("video", &v.video_url),
+                ("discord", &v.discord_url),
+            ],
+            description: &v.description,
+            trust: TrustLevel::of_submitter(
+                v.submitted_by.as_deref(),
+                v.submitter_role.as_deref(),
+            ),
+            images: v.images.as_deref().unwrap_or_default(),
+        })
+        .collect();
+    let educationals = issue
+        .educationals
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| Entry {
+            title: &v.title,
+            url: &v.post_url,
+            links: vec![
+                ("video", &v.video_url),
+                ("discord", &v.discord_url),
+            ],
+            description: &v.description,
+            trust: TrustLevel::of_submitter(
+                v.submitted_by.as_deref(),
+                v.submitter_role.as_deref(),
+            ),
+            images: v.images.as_deref().unwrap_or_default(),
+        })
+        .collect();
+
+    vec![
+        ("Showcase", showcases),
+        ("Crates", crate_releases),
+        ("Devlogs", devlogs),
+        ("Educational", educationals),
+    ]
+}
+
+pub fn github_entries(
+    issue: &SqlIssue,
+) -> Vec<(&'static str, Vec<GitHubEntry<'_>>)> {
+    let merged = issue
+        .merged_pull_requests
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| GitHubEntry {
+            title: &v.title,
+            url: &v.url,
+            author: &v.author,
+            author_url: &v.author_url,
+        })
+        .collect();
+    let new_prs = issue
+        .new_pull_requests
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| GitHubEntry {
+            title: &v.title,
+            url: &v.url,
+            author: &v.author,
+            author_url: &v.author_url,
+        })
+        .collect();
+    let new_issues = issue
+        .new_github_issues
+        .iter()
+        .flat_map(|json| json.0.iter())
+        .map(|v| GitHubEntry {
+            title: &v.title,
+            url: &v.url,
+            author: &v.author,
+            author_url: &v.author_url,
+        })
+        .collect();
+
+    vec![
+        ("Pull Requests Merged this week", merged),
+        (
+            "Contributing: Pull Requests opened this week",
+            new_prs,
+        ),
+        (
+            "Contributing: Issues opened this week",
+            new_issues,
+        ),
+    ]
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn plain_text_drops_markup() {
+        assert_eq!(
+            plain_text(
+                "A **big** week for `bevy_ecs`\n\n- [one](https://example.com)\n- <b>two</b>\n"
+            ),
+            "A big week for bevy_ecs one two"
+        );
+    }
+
+    /// A published issue with a showcase and a
+    /// merged pull request, for testing the
+    /// renderers
+    pub(crate) fn issue() -> SqlIssue {
+        SqlIssue {
+            issue_date: date!(2024 - 02 - 12),
+            slug: "2024-02-12-some-slug".to_string(),
+            cloudinary_public_id: "thisweekinbevy/og".to_string(),
+            display_name: "Bevy <3".to_string(),
+            description: "A **big** week".to_string(),
+            youtube_id: "".to_string(),
+            status: "publish".to_string(),
+            updated_at: datetime!(2024-02-14 09:00 UTC),
+            showcases: Some(Json(vec![ShowcaseData {
+                position: 1,
+                title: "A Game".to_string(),
+                url: "https://example.com/game".to_string(),
+                discord_url: "".to_string(),
+                description: "made with _bevy_".to_string(),
+                images: None,
+                submitted_by: None,
+                submitter_role: None,
+            }])),
+            crate_releases: None,
+            devlogs: None,
+            educationals: None,
+            new_github_issues: None,
+            new_pull_requests: None,
+            merged_pull_requests: Some(Json(vec![
+                SqlMergedPullRequest {
+                    github_id: "12345".to_string(),
+                    title: "Fix the thing".to_string(),
+                    url: "https://github.com/bevyengine/bevy/pull/12345".to_string(),
+                    merged_at_date: "2024-02-10".to_string(),
+                    author: "alice".to_string(),
+                    author_url: "https://github.com/alice".to_string(),
+                },
+            ])),
+        }
+    }
+}
@@ -5,6 +5,18 @@ use time::Date;
 pub const ISSUE_DATE_FORMAT: &[FormatItem<'_>] =
     format_description!("[year]-[month]-[day]");
 
+/// how publish timestamps are shown in the admin
+/// panel. Timestamps are stored in UTC.
+pub const TIMESTAMP_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute] UTC"
+);
+
+/// the value format of an
+/// `<input type="datetime-local">`
+pub const DATETIME_LOCAL_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day]T[hour]:[minute]"
+);
+
 /// takes a "2024-02-12-some-slug" and returns the
 /// date portion
 pub fn parse_issue_date_from_slug(
@@ -13,6 +25,19 @@ pub fn parse_issue_date_from_slug(
     input.get(0..10).and_then(parse_issue_date)
 }
 
+/// takes a "2024-02-12T09:30" from a
+/// `datetime-local` input and treats it as UTC
+pub fn parse_datetime_local(
+    input: &str,
+) -> Option<time::OffsetDateTime> {
+    time::PrimitiveDateTime::parse(
+        input,
+        &DATETIME_LOCAL_FORMAT,
+    )
+    .ok()
+    .map(time::PrimitiveDateTime::assume_utc)
+}
+
 /// takes a "2024-02-12" and returns the date
 /// portion
 pub fn parse_issue_date(input: &str) -> Option<time::Date> {
@@ -23,7 +48,7 @@ pub fn parse_issue_date(input: &str) -> Option<time::Date> {
 mod tests {
 
     use super::*;
-    use time::macros::date;
+    use time::macros::{date, datetime};
 
     #[test]
     fn can_parse_date_from_slug() {
@@ -54,4 +79,12 @@ mod tests {
         let slug = "2024";
         assert_eq!(None, parse_issue_date(slug));
     }
+
+    #[test]
+    fn can_parse_datetime_local_as_utc() {
+        assert_eq!(
+            Some(datetime!(2024-02-12 9:30 UTC)),
+            parse_datetime_local("2024-02-12T09:30")
+        );
+    }
 }
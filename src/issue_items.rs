@@ -0,0 +1,306 @@
+//! The order of the showcases, crate releases,
+//! devlogs and educationals in an issue, stored
+//! in the `position` column of each
+//! `issue__*` join table.
+//!
+//! Positions start at 1 and have no gaps. Moving
+//! or removing an item renumbers the whole
+//! section, which is a handful of rows.
+use crate::{
+    string_enum::string_enum, submissions::SubmissionKind,
+};
+use sqlx::{MySql, MySqlPool, Transaction};
+
+/// The join table between issues and `section`
+fn table(section: SubmissionKind) -> String {
+    format!("issue__{section}")
+}
+
+/// The join table's column for the item's id
+fn item_column(section: SubmissionKind) -> String {
+    format!("{section}_id")
+}
+
+string_enum! {
+    pub enum Direction {
+        Up = "up",
+        Down = "down",
+    }
+    #[error("unknown direction `{0}`")]
+    pub struct UnknownDirection;
+}
+
+/// Swap `item` with its neighbour in `direction`.
+/// Returns false if `item` isn't in `items` or
+/// is already at that end.
+pub fn move_within<T: PartialEq>(
+    items: &mut [T],
+    item: &T,
+    direction: Direction,
+) -> bool {
+    let Some(index) = items.iter().position(|i| i == item)
+    else {
+        return false;
+    };
+    let neighbour = match direction {
+        Direction::Up => index.checked_sub(1),
+        Direction::Down => {
+            Some(index + 1).filter(|n| *n < items.len())
+        }
+    };
+    match neighbour {
+        Some(neighbour) => {
+            items.swap(index, neighbour);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Locks the issue's row until `tx` ends. Adding,
+/// moving and removing items all take this lock
+/// first, so two admins adding items at once
+/// can't both get `MAX(position) + 1`.
+pub async fn lock_issue(
+    tx: &mut Transaction<'_, MySql>,
+    issue_id: &[u8],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "SELECT id FROM issue WHERE id = ? FOR UPDATE",
+    )
+    .bind(issue_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+// The table and column names come from
+// `SubmissionKind`, never from input, so these
+// queries are built at runtime instead of having
+// one `query!` per section.
+
+async fn ordered_items(
+    tx: &mut Transaction<'_, MySql>,
+    section: SubmissionKind,
+    issue_id: &[u8],
+) -> Result<Vec<Vec<u8>>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {column} FROM {table}
+WHERE issue_id = ?
+ORDER BY position, created_at
+FOR UPDATE",
+        column = item_column(section),
+        table = table(section),
+    ))
+    .bind(issue_id)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+async fn renumber(
+    tx: &mut Transaction<'_, MySql>,
+    section: SubmissionKind,
+    issue_id: &[u8],
+    items: &[Vec<u8>],
+) -> Result<(), sqlx::Error> {
+    let sql = format!(
+        "UPDATE {table} SET position = ?
+WHERE issue_id = ? AND {column} = ?",
+        column = item_column(section),
+        table = table(section),
+    );
+    for (position, item) in (1u32..).zip(items) {
+        sqlx::query(&sql)
+            .bind(position)
+            .bind(issue_id)
+            .bind(item.as_slice())
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Move an item one place up or down in its
+/// section. Returns false if it wasn't in the
+/// issue or couldn't move any further.
+pub async fn move_item(
+    pool: &MySqlPool,
+    section: SubmissionKind,
+    issue_id: &[u8],
+    item_id: &[u8],
+    direction: Direction,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    lock_issue(&mut tx, issue_id).await?;
+    let mut items =
+        ordered_items(&mut tx, section, issue_id).await?;
+    let moved = move_within(
+        &mut items,
+        &item_id.to_vec(),
+        direction,
+    );
+    if moved {
+        renumber(&mut tx, section, issue_id, &items)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(moved)
+}
+
+/// Take an item out of an issue. The item itself
+/// isn't deleted and can be added to an issue
+/// again. Returns false if it wasn't in the
+/// issue.
+pub async fn remove_item(
+    pool: &MySqlPool,
+    section: SubmissionKind,
+    issue_id: &[u8],
+    item_id: &[u8],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    lock_issue(&mut tx, issue_id).await?;
+    let result = sqlx::query(&format!(
+        "DELETE FROM {table}
+WHERE issue_id = ? AND {column} = ?",
+        column = item_column(section),
+        table = table(section),
+    ))
+    .bind(issue_id)
+    .bind(item_id)
+    .execute(&mut *tx)
+    .await?;
+    let items =
+        ordered_items(&mut tx, section, issue_id).await?;
+    renumber(&mut tx, section, issue_id, &items).await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions_round_trip_through_strings() {
+        for direction in [Direction::Up, Direction::Down] {
+            assert_eq!(
+                direction.as_str().parse(),
+                Ok(direction)
+            );
+        }
+        assert!("left".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn moving_swaps_with_the_neighbour() {
+        let mut items = vec!['a', 'b', 'c'];
+        assert!(move_within(
+            &mut items,
+            &'b',
+            Direction::Up
+        ));
+        assert_eq!(items, ['b', 'a', 'c']);
+        assert!(move_within(
+            &mut items,
+            &'a',
+            Direction::Down
+        ));
+        assert_eq!(items, ['b', 'c', 'a']);
+    }
+
+    #[test]
+    fn moving_stops_at_the_ends() {
+        let mut items = vec!['a', 'b'];
+        assert!(!move_within(
+            &mut items,
+            &'a',
+            Direction::Up
+        ));
+        assert!(!move_within(
+            &mut items,
+            &'b',
+            Direction::Down
+        ));
+        assert!(!move_within(
+            &mut items,
+            &'z',
+            Direction::Up
+        ));
+        assert_eq!(items, ['a', 'b']);
+    }
+
+    async fn positions(
+        pool: &MySqlPool,
+        issue_id: &[u8],
+    ) -> Vec<(Vec<u8>, u32)> {
+        sqlx::query_as(
+            "SELECT devlog_id, position FROM issue__devlog WHERE issue_id = ? ORDER BY position",
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn moving_and_removing_keeps_positions_contiguous(
+    ) {
+        use crate::sql::test_db::DisposableSchema;
+
+        let schema = DisposableSchema::new().await;
+        let issue_id: [u8; 16] =
+            rusty_ulid::Ulid::generate().into();
+        let devlogs: Vec<[u8; 16]> = (0..3)
+            .map(|_| rusty_ulid::Ulid::generate().into())
+            .collect();
+        for (position, devlog_id) in (1u32..).zip(&devlogs)
+        {
+            sqlx::query(
+                "INSERT INTO issue__devlog (issue_id, devlog_id, position) VALUES (?, ?, ?)",
+            )
+            .bind(issue_id.as_slice())
+            .bind(devlog_id.as_slice())
+            .bind(position)
+            .execute(&schema.pool)
+            .await
+            .unwrap();
+        }
+
+        assert!(move_item(
+            &schema.pool,
+            SubmissionKind::Devlog,
+            &issue_id,
+            &devlogs[2],
+            Direction::Up
+        )
+        .await
+        .unwrap());
+        assert_eq!(
+            positions(&schema.pool, &issue_id).await,
+            vec![
+                (devlogs[0].to_vec(), 1),
+                (devlogs[2].to_vec(), 2),
+                (devlogs[1].to_vec(), 3),
+            ]
+        );
+
+        assert!(remove_item(
+            &schema.pool,
+            SubmissionKind::Devlog,
+            &issue_id,
+            &devlogs[0]
+        )
+        .await
+        .unwrap());
+        assert_eq!(
+            positions(&schema.pool, &issue_id).await,
+            vec![
+                (devlogs[2].to_vec(), 1),
+                (devlogs[1].to_vec(), 2),
+            ]
+        );
+
+        schema.drop().await;
+    }
+}
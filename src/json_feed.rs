@@ -0,0 +1,200 @@
+//! Every issue in full at `/feed.json`, in the
+//! JSON Feed 1.1 format.
+//!
+//! <https://www.jsonfeed.org/version/1.1/>
+use crate::{
+    feed::{
+        self, FeedParams, SiteFeed, ICON_URL, LOGO_URL,
+    },
+    site_config::SiteConfig,
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Response,
+};
+use serde::Serialize;
+use time::{
+    format_description::well_known::Rfc3339, OffsetDateTime,
+};
+
+const CONTENT_TYPE: &str = "application/feed+json";
+
+pub async fn json_feed(
+    State(app_state): State<AppState>,
+    State(config): State<SiteConfig>,
+    Query(params): Query<FeedParams>,
+    headers: HeaderMap,
+) -> Response {
+    let issues = feed::issues(
+        &app_state.pool,
+        &config,
+        params.limit(),
+    )
+    .await;
+    feed::respond(&headers, issues, CONTENT_TYPE, |feed| {
+        render(
+            feed,
+            &config.site_url.join("/feed.json"),
+        )
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: &'a str,
+    feed_url: &'a str,
+    description: &'a str,
+    icon: &'static str,
+    favicon: &'static str,
+    language: &'static str,
+    authors: [FeedAuthor<'a>; 1],
+    items: Vec<Item<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedAuthor<'a> {
+    name: &'a str,
+    url: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct Item<'a> {
+    id: &'a str,
+    url: &'a str,
+    title: &'a str,
+    content_html: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_published: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_modified: Option<String>,
+}
+
+fn rfc3339(date: OffsetDateTime) -> Option<String> {
+    date.format(&Rfc3339).ok()
+}
+
+/// `feed_url` is where this feed is served
+pub fn render(feed: &SiteFeed, feed_url: &str) -> String {
+    let json = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: &feed.title,
+        home_page_url: &feed.home_page_url,
+        feed_url,
+        description: &feed.description,
+        icon: LOGO_URL,
+        favicon: ICON_URL,
+        language: "en",
+        authors: [FeedAuthor {
+            name: &feed.author.name,
+            url: &feed.author.url,
+        }],
+        items: feed
+            .items
+            .iter()
+            .map(|item| Item {
+                id: &item.url,
+                url: &item.url,
+                title: &item.title,
+                content_html: &item.content_html,
+                summary: item.summary_text.as_deref(),
+                image: item.image.as_deref(),
+                date_published: rfc3339(item.published),
+                date_modified: rfc3339(item.updated),
+            })
+            .collect(),
+    };
+    serde_json::to_string(&json)
+        .expect("feeds are always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{feed::FeedItem, site_config::Author};
+    use time::macros::datetime;
+
+    #[test]
+    fn renders_json_feed() {
+        let feed = SiteFeed {
+            title: "This Week in Bevy".to_string(),
+            home_page_url: "https://thisweekinbevy.com/"
+                .to_string(),
+            description: "What happened".to_string(),
+            author: Author::default(),
+            items: vec![FeedItem {
+                url: "https://thisweekinbevy.com/issue/2024-02-12-some-slug".to_string(),
+                title: "Bevy <3".to_string(),
+                summary_html: Some(
+                    "<p>A <strong>big</strong> week</p>"
+                        .to_string(),
+                ),
+                summary_text: Some("A big week".to_string()),
+                content_html: "<p>A <strong>big</strong> week</p>"
+                    .to_string(),
+                image: Some(
+                    "https://example.com/og.png".to_string(),
+                ),
+                published: datetime!(2024-02-12 12:30 UTC),
+                updated: datetime!(2024-02-14 09:00 UTC),
+            }],
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&render(
+                &feed,
+                "https://thisweekinbevy.com/feed.json",
+            ))
+            .unwrap();
+
+        assert_eq!(
+            json["version"],
+            "https://jsonfeed.org/version/1.1"
+        );
+        assert_eq!(
+            json["feed_url"],
+            "https://thisweekinbevy.com/feed.json"
+        );
+        let item = &json["items"][0];
+        assert_eq!(item["title"], "Bevy <3");
+        assert_eq!(
+            item["content_html"],
+            "<p>A <strong>big</strong> week</p>"
+        );
+        assert_eq!(
+            item["date_published"],
+            "2024-02-12T12:30:00Z"
+        );
+        assert_eq!(
+            item["date_modified"],
+            "2024-02-14T09:00:00Z"
+        );
+        // JSON Feed summaries are plain text
+        assert_eq!(item["summary"], "A big week");
+    }
+
+    #[test]
+    fn empty_feed_has_no_items() {
+        let feed = SiteFeed {
+            title: "This Week in Bevy".to_string(),
+            home_page_url: "https://thisweekinbevy.com/"
+                .to_string(),
+            description: "What happened".to_string(),
+            author: Author::default(),
+            items: vec![],
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&render(
+                &feed,
+                "https://thisweekinbevy.com/feed.json",
+            ))
+            .unwrap();
+        assert_eq!(json["items"], serde_json::json!([]));
+    }
+}
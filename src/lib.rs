@@ -1,21 +1,58 @@
 #![recursion_limit = "256"]
 
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+pub mod alerts;
 pub mod app;
 #[cfg(feature = "ssr")]
 pub mod atom_feed;
 #[cfg(feature = "ssr")]
 pub mod auth;
+#[cfg(feature = "ssr")]
+pub mod cloudinary_upload;
+pub mod contributors;
+#[cfg(feature = "ssr")]
+pub mod crates_io_ingest;
+#[cfg(feature = "gen-email")]
+pub mod email;
 pub mod error_template;
+#[cfg(feature = "ssr")]
+pub mod feed;
+#[cfg(feature = "ssr")]
+pub mod github_ingest;
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+pub mod images;
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+pub mod issue_content;
 pub mod issue_date;
 #[cfg(feature = "ssr")]
+pub mod issue_items;
+#[cfg(feature = "ssr")]
+pub mod json_feed;
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
 pub mod markdown;
 #[cfg(feature = "ssr")]
+pub mod migrate;
+#[cfg(feature = "ssr")]
 pub mod oauth;
 #[cfg(feature = "ssr")]
+pub mod preview;
+pub mod roles;
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+pub mod sanitize;
+#[cfg(feature = "ssr")]
+pub mod scheduler;
+#[cfg(feature = "ssr")]
 pub mod session_store;
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+pub mod shortcodes;
+pub mod site_config;
+#[cfg(feature = "ssr")]
+pub mod sitemap;
 pub mod sql;
 #[cfg(feature = "ssr")]
 pub mod state;
+mod string_enum;
+pub mod submissions;
 #[cfg(feature = "ssr")]
 pub mod users;
 
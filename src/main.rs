@@ -23,12 +23,17 @@ use sqlx::mysql::MySqlPoolOptions;
 use std::env;
 use this_week_in_bevy::{
     app::{shell, App},
-    auth, oauth,
+    auth, crates_io_ingest, github_ingest,
+    issue_date::parse_issue_date,
+    migrate, oauth, scheduler,
+    site_config::SiteConfig,
     state::AppState,
     users::Backend,
 };
 use this_week_in_bevy::{
-    session_store, users::AuthSession, Username,
+    session_store,
+    users::{AuthSession, User},
+    Username,
 };
 use time::Duration;
 
@@ -46,11 +51,21 @@ async fn server_fn_handler(
     handle_server_fns_with_context(
         move || {
             provide_context(app_state.pool.clone());
+            provide_context(app_state.config.clone());
+            provide_context(
+                app_state.crates_io_import.clone(),
+            );
+            provide_context(
+                app_state.session_cleanup.clone(),
+            );
             provide_context(
                 auth_session.user.as_ref().map(|user| {
                     Username(user.username.clone())
                 }),
             );
+            provide_context(
+                auth_session.user.as_ref().map(User::role),
+            );
         },
         request,
     )
@@ -67,11 +82,21 @@ async fn leptos_routes_handler(
         app_state.routes.clone(),
         move || {
             provide_context(app_state.pool.clone());
+            provide_context(app_state.config.clone());
+            provide_context(
+                app_state.crates_io_import.clone(),
+            );
+            provide_context(
+                app_state.session_cleanup.clone(),
+            );
             provide_context(
                 auth_session.user.as_ref().map(|user| {
                     Username(user.username.clone())
                 }),
             );
+            provide_context(
+                auth_session.user.as_ref().map(User::role),
+            );
         },
         move || shell(app_state.leptos_options.clone()),
     );
@@ -94,6 +119,11 @@ async fn main() {
 
     tracing_subscriber::fmt::init();
 
+    let config = SiteConfig::load().unwrap_or_else(|e| {
+        eprintln!("invalid site configuration: {e}");
+        std::process::exit(1);
+    });
+
     let pool = MySqlPoolOptions::new()
         .connect(
             &env::var("DATABASE_URL")
@@ -102,6 +132,120 @@ async fn main() {
         .await
         .expect("Could not make pool.");
 
+    // one-off commands that share the server's
+    // database configuration, such as
+    // `this-week-in-bevy ingest-github 2024-02-05
+    // 2024-02-12` or `this-week-in-bevy migrate`
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()[..]
+    {
+        [] => {}
+        ["ingest-github", start_date, end_date] => {
+            let start_date = parse_issue_date(start_date)
+                .expect("start_date should be YYYY-MM-DD");
+            let end_date = parse_issue_date(end_date)
+                .expect("end_date should be YYYY-MM-DD");
+            let counts = github_ingest::ingest(
+                &pool,
+                &github_ingest::GitHubClient::from_env(),
+                start_date,
+                end_date,
+            )
+            .await
+            .expect("ingest from github");
+            println!("{counts:?}");
+            return;
+        }
+        ["import-crates", start_date, end_date] => {
+            let start_date = parse_issue_date(start_date)
+                .expect("start_date should be YYYY-MM-DD");
+            let end_date = parse_issue_date(end_date)
+                .expect("end_date should be YYYY-MM-DD");
+            let counts = crates_io_ingest::import(
+                &pool,
+                &crates_io_ingest::CratesIoClient::new(
+                    &config.crates_io_api_url,
+                ),
+                start_date,
+                end_date,
+                |progress| println!("{progress}"),
+            )
+            .await
+            .expect("import from crates.io");
+            println!("{counts:?}");
+            return;
+        }
+        ["migrate"] => {
+            let applied = migrate::run(&pool)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                });
+            for migration in &applied {
+                println!("applied {}", migration.file);
+            }
+            println!(
+                "{} migrations applied",
+                applied.len()
+            );
+            return;
+        }
+        ["migrate", "status"] => {
+            let pending = migrate::pending(&pool)
+                .await
+                .expect("read schema_migrations");
+            for migration in &pending {
+                println!("pending {}", migration.file);
+            }
+            println!(
+                "{} migrations pending",
+                pending.len()
+            );
+            return;
+        }
+        ["migrate", "baseline", version] => {
+            let version = version
+                .parse()
+                .expect("version should be a number");
+            let baselined =
+                migrate::baseline(&pool, version)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("{e}");
+                        std::process::exit(1);
+                    });
+            for migration in &baselined {
+                println!("recorded {}", migration.file);
+            }
+            return;
+        }
+        _ => {
+            eprintln!(
+                "usage: this-week-in-bevy [ingest-github <start_date> <end_date> | import-crates <start_date> <end_date> | migrate [status | baseline <version>]]"
+            );
+            std::process::exit(1);
+        }
+    }
+
+    // serving requests against an old schema fails
+    // in confusing ways, so don't start at all
+    let pending = migrate::pending(&pool)
+        .await
+        .expect("read schema_migrations");
+    if !pending.is_empty() {
+        eprintln!(
+            "the database schema is behind, run `this-week-in-bevy migrate` to apply:"
+        );
+        for migration in &pending {
+            eprintln!("  {}", migration.file);
+        }
+        std::process::exit(1);
+    }
+
     let client_id = env::var("GITHUB_CLIENT_ID")
         .map(ClientId::new)
         .expect("GITHUB_CLIENT_ID should be provided.");
@@ -115,16 +259,12 @@ async fn main() {
         warn!("GITHUB_CLIENT_SECRET starts with one password prefix. Did you use `op run`?")
     }
 
-    let auth_url = AuthUrl::new(
-        "https://github.com/login/oauth/authorize"
-            .to_string(),
-    )
-    .expect("it to have worked :: authorize");
-    let token_url = TokenUrl::new(
-        "https://github.com/login/oauth/access_token"
-            .to_string(),
-    )
-    .expect("it to have worked :: access_token");
+    let auth_url =
+        AuthUrl::new(config.github.authorize_url.clone())
+            .expect("it to have worked :: authorize");
+    let token_url =
+        TokenUrl::new(config.github.token_url.clone())
+            .expect("it to have worked :: access_token");
     let client = BasicClient::new(
         client_id,
         Some(client_secret),
@@ -142,9 +282,10 @@ async fn main() {
     // a memory store would be instantiated like this
     // let session_store = MemoryStore::default();
 
-    // migrations happen in `bin/migrate-sessions` as
-    // we don't do "live migrations".
-    // session_store.migrate().await?;
+    // the session table is created by
+    // `sql/004-users.sql`, which is applied by
+    // `this-week-in-bevy migrate` like any other
+    // migration.
 
     let session_layer =
         SessionManagerLayer::new(session_store)
@@ -161,7 +302,8 @@ async fn main() {
     // backend to establish the auth service which
     // will provide the auth session as a request
     // extension.
-    let backend = Backend::new(pool.clone(), client);
+    let backend =
+        Backend::new(pool.clone(), client, config.clone());
     let auth_layer = AuthManagerLayerBuilder::new(
         backend,
         session_layer,
@@ -185,14 +327,64 @@ async fn main() {
         leptos_options,
         pool: pool.clone(),
         routes: routes.clone(),
+        config,
+        crates_io_import: Default::default(),
+        session_cleanup: Default::default(),
     };
 
+    // background tasks stop once this is sent,
+    // after the server has finished its in-flight
+    // requests
+    let (shutdown, shutdown_receiver) =
+        tokio::sync::watch::channel(false);
+
+    let session_cleanup =
+        session_store::spawn_expired_deletion(
+            session_store::MySqlStore::new(pool.clone()),
+            std::time::Duration::from_secs(
+                app_state
+                    .config
+                    .session_cleanup_interval_secs,
+            ),
+            app_state.session_cleanup.clone(),
+            shutdown_receiver.clone(),
+        );
+
+    // publish issues that were scheduled from the
+    // admin panel once their time comes
+    let publish_scheduler = scheduler::spawn(
+        pool.clone(),
+        std::sync::Arc::new(scheduler::SystemClock),
+        std::time::Duration::from_secs(60),
+        shutdown_receiver,
+    );
+
     // build our application with a route
     let app = Router::new()
         .route(
             "/feed.xml",
             get(this_week_in_bevy::atom_feed::atom_feed),
         )
+        .route(
+            "/sitemap.xml",
+            get(this_week_in_bevy::sitemap::sitemap),
+        )
+        .route(
+            "/robots.txt",
+            get(this_week_in_bevy::sitemap::robots),
+        )
+        .route(
+            "/feed.json",
+            get(this_week_in_bevy::json_feed::json_feed),
+        )
+        .route(
+            "/feeds/:file",
+            get(this_week_in_bevy::atom_feed::section_atom_feed),
+        )
+        .route(
+            "/images/*public_id",
+            get(this_week_in_bevy::images::serve_local_image),
+        )
         .route(
             "/api/*fn_name",
             get(server_fn_handler).post(server_fn_handler),
@@ -215,8 +407,49 @@ async fn main() {
         tokio::net::TcpListener::bind(&addr).await.unwrap();
     logging::log!("listening on http://{}", &addr);
     axum::serve(listener, app.into_make_service())
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
+
+    let _ = shutdown.send(true);
+    if let Err(e) = session_cleanup.await {
+        warn!(
+            ?e,
+            "session cleanup didn't stop cleanly"
+        );
+    }
+    if let Err(e) = publish_scheduler.await {
+        warn!(
+            ?e,
+            "the publish scheduler didn't stop cleanly"
+        );
+    }
+}
+
+/// Resolves on ctrl-c, or SIGTERM on unix
+#[cfg(feature = "ssr")]
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("to listen for ctrl-c");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(
+            tokio::signal::unix::SignalKind::terminate(),
+        )
+        .expect("to listen for SIGTERM")
+        .recv()
+        .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
 
 #[cfg(not(feature = "ssr"))]
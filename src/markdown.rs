@@ -1,21 +1,44 @@
-#[cfg(feature = "ssr")]
-use comrak::plugins::syntect::SyntectAdapterBuilder;
-#[cfg(feature = "ssr")]
+pub use crate::sanitize::TrustLevel;
+use comrak::plugins::syntect::{
+    SyntectAdapter, SyntectAdapterBuilder,
+};
 use comrak::{
-    markdown_to_html_with_plugins, ComrakPlugins, Options,
+    format_html_with_plugins,
+    nodes::{AstNode, NodeValue},
+    parse_document, Arena, ComrakPlugins, Options,
 };
-use leptos::prelude::*;
-#[cfg(feature = "ssr")]
-use std::io::Cursor;
 #[cfg(feature = "ssr")]
+use leptos::prelude::*;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Cursor,
+    sync::{LazyLock, Mutex},
+};
 use syntect::highlighting::ThemeSet;
 
-#[cfg(feature = "ssr")]
 const NIGHT_OWL: &[u8; 27913] =
     include_bytes!("../night-owlish.tmtheme");
 
-#[cfg(feature = "ssr")]
-pub fn compile(input: &str) -> String {
+/// Loading the theme and syntaxes takes far
+/// longer than rendering a description, so it's
+/// only done once
+static ADAPTER: LazyLock<SyntectAdapter> =
+    LazyLock::new(syntect_adapter);
+
+static CACHE: LazyLock<Mutex<RenderCache>> =
+    LazyLock::new(|| {
+        Mutex::new(RenderCache::new(CACHE_BYTES))
+    });
+
+/// Every description on the site fits many times
+/// over. Edited descriptions leave their old html
+/// behind until it's the least recently used.
+const CACHE_BYTES: usize = 16 * 1024 * 1024;
+
+/// A syntax highlighter using the Night Owl
+/// theme. Prefer [`compile`], which reuses one.
+pub fn syntect_adapter() -> SyntectAdapter {
     // let adapter = SyntectAdapter::new("Solarized
     // (dark)");
     let mut cursor = Cursor::new(NIGHT_OWL);
@@ -28,20 +51,39 @@ pub fn compile(input: &str) -> String {
         .themes
         .entry("Night Owl".to_string())
         .or_insert(theme_night_owl);
-    // let theme_set = ;
-    let adapter = SyntectAdapterBuilder::new()
+    SyntectAdapterBuilder::new()
         .theme_set(theme_set)
         .theme("Night Owl")
-        .build();
+        .build()
+}
+
+/// Renders markdown without looking in the
+/// cache. The rendered html is cleaned with the
+/// policy for `trust`.
+/// [Shortcodes](crate::shortcodes) are
+/// expanded into links and embeds, and
+/// [alerts](crate::alerts) into callouts, at
+/// every trust level.
+pub fn render_with(
+    input: &str,
+    trust: TrustLevel,
+    adapter: &SyntectAdapter,
+) -> String {
     let mut plugins = ComrakPlugins::default();
     plugins.render.codefence_syntax_highlighter =
-        Some(&adapter);
+        Some(adapter);
     let mut options = Options::default();
-    // UNSAFE HTML TAGS!
+    // raw HTML is allowed, but only what the
+    // sanitizer lets through
     options.render.unsafe_ = true;
-    // extensions, like those on github
+    // keeps escapes apart, so `\#123` isn't a
+    // shortcode
+    options.render.escaped_char_spans = true;
+    // extensions, like those on github. The
+    // sanitizer replaces `tagfilter`, which would
+    // escape the iframes admins can embed.
     options.extension.strikethrough = true;
-    options.extension.tagfilter = true;
+    options.extension.tagfilter = false;
     options.extension.table = true;
     options.extension.autolink = false;
     options.extension.tasklist = true;
@@ -52,12 +94,329 @@ pub fn compile(input: &str) -> String {
     options.extension.front_matter_delimiter = None;
     options.extension.multiline_block_quotes = true;
 
-    markdown_to_html_with_plugins(input, &options, &plugins)
+    let arena = Arena::new();
+    let root = parse_document(&arena, input, &options);
+    let authored = root
+        .descendants()
+        .filter(|node| is_html(node))
+        .map(|node| node as *const AstNode)
+        .collect::<HashSet<_>>();
+    crate::shortcodes::expand(&arena, root);
+    crate::alerts::expand(&arena, root);
+
+    // the html the expansions added is ours, so
+    // it's swapped for placeholders the author
+    // can't guess while the rest is cleaned
+    let nonce = rusty_ulid::Ulid::generate();
+    let mut trusted = vec![];
+    for node in root.descendants() {
+        if !is_html(node)
+            || authored.contains(&(node as *const AstNode))
+        {
+            continue;
+        }
+        let placeholder =
+            format!("trusted-{nonce}-{}", trusted.len());
+        let html = match &mut node.data.borrow_mut().value {
+            NodeValue::HtmlBlock(block) => {
+                std::mem::replace(
+                    &mut block.literal,
+                    placeholder.clone(),
+                )
+            }
+            NodeValue::HtmlInline(html) => {
+                std::mem::replace(html, placeholder.clone())
+            }
+            _ => {
+                unreachable!("only html nodes are swapped")
+            }
+        };
+        trusted.push((placeholder, html));
+    }
+
+    let mut html = vec![];
+    format_html_with_plugins(
+        root, &options, &mut html, &plugins,
+    )
+    .expect("writing to a Vec to succeed");
+    let html = String::from_utf8(html)
+        .expect("comrak to write utf-8");
+    trusted.into_iter().fold(
+        trust.clean(&html),
+        |html, (placeholder, trusted)| {
+            html.replacen(&placeholder, &trusted, 1)
+        },
+    )
 }
 
+fn is_html<'a>(node: &'a AstNode<'a>) -> bool {
+    matches!(
+        node.data.borrow().value,
+        NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_)
+    )
+}
+
+/// Rendered html by the SHA-256 of its markdown
+/// and trust level, and nothing else. Changes to
+/// the renderer itself only show up after a
+/// restart.
+///
+/// Holds at most `max_bytes` of html, dropping
+/// the least recently used entries to make room.
+#[derive(Debug)]
+struct RenderCache {
+    max_bytes: usize,
+    bytes: usize,
+    /// bumped on every use, to order the entries
+    clock: u64,
+    entries: HashMap<[u8; 32], (u64, String)>,
+    by_last_use: BTreeMap<u64, [u8; 32]>,
+}
+
+impl RenderCache {
+    fn new(max_bytes: usize) -> Self {
+        RenderCache {
+            max_bytes,
+            bytes: 0,
+            clock: 0,
+            entries: HashMap::new(),
+            by_last_use: BTreeMap::new(),
+        }
+    }
+
+    fn key(input: &str, trust: TrustLevel) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([trust as u8]);
+        hasher.update(input.as_bytes());
+        hasher.finalize().into()
+    }
+
+    fn get(&mut self, key: &[u8; 32]) -> Option<String> {
+        self.clock += 1;
+        let (last_use, html) = self.entries.get_mut(key)?;
+        self.by_last_use.remove(last_use);
+        self.by_last_use.insert(self.clock, *key);
+        *last_use = self.clock;
+        Some(html.clone())
+    }
+
+    fn insert(&mut self, key: [u8; 32], html: String) {
+        if html.len() > self.max_bytes
+            || self.entries.contains_key(&key)
+        {
+            return;
+        }
+        while self.bytes + html.len() > self.max_bytes {
+            let Some((_, oldest)) =
+                self.by_last_use.pop_first()
+            else {
+                break;
+            };
+            if let Some((_, evicted)) =
+                self.entries.remove(&oldest)
+            {
+                self.bytes -= evicted.len();
+            }
+        }
+        self.clock += 1;
+        self.bytes += html.len();
+        self.by_last_use.insert(self.clock, key);
+        self.entries.insert(key, (self.clock, html));
+    }
+}
+
+/// Renders markdown to html, highlighting code
+/// blocks. The same markdown is only rendered
+/// once per process.
+pub fn compile(input: &str, trust: TrustLevel) -> String {
+    let key = RenderCache::key(input, trust);
+    let cache = || {
+        CACHE
+            .lock()
+            .expect("the markdown cache to not be poisoned")
+    };
+    if let Some(html) = cache().get(&key) {
+        return html;
+    }
+    // rendered without holding the lock, so two
+    // requests might both render something new
+    let html = render_with(input, trust, &ADAPTER);
+    cache().insert(key, html.clone());
+    html
+}
+
+#[cfg(feature = "ssr")]
 #[server(MarkdownCompileServer, "/api")]
 pub async fn markdown_compile_server(
     code: String,
 ) -> Result<String, ServerFnError> {
-    Ok(compile(&code))
+    // not cached, so callers can't fill the cache
+    // with markdown that's never on the site
+    Ok(render_with(
+        &code,
+        TrustLevel::Community,
+        &ADAPTER,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_code_blocks() {
+        let html = compile(
+            "```rust\nfn main() {}\n```\n",
+            TrustLevel::Community,
+        );
+        assert!(html.contains("<pre style="), "{html}");
+        assert_eq!(
+            html,
+            render_with(
+                "```rust\nfn main() {}\n```\n",
+                TrustLevel::Community,
+                &syntect_adapter()
+            )
+        );
+    }
+
+    #[test]
+    fn cache_drops_the_least_recently_used() {
+        let mut cache = RenderCache::new(8);
+        let (a, b, c) = (
+            RenderCache::key("a", TrustLevel::Admin),
+            RenderCache::key("a", TrustLevel::Community),
+            RenderCache::key("c", TrustLevel::Admin),
+        );
+        cache.insert(a, "<p>a</p>".to_string());
+        cache.insert(b, "<b>".to_string());
+        assert_eq!(cache.get(&a), None);
+        assert_eq!(cache.get(&b).as_deref(), Some("<b>"));
+
+        cache.insert(c, "<c>".to_string());
+        // `b` was used last, so it stays
+        assert_eq!(cache.get(&b).as_deref(), Some("<b>"));
+        cache.insert(a, "<a>".to_string());
+        assert_eq!(cache.get(&c), None);
+        assert_eq!(cache.get(&a).as_deref(), Some("<a>"));
+        assert_eq!(cache.bytes, 6);
+
+        cache.insert(c, "<p>too long</p>".to_string());
+        assert_eq!(cache.get(&c), None);
+        assert_eq!(cache.get(&b).as_deref(), Some("<b>"));
+    }
+
+    #[test]
+    fn markdown_links_cant_run_scripts() {
+        for input in [
+            "[x](javascript:alert(1))",
+            "[x](JAVASCRIPT:alert(1))",
+            "[x](javascript&#58;alert(1))",
+            "<javascript:alert(1)>",
+            "![x](javascript:alert(1))",
+        ] {
+            let html = compile(input, TrustLevel::Admin)
+                .to_lowercase();
+            // the url can still show up as link text
+            assert!(
+                !html.contains("href=\"javascript")
+                    && !html.contains("src=\"javascript"),
+                "{input} became {html}"
+            );
+        }
+        assert_eq!(
+            compile("[x](https://bevyengine.org)", TrustLevel::Community),
+            "<p><a href=\"https://bevyengine.org\">x</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn shortcodes_work_for_everyone() {
+        let html = compile(
+            "Thanks @alice for #123\n\n{{youtube dQw4w9WgXcQ}}\n",
+            TrustLevel::Community,
+        );
+        assert!(
+            html.contains(r#"<a href="https://github.com/alice">@alice</a>"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ""#),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn escaped_shortcodes_are_text() {
+        assert_eq!(
+            compile(r"Not \#123", TrustLevel::Community),
+            "<p>Not #123</p>\n"
+        );
+    }
+
+    #[test]
+    fn raw_html_is_sanitized() {
+        let input = "Hi <img src=x onerror=alert(1)> there\n\n<script>alert(1)</script>\n\n<iframe src=\"https://www.youtube.com/embed/abc\"></iframe>\n\n<iframe src=\"https://example.com\"></iframe>\n";
+        let youtube = r#"<iframe src="https://www.youtube.com/embed/abc">"#;
+        let community =
+            compile(input, TrustLevel::Community);
+        assert!(
+            !community.contains("onerror"),
+            "{community}"
+        );
+        assert!(
+            !community.contains("<script"),
+            "{community}"
+        );
+        assert!(
+            community.contains(youtube),
+            "{community}"
+        );
+        assert!(
+            !community.contains("example.com"),
+            "{community}"
+        );
+
+        let admin = compile(input, TrustLevel::Admin);
+        assert!(!admin.contains("<script"), "{admin}");
+        assert!(admin.contains(youtube), "{admin}");
+        assert!(
+            admin.contains(
+                r#"<iframe src="https://example.com">"#
+            ),
+            "{admin}"
+        );
+    }
+
+    #[test]
+    fn html_is_sanitized_across_nodes() {
+        // comrak splits this into several inline
+        // html nodes, which only make sense together
+        assert_eq!(
+            compile(
+                "Press <kbd>Ctrl</kbd> <b onclick=alert(1)>now</b>",
+                TrustLevel::Community
+            ),
+            "<p>Press <kbd>Ctrl</kbd> <b>now</b></p>\n"
+        );
+    }
+
+    #[test]
+    fn generated_markup_survives() {
+        let html = compile(
+            "## Hi\n\n- [x] done\n\nA note[^1]\n\n[^1]: here\n\n> [!NOTE]\n> Careful\n",
+            TrustLevel::Community,
+        );
+        for expected in [
+            r##"<a href="#hi" aria-hidden="true" class="anchor" id="hi">"##,
+            r#"<input checked="""#,
+            r#"type="checkbox""#,
+            r#"disabled="""#,
+            r#"<section class="footnotes">"#,
+            ">Note</h3>",
+        ] {
+            assert!(html.contains(expected), "{html}");
+        }
+        assert!(!html.contains("trusted-"), "{html}");
+    }
 }
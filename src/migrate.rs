@@ -0,0 +1,421 @@
+//! Applies the numbered `sql/*.sql` files to the
+//! database.
+//!
+//! The files are embedded in the binary and
+//! applied in order by `this-week-in-bevy
+//! migrate`. Each applied file is recorded in
+//! `schema_migrations`, and the server refuses to
+//! start while any are [`pending`].
+//!
+//! A new migration is a new `sql/NNN-name.sql`
+//! file plus an entry in [`MIGRATIONS`].
+use sqlx::{
+    pool::PoolConnection, Acquire, Executor, MySql,
+    MySqlPool,
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// ex: `004-users.sql`
+    pub file: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// The number the file name starts with
+    pub fn version(&self) -> u32 {
+        self.file
+            .split_once('-')
+            .and_then(|(version, _)| version.parse().ok())
+            .expect("migration files start with a number")
+    }
+
+    /// The file without its own `BEGIN;` and
+    /// `COMMIT;`, which are replaced by the
+    /// transaction the migration runs in.
+    fn statements(&self) -> &'static str {
+        let sql = self.sql.trim();
+        let mut sql =
+            sql.strip_prefix("BEGIN;").unwrap_or(sql);
+        // files can end with commented out queries
+        // after their `COMMIT;`
+        while let Some((rest, last)) = sql.rsplit_once('\n')
+        {
+            if !last.trim_start().starts_with("--") {
+                break;
+            }
+            sql = rest.trim_end();
+        }
+        sql.strip_suffix("COMMIT;").unwrap_or(sql).trim()
+    }
+}
+
+macro_rules! migrations {
+    ($($file:literal),* $(,)?) => {
+        &[$(Migration {
+            file: $file,
+            sql: include_str!(concat!("../sql/", $file)),
+        }),*]
+    };
+}
+
+/// Every migration, in the order they're applied
+pub const MIGRATIONS: &[Migration] = migrations![
+    "001-tables.sql",
+    "002-devlogs.sql",
+    "003-posted-at.sql",
+    "004-users.sql",
+    "005-expand-cloudinary-ids.sql",
+    "006-expand-issue-description.sql",
+    "007-publish-metadata.sql",
+    "008-publish-schedule.sql",
+    "009-user-roles.sql",
+    "010-submissions.sql",
+    "011-search.sql",
+    "012-crates-io-releases.sql",
+    "013-issue-contributors.sql",
+    "014-issue-item-positions.sql",
+];
+
+/// Held while migrating so two servers deploying
+/// at once don't both apply the same file
+const LOCK_NAME: &str = "this_week_in_bevy_migrate";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrateError {
+    #[error(transparent)]
+    Sqlx(sqlx::Error),
+
+    #[error("{file} failed: {source}")]
+    Failed {
+        file: &'static str,
+        source: sqlx::Error,
+    },
+
+    #[error("another process is already migrating")]
+    Locked,
+
+    #[error("there is no migration {0}")]
+    UnknownVersion(u32),
+}
+
+async fn ensure_table(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<(), MigrateError> {
+    conn.execute(
+        r#"CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INT UNSIGNED NOT NULL,
+    file VARCHAR(200) NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (version)
+)"#,
+    )
+    .await
+    .map_err(MigrateError::Sqlx)?;
+    Ok(())
+}
+
+async fn applied_versions(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<Vec<u32>, MigrateError> {
+    ensure_table(conn).await?;
+    sqlx::query_scalar(
+        "SELECT version FROM schema_migrations ORDER BY version",
+    )
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(MigrateError::Sqlx)
+}
+
+fn not_applied(applied: &[u32]) -> Vec<&'static Migration> {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| {
+            !applied.contains(&migration.version())
+        })
+        .collect()
+}
+
+/// Migrations that haven't been applied yet
+pub async fn pending(
+    pool: &MySqlPool,
+) -> Result<Vec<&'static Migration>, MigrateError> {
+    let mut conn =
+        pool.acquire().await.map_err(MigrateError::Sqlx)?;
+    let applied = applied_versions(&mut conn).await?;
+    Ok(not_applied(&applied))
+}
+
+async fn lock(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<(), MigrateError> {
+    let locked: Option<i32> =
+        sqlx::query_scalar("SELECT GET_LOCK(?, 10)")
+            .bind(LOCK_NAME)
+            .fetch_one(&mut **conn)
+            .await
+            .map_err(MigrateError::Sqlx)?;
+    if locked != Some(1) {
+        return Err(MigrateError::Locked);
+    }
+    Ok(())
+}
+
+async fn unlock(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<(), MigrateError> {
+    sqlx::query("SELECT RELEASE_LOCK(?)")
+        .bind(LOCK_NAME)
+        .execute(&mut **conn)
+        .await
+        .map_err(MigrateError::Sqlx)?;
+    Ok(())
+}
+
+/// The lock is released even when migrating
+/// fails, but that failure is the one worth
+/// reporting.
+fn first_error<T>(
+    result: Result<T, MigrateError>,
+    unlocked: Result<(), MigrateError>,
+) -> Result<T, MigrateError> {
+    match (result, unlocked) {
+        (Err(error), Err(unlock_error)) => {
+            warn!(
+                ?unlock_error,
+                "failed to release the migration lock"
+            );
+            Err(error)
+        }
+        (result, Ok(())) => result,
+        (Ok(_), Err(unlock_error)) => Err(unlock_error),
+    }
+}
+
+/// Apply every pending migration in order,
+/// returning the ones that were applied.
+///
+/// Each file runs in a transaction together with
+/// its `schema_migrations` row. MySQL commits
+/// DDL statements implicitly, so a file that
+/// fails part way can leave its earlier
+/// statements behind. It stays pending and has
+/// to be fixed up by hand before re-running.
+pub async fn run(
+    pool: &MySqlPool,
+) -> Result<Vec<&'static Migration>, MigrateError> {
+    // GET_LOCK belongs to the connection, so
+    // everything runs on this one
+    let mut conn =
+        pool.acquire().await.map_err(MigrateError::Sqlx)?;
+    lock(&mut conn).await?;
+    let result = apply_pending(&mut conn).await;
+    let unlocked = unlock(&mut conn).await;
+    first_error(result, unlocked)
+}
+
+async fn apply_pending(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<Vec<&'static Migration>, MigrateError> {
+    let applied = applied_versions(conn).await?;
+    let pending = not_applied(&applied);
+    for migration in &pending {
+        let failed = |source| MigrateError::Failed {
+            file: migration.file,
+            source,
+        };
+        let mut tx = conn.begin().await.map_err(failed)?;
+        tx.execute(migration.statements())
+            .await
+            .map_err(failed)?;
+        record(&mut tx, migration).await.map_err(failed)?;
+        tx.commit().await.map_err(failed)?;
+        info!(
+            file = migration.file,
+            "applied migration"
+        );
+    }
+    Ok(pending)
+}
+
+/// Record every migration up to and including
+/// `version` as applied without running it, for
+/// databases whose schema was set up by hand
+/// before migrations were tracked.
+pub async fn baseline(
+    pool: &MySqlPool,
+    version: u32,
+) -> Result<Vec<&'static Migration>, MigrateError> {
+    if !MIGRATIONS
+        .iter()
+        .any(|migration| migration.version() == version)
+    {
+        return Err(MigrateError::UnknownVersion(version));
+    }
+    let mut conn =
+        pool.acquire().await.map_err(MigrateError::Sqlx)?;
+    lock(&mut conn).await?;
+    let result = record_up_to(&mut conn, version).await;
+    let unlocked = unlock(&mut conn).await;
+    first_error(result, unlocked)
+}
+
+async fn record_up_to(
+    conn: &mut PoolConnection<MySql>,
+    version: u32,
+) -> Result<Vec<&'static Migration>, MigrateError> {
+    let applied = applied_versions(conn).await?;
+    let baselined: Vec<_> = not_applied(&applied)
+        .into_iter()
+        .filter(|migration| migration.version() <= version)
+        .collect();
+    for migration in &baselined {
+        record(conn, migration)
+            .await
+            .map_err(MigrateError::Sqlx)?;
+    }
+    Ok(baselined)
+}
+
+async fn record(
+    conn: &mut sqlx::MySqlConnection,
+    migration: &Migration,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO schema_migrations (version, file) VALUES (?, ?)",
+    )
+    .bind(migration.version())
+    .bind(migration.file)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_sql_file_is_listed_in_order() {
+        let mut files = std::fs::read_dir(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/sql"
+        ))
+        .expect("the sql directory to exist")
+        .map(|entry| {
+            entry
+                .unwrap()
+                .file_name()
+                .into_string()
+                .unwrap()
+        })
+        .filter(|file| file.ends_with(".sql"))
+        .collect::<Vec<_>>();
+        files.sort();
+
+        assert_eq!(
+            MIGRATIONS
+                .iter()
+                .map(|migration| migration.file)
+                .collect::<Vec<_>>(),
+            files
+        );
+        assert!(MIGRATIONS
+            .windows(2)
+            .all(|pair| pair[0].version()
+                < pair[1].version()));
+    }
+
+    #[test]
+    fn statements_drop_the_files_own_transaction() {
+        let migration = Migration {
+            file: "012-example.sql",
+            sql: "BEGIN;\n\nALTER TABLE issue ADD x INT;\n\nCOMMIT;\n",
+        };
+        assert_eq!(migration.version(), 12);
+        assert_eq!(
+            migration.statements(),
+            "ALTER TABLE issue ADD x INT;"
+        );
+    }
+
+    #[test]
+    fn statements_ignore_comments_after_commit() {
+        let migration = Migration {
+            file: "012-example.sql",
+            sql: "BEGIN;\nALTER TABLE issue ADD x INT;\nCOMMIT;\n\n-- SELECT x\n--   FROM issue;\n",
+        };
+        assert_eq!(
+            migration.statements(),
+            "ALTER TABLE issue ADD x INT;"
+        );
+    }
+
+    #[test]
+    fn no_migration_manages_its_own_transaction() {
+        for migration in MIGRATIONS {
+            assert!(
+                !migration.statements().lines().any(
+                    |line| matches!(
+                        line.trim(),
+                        "BEGIN;" | "COMMIT;"
+                    )
+                ),
+                "{} would end the transaction it runs in",
+                migration.file
+            );
+        }
+    }
+
+    #[test]
+    fn migration_errors_win_over_unlock_errors() {
+        let result: Result<(), _> = first_error(
+            Err(MigrateError::UnknownVersion(99)),
+            Err(MigrateError::Locked),
+        );
+        assert!(matches!(
+            result,
+            Err(MigrateError::UnknownVersion(99))
+        ));
+        assert!(matches!(
+            first_error(Ok(()), Err(MigrateError::Locked)),
+            Err(MigrateError::Locked)
+        ));
+    }
+
+    #[test]
+    fn not_applied_skips_recorded_versions() {
+        let pending = not_applied(&[1, 2, 3]);
+        assert_eq!(pending.len(), MIGRATIONS.len() - 3);
+        assert_eq!(pending[0].version(), 4);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn migrating_twice_applies_nothing() {
+        use crate::sql::test_db::DisposableSchema;
+
+        // the disposable schema is migrated with
+        // `run` already
+        let schema = DisposableSchema::new().await;
+        assert!(pending(&schema.pool)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(run(&schema.pool)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM schema_migrations",
+        )
+        .fetch_one(&schema.pool)
+        .await
+        .unwrap();
+        assert_eq!(count, MIGRATIONS.len() as i64);
+
+        schema.drop().await;
+    }
+}
@@ -0,0 +1,220 @@
+//! Links that let someone without an account read
+//! an issue before it's published.
+//!
+//! Links look like
+//! `/preview/{slug}?expires=...&signature=...`,
+//! where the signature is an HMAC-SHA256 of the
+//! issue date and the expiry, keyed with
+//! `PREVIEW_SIGNING_KEY`. Changing the key
+//! revokes every link that's been shared.
+use data_encoding::HEXLOWER;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use time::{Date, OffsetDateTime};
+
+/// The longest a preview link can stay valid for
+pub const MAX_DAYS: u32 = 30;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PreviewError {
+    #[error(
+        "PREVIEW_SIGNING_KEY must be set to share previews"
+    )]
+    MissingKey,
+
+    #[error(
+        "preview links last between 1 and {MAX_DAYS} days"
+    )]
+    InvalidDuration,
+
+    #[error("the preview link has expired")]
+    Expired,
+
+    #[error("the preview link's signature doesn't match")]
+    InvalidSignature,
+}
+
+#[derive(Clone)]
+pub struct PreviewKey(Vec<u8>);
+
+impl PreviewKey {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        PreviewKey(key.into())
+    }
+
+    pub fn from_env() -> Result<Self, PreviewError> {
+        std::env::var("PREVIEW_SIGNING_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+            .map(PreviewKey::new)
+            .ok_or(PreviewError::MissingKey)
+    }
+
+    fn mac(
+        &self,
+        issue_date: Date,
+        expires: i64,
+    ) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0)
+                .expect("HMAC takes keys of any size");
+        mac.update(
+            format!("{issue_date}:{expires}").as_bytes(),
+        );
+        mac
+    }
+
+    /// The signature for a link to `issue_date`
+    /// that stops working at the `expires` unix
+    /// timestamp
+    pub fn sign(
+        &self,
+        issue_date: Date,
+        expires: i64,
+    ) -> String {
+        HEXLOWER.encode(
+            &self
+                .mac(issue_date, expires)
+                .finalize()
+                .into_bytes(),
+        )
+    }
+
+    pub fn verify(
+        &self,
+        issue_date: Date,
+        expires: i64,
+        signature: &str,
+        now: OffsetDateTime,
+    ) -> Result<(), PreviewError> {
+        let signature = HEXLOWER
+            .decode(signature.as_bytes())
+            .map_err(|_| PreviewError::InvalidSignature)?;
+        // checked before the expiry, so a link
+        // with an edited `expires` is reported as
+        // tampered with rather than expired
+        self.mac(issue_date, expires)
+            .verify_slice(&signature)
+            .map_err(|_| PreviewError::InvalidSignature)?;
+        if now.unix_timestamp() >= expires {
+            return Err(PreviewError::Expired);
+        }
+        Ok(())
+    }
+
+    /// A path to the preview of the issue at
+    /// `slug` that works for `days` days from
+    /// `now`
+    pub fn signed_path(
+        &self,
+        slug: &str,
+        issue_date: Date,
+        days: u32,
+        now: OffsetDateTime,
+    ) -> Result<String, PreviewError> {
+        if !(1..=MAX_DAYS).contains(&days) {
+            return Err(PreviewError::InvalidDuration);
+        }
+        let expires = (now
+            + time::Duration::days(days.into()))
+        .unix_timestamp();
+        Ok(format!(
+            "/preview/{slug}?expires={expires}&signature={}",
+            self.sign(issue_date, expires)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    const ISSUE: Date = date!(2024 - 02 - 12);
+    const NOW: OffsetDateTime =
+        datetime!(2024-02-10 12:00 UTC);
+
+    fn query(path: &str) -> (i64, String) {
+        let (_, query) = path.split_once('?').unwrap();
+        let params: Vec<(&str, &str)> = query
+            .split('&')
+            .map(|pair| pair.split_once('=').unwrap())
+            .collect();
+        assert_eq!(params[0].0, "expires");
+        assert_eq!(params[1].0, "signature");
+        (
+            params[0].1.parse().unwrap(),
+            params[1].1.to_string(),
+        )
+    }
+
+    #[test]
+    fn signed_links_work_until_they_expire() {
+        let key = PreviewKey::new("secret");
+        let path = key
+            .signed_path("2024-02-12-slug", ISSUE, 7, NOW)
+            .unwrap();
+        assert!(
+            path.starts_with("/preview/2024-02-12-slug?")
+        );
+        let (expires, signature) = query(&path);
+
+        assert_eq!(
+            key.verify(ISSUE, expires, &signature, NOW),
+            Ok(())
+        );
+        assert_eq!(
+            key.verify(
+                ISSUE,
+                expires,
+                &signature,
+                NOW + time::Duration::days(7)
+            ),
+            Err(PreviewError::Expired)
+        );
+    }
+
+    #[test]
+    fn tampered_links_are_rejected() {
+        let key = PreviewKey::new("secret");
+        let expires = (NOW + time::Duration::days(1))
+            .unix_timestamp();
+        let signature = key.sign(ISSUE, expires);
+
+        for (issue_date, expires, signature) in [
+            // another issue
+            (
+                date!(2024 - 02 - 19),
+                expires,
+                signature.clone(),
+            ),
+            // a later expiry
+            (ISSUE, expires + 3600, signature.clone()),
+            (ISSUE, expires, "not hex".to_string()),
+        ] {
+            assert_eq!(
+                key.verify(
+                    issue_date, expires, &signature, NOW
+                ),
+                Err(PreviewError::InvalidSignature)
+            );
+        }
+
+        assert_eq!(
+            PreviewKey::new("another secret")
+                .verify(ISSUE, expires, &signature, NOW),
+            Err(PreviewError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn links_last_a_limited_time() {
+        let key = PreviewKey::new("secret");
+        for days in [0, MAX_DAYS + 1] {
+            assert_eq!(
+                key.signed_path("slug", ISSUE, days, NOW),
+                Err(PreviewError::InvalidDuration)
+            );
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Roles stored on `github_users.role`.
+//!
+//! Every admin check goes through
+//! [`has_admin_access`] so the server functions
+//! and the `/admin` route agree on who gets in.
+use crate::string_enum::string_enum;
+use serde::{Deserialize, Serialize};
+
+string_enum! {
+    #[derive(Serialize, Deserialize)]
+    pub enum Role {
+        /// Can do everything, including managing
+        /// other users' roles
+        Admin = "admin",
+        /// Can use the admin panel to build issues
+        Editor = "editor",
+        /// Any logged in GitHub user. The default
+        /// for new accounts.
+        Contributor = "contributor",
+    }
+    #[error("unknown role `{0}`")]
+    pub struct UnknownRole;
+}
+
+impl Role {
+    pub fn can_access_admin(&self) -> bool {
+        matches!(self, Role::Admin | Role::Editor)
+    }
+
+    pub fn can_manage_roles(&self) -> bool {
+        matches!(self, Role::Admin)
+    }
+}
+
+/// The one check for admin panel access, used
+/// by `with_admin_access` and the `/admin` route.
+/// Logged out users (`None`) never have access.
+pub fn has_admin_access(role: Option<Role>) -> bool {
+    role.is_some_and(|role| role.can_access_admin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_access() {
+        assert!(has_admin_access(Some(Role::Admin)));
+        assert!(has_admin_access(Some(Role::Editor)));
+        assert!(!has_admin_access(Some(
+            Role::Contributor
+        )));
+        assert!(!has_admin_access(None));
+    }
+
+    #[test]
+    fn only_admins_manage_roles() {
+        assert!(Role::Admin.can_manage_roles());
+        assert!(!Role::Editor.can_manage_roles());
+        assert!(!Role::Contributor.can_manage_roles());
+    }
+}
@@ -0,0 +1,453 @@
+//! An allowlist for the html that rendered
+//! markdown can contain, built on [`ammonia`].
+//!
+//! `markdown::render_with` cleans the whole
+//! rendered document with the policy for the
+//! text's [`TrustLevel`]. The policies also allow
+//! the markup comrak writes itself, like
+//! highlighted code, heading anchors, footnotes
+//! and task lists.
+//!
+//! Tags that aren't allowed are dropped and
+//! their contents kept, except for `<script>` and
+//! `<style>` whose contents are dropped too.
+//! Attributes that aren't allowed are dropped,
+//! and so are urls with a scheme that isn't.
+use ammonia::Builder;
+use std::{borrow::Cow, sync::LazyLock};
+
+/// Who wrote the markdown being rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrustLevel {
+    /// Issue descriptions and items made by
+    /// editors and admins. Allows any embed.
+    Admin,
+    /// Anything that can come from a submission
+    /// or an import. Only YouTube can be
+    /// embedded.
+    Community,
+}
+
+static ADMIN: LazyLock<Builder<'static>> =
+    LazyLock::new(|| policy(TrustLevel::Admin));
+
+static COMMUNITY: LazyLock<Builder<'static>> =
+    LazyLock::new(|| policy(TrustLevel::Community));
+
+/// Tags and their attributes, for every level
+const TAGS: &[(&str, &[&str])] = &[
+    (
+        "a",
+        &[
+            "href",
+            "title",
+            "id",
+            "class",
+            "aria-hidden",
+            "aria-label",
+        ],
+    ),
+    ("abbr", &["title"]),
+    ("b", &[]),
+    ("blockquote", &["cite"]),
+    ("br", &[]),
+    ("code", &["class"]),
+    ("dd", &[]),
+    ("del", &[]),
+    ("details", &[]),
+    ("dl", &[]),
+    ("dt", &[]),
+    ("em", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("hr", &[]),
+    ("i", &[]),
+    (
+        "iframe",
+        &[
+            "src",
+            "width",
+            "height",
+            "title",
+            "allow",
+            "allowfullscreen",
+            "frameborder",
+            "loading",
+            "referrerpolicy",
+        ],
+    ),
+    (
+        "img",
+        &["src", "alt", "title", "width", "height"],
+    ),
+    ("input", &["checked"]),
+    ("kbd", &[]),
+    ("li", &["id"]),
+    ("ol", &["start"]),
+    ("p", &[]),
+    ("pre", &["style"]),
+    ("s", &[]),
+    ("section", &["class"]),
+    ("span", &["style"]),
+    ("strong", &[]),
+    ("sub", &[]),
+    ("summary", &[]),
+    ("sup", &["class"]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["align"]),
+    ("th", &["align"]),
+    ("thead", &[]),
+    ("tr", &[]),
+    ("ul", &[]),
+];
+
+/// Tags only admins can use, and their
+/// attributes
+const ADMIN_TAGS: &[(&str, &[&str])] = &[
+    (
+        "audio",
+        &["src", "controls", "loop", "muted"],
+    ),
+    ("div", &["class"]),
+    ("figcaption", &[]),
+    ("figure", &["class"]),
+    ("source", &["src", "type"]),
+    (
+        "video",
+        &[
+            "src",
+            "poster",
+            "controls",
+            "autoplay",
+            "loop",
+            "muted",
+            "playsinline",
+            "width",
+            "height",
+        ],
+    ),
+];
+
+/// Where community text can embed from
+const EMBEDS: &[&str] = &[
+    "https://www.youtube.com/embed/",
+    "https://www.youtube-nocookie.com/embed/",
+];
+
+impl TrustLevel {
+    /// The trust for an item's text, from who
+    /// submitted it and the role they have now.
+    /// Items from before `submitted_by` was
+    /// recorded could only be made in the admin
+    /// panel.
+    pub fn of_submitter(
+        submitted_by: Option<&str>,
+        role: Option<&str>,
+    ) -> Self {
+        let role = role
+            .and_then(|role| role.parse().ok())
+            .filter(crate::roles::Role::can_access_admin);
+        match (submitted_by, role) {
+            (None, _) | (_, Some(_)) => TrustLevel::Admin,
+            (Some(_), None) => TrustLevel::Community,
+        }
+    }
+
+    /// `html` with everything this level doesn't
+    /// allow removed
+    pub fn clean(&self, html: &str) -> String {
+        let policy = match self {
+            TrustLevel::Admin => &ADMIN,
+            TrustLevel::Community => &COMMUNITY,
+        };
+        policy.clean(html).to_string()
+    }
+}
+
+fn policy(trust: TrustLevel) -> Builder<'static> {
+    let tags = match trust {
+        TrustLevel::Admin => [TAGS, ADMIN_TAGS].concat(),
+        TrustLevel::Community => TAGS.to_vec(),
+    };
+    let mut builder = Builder::empty();
+    builder
+        .tags(tags.iter().map(|(tag, _)| *tag).collect())
+        .tag_attributes(
+            tags.iter()
+                .map(|(tag, attributes)| {
+                    (
+                        *tag,
+                        attributes
+                            .iter()
+                            .copied()
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+        .generic_attributes(Default::default())
+        // task list checkboxes are never inputs
+        // a reader can type into
+        .set_tag_attribute_values(
+            [(
+                "input",
+                [("type", "checkbox"), ("disabled", "")]
+                    .into(),
+            )]
+            .into(),
+        )
+        .url_schemes(["http", "https", "mailto"].into())
+        .link_rel(None)
+        .attribute_filter(
+            move |element, attribute, value| {
+                filter(trust, element, attribute, value)
+            },
+        );
+    builder
+}
+
+/// Checks the values the tag and attribute
+/// allowlists can't
+fn filter<'v>(
+    trust: TrustLevel,
+    element: &str,
+    attribute: &str,
+    value: &'v str,
+) -> Option<Cow<'v, str>> {
+    let allowed = match (element, attribute) {
+        ("iframe", "src") => {
+            trust == TrustLevel::Admin
+                || EMBEDS
+                    .iter()
+                    .any(|embed| value.starts_with(embed))
+        }
+        // highlighted code sets the colors inline
+        ("pre" | "span", "style") => {
+            value.split(';').all(|declaration| {
+                is_color_declaration(declaration.trim())
+            })
+        }
+        ("code", "class") => value
+            .strip_prefix("language-")
+            .is_some_and(|language| {
+                language.chars().all(|c| {
+                    c.is_ascii_alphanumeric()
+                        || matches!(c, '-' | '_' | '+')
+                })
+            }),
+        // heading anchors and footnotes
+        ("a", "class") => {
+            matches!(value, "anchor" | "footnote-backref")
+        }
+        ("section", "class") => value == "footnotes",
+        ("sup", "class") => value == "footnote-ref",
+        _ => true,
+    };
+    allowed.then_some(Cow::Borrowed(value))
+}
+
+/// `color:#011627` and the like, or nothing
+fn is_color_declaration(declaration: &str) -> bool {
+    if declaration.is_empty() {
+        return true;
+    }
+    let Some((property, value)) =
+        declaration.split_once(':')
+    else {
+        return false;
+    };
+    let value = value.trim();
+    match property.trim() {
+        "color" | "background-color" => {
+            value.strip_prefix('#').is_some_and(|hex| {
+                matches!(hex.len(), 3 | 4 | 6 | 8)
+                    && hex
+                        .chars()
+                        .all(|c| c.is_ascii_hexdigit())
+            })
+        }
+        "font-weight" => value == "bold",
+        "font-style" => value == "italic",
+        "text-decoration" => value == "underline",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn community(html: &str) -> String {
+        TrustLevel::Community.clean(html)
+    }
+
+    #[test]
+    fn keeps_allowed_markup() {
+        assert_eq!(
+            community(
+                r#"<p>Hi <a href="https://bevyengine.org" title='Bevy'>there</a><br/></p>"#
+            ),
+            r#"<p>Hi <a href="https://bevyengine.org" title="Bevy">there</a><br></p>"#
+        );
+        assert_eq!(
+            community(
+                r#"<img src="/bevy.png" alt="a &amp; b">"#
+            ),
+            r#"<img src="/bevy.png" alt="a &amp; b">"#
+        );
+        assert_eq!(
+            community(
+                r#"<pre style="background-color:#011627;"><span style="color:#c792ea;font-weight:bold;">fn</span></pre>"#
+            ),
+            r#"<pre style="background-color:#011627;"><span style="color:#c792ea;font-weight:bold;">fn</span></pre>"#
+        );
+    }
+
+    #[test]
+    fn drops_scripts_and_their_contents() {
+        for html in [
+            "<script>alert(1)</script>ok",
+            "<SCRIPT src=//evil.example>alert(1)</SCRIPT >ok",
+            "<style>body { display: none }</style>ok",
+            "<!-- <script>alert(1)</script> -->ok",
+        ] {
+            assert_eq!(community(html), "ok", "{html}");
+        }
+        assert_eq!(community("<script>alert(1)"), "");
+    }
+
+    #[test]
+    fn drops_event_handlers_and_styles() {
+        assert_eq!(
+            community(
+                r#"<img src="x" onerror="alert(1)">"#
+            ),
+            r#"<img src="x">"#
+        );
+        assert_eq!(
+            community(
+                "<a href=/ OnMouseOver=alert(1)>x</a>"
+            ),
+            r#"<a href="/">x</a>"#
+        );
+        assert_eq!(
+            community(r#"<p onclick="alert(1)">x</p>"#),
+            "<p>x</p>"
+        );
+        assert_eq!(
+            community(
+                r#"<span style="position:fixed;color:#fff">x</span>"#
+            ),
+            "<span>x</span>"
+        );
+        // the forced attributes come in any order
+        let input =
+            community(r#"<input type="text" value="x">"#);
+        assert!(input.starts_with("<input "), "{input}");
+        assert!(input.contains(r#"type="checkbox""#));
+        assert!(input.contains(r#"disabled="""#));
+        assert!(!input.contains("value"), "{input}");
+    }
+
+    #[test]
+    fn drops_javascript_urls() {
+        for html in [
+            r#"<a href="javascript:alert(1)">x</a>"#,
+            r#"<a href="JavaScript:alert(1)">x</a>"#,
+            "<a href=' javascript:alert(1)'>x</a>",
+            "<a href=\"java\tscript:alert(1)\">x</a>",
+            r#"<a href="&#106;avascript&#58;alert(1)">x</a>"#,
+            r#"<a href="&#x6A;avascript&colon;alert(1)">x</a>"#,
+            r#"<a href="data:text/html,<script>alert(1)</script>">x</a>"#,
+            r#"<a href="vbscript:msgbox(1)">x</a>"#,
+        ] {
+            let clean = community(html);
+            assert_eq!(clean, "<a>x</a>", "{html}");
+        }
+        assert_eq!(
+            community(r#"<img src="javascript:alert(1)">"#),
+            "<img>"
+        );
+    }
+
+    #[test]
+    fn embeds_depend_on_trust() {
+        let youtube = r#"<iframe src="https://www.youtube.com/embed/abc" allowfullscreen onload="alert(1)"></iframe>"#;
+        let clean_youtube = r#"<iframe src="https://www.youtube.com/embed/abc" allowfullscreen=""></iframe>"#;
+        assert_eq!(community(youtube), clean_youtube);
+        assert_eq!(
+            TrustLevel::Admin.clean(youtube),
+            clean_youtube
+        );
+
+        let other = r#"<iframe src="https://example.com/embed"></iframe>"#;
+        assert_eq!(community(other), "<iframe></iframe>");
+        assert_eq!(TrustLevel::Admin.clean(other), other);
+        assert_eq!(
+            TrustLevel::Admin.clean(
+                r#"<iframe src="javascript:alert(1)"></iframe>"#
+            ),
+            "<iframe></iframe>"
+        );
+        assert_eq!(
+            community(
+                r#"<video src="/clip.mp4" controls></video><b>x</b>"#
+            ),
+            "<b>x</b>"
+        );
+        assert_eq!(
+            community(
+                r#"<div class="fixed inset-0">x</div>"#
+            ),
+            "x"
+        );
+    }
+
+    #[test]
+    fn text_that_isnt_a_tag_is_escaped() {
+        assert_eq!(
+            community("1 < 2 > 0"),
+            "1 &lt; 2 &gt; 0"
+        );
+        assert_eq!(
+            community("<div><b>kept</b></div>"),
+            "<b>kept</b>"
+        );
+    }
+
+    #[test]
+    fn trust_comes_from_the_submitter() {
+        assert_eq!(
+            TrustLevel::of_submitter(None, None),
+            TrustLevel::Admin
+        );
+        assert_eq!(
+            TrustLevel::of_submitter(
+                Some("alice"),
+                Some("editor")
+            ),
+            TrustLevel::Admin
+        );
+        for role in
+            [Some("contributor"), Some("wizard"), None]
+        {
+            assert_eq!(
+                TrustLevel::of_submitter(Some("bob"), role),
+                TrustLevel::Community,
+                "{role:?}"
+            );
+        }
+        assert_eq!(
+            TrustLevel::of_submitter(
+                Some("crates.io"),
+                None
+            ),
+            TrustLevel::Community
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Publishes draft issues once their `publish_at`
+//! time has passed.
+//!
+//! The loop is started from `main.rs` next to the
+//! router. The current time comes from a
+//! [`Clock`] so the "is it time yet" logic can be
+//! tested without waiting on the wall clock.
+use crate::issue_date::parse_issue_date_from_slug;
+use sqlx::MySqlPool;
+use std::{sync::Arc, time::Duration};
+use time::OffsetDateTime;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// The wall clock, used in production
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ScheduledIssue {
+    pub id: Vec<u8>,
+    pub slug: String,
+    pub issue_date: time::Date,
+    pub publish_at: Option<OffsetDateTime>,
+    pub publish_scheduled_by: Option<String>,
+}
+
+impl ScheduledIssue {
+    /// An issue is due once its `publish_at` has
+    /// passed. Issues whose slug doesn't match
+    /// the issue date are never published
+    /// automatically, the same rule
+    /// `publish_issue` enforces.
+    pub fn is_due(&self, now: OffsetDateTime) -> bool {
+        self.publish_at
+            .is_some_and(|publish_at| publish_at <= now)
+            && parse_issue_date_from_slug(&self.slug)
+                == Some(self.issue_date)
+    }
+}
+
+/// Spawn the scheduler loop, checking for due
+/// issues every `interval`. The loop stops once
+/// `shutdown` changes (or its sender is
+/// dropped), letting a run that's in progress
+/// finish first.
+pub fn spawn(
+    pool: MySqlPool,
+    clock: Arc<dyn Clock>,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => {
+                    info!("stopped the publish scheduler");
+                    return;
+                }
+            }
+            match publish_due_issues(&pool, clock.as_ref())
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => {
+                    info!(
+                        count,
+                        "published scheduled issues"
+                    )
+                }
+                Err(e) => {
+                    error!(?e, "failed to publish scheduled issues")
+                }
+            }
+        }
+    })
+}
+
+/// Publish every scheduled draft that is due
+/// according to `clock`, returning how many were
+/// published.
+pub async fn publish_due_issues(
+    pool: &MySqlPool,
+    clock: &dyn Clock,
+) -> Result<u64, sqlx::Error> {
+    let now = clock.now();
+
+    let scheduled = sqlx::query_as!(
+        ScheduledIssue,
+        r#"SELECT
+    id,
+    slug,
+    issue_date,
+    publish_at,
+    publish_scheduled_by
+FROM issue
+WHERE status = 'draft'
+AND publish_at IS NOT NULL"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut published = 0;
+    for issue in scheduled {
+        if !issue.is_due(now) {
+            if issue.publish_at.is_some_and(|at| at <= now)
+            {
+                warn!(
+                    slug = issue.slug,
+                    "scheduled issue has a slug that doesn't match its issue date, skipping"
+                );
+            }
+            continue;
+        }
+
+        let result = sqlx::query!(
+            r#"
+    UPDATE issue
+    SET
+        status = 'publish',
+        published_at = ?,
+        published_by = ?,
+        publish_at = NULL,
+        publish_scheduled_by = NULL
+    WHERE id = ?
+    AND status = 'draft'
+        "#,
+            now,
+            issue.publish_scheduled_by,
+            issue.id
+        )
+        .execute(pool)
+        .await?;
+        published += result.rows_affected();
+    }
+
+    Ok(published)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    struct FixedClock(OffsetDateTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> OffsetDateTime {
+            self.0
+        }
+    }
+
+    fn scheduled(
+        slug: &str,
+        publish_at: Option<OffsetDateTime>,
+    ) -> ScheduledIssue {
+        ScheduledIssue {
+            id: vec![],
+            slug: slug.to_string(),
+            issue_date: date!(2024 - 02 - 12),
+            publish_at,
+            publish_scheduled_by: Some(
+                "ChristopherBiscardi".to_string(),
+            ),
+        }
+    }
+
+    #[test]
+    fn not_due_before_publish_at() {
+        let clock =
+            FixedClock(datetime!(2024-02-12 8:59 UTC));
+        let issue = scheduled(
+            "2024-02-12-some-slug",
+            Some(datetime!(2024-02-12 9:00 UTC)),
+        );
+        assert!(!issue.is_due(clock.now()));
+    }
+
+    #[test]
+    fn due_at_and_after_publish_at() {
+        let issue = scheduled(
+            "2024-02-12-some-slug",
+            Some(datetime!(2024-02-12 9:00 UTC)),
+        );
+        let at = FixedClock(datetime!(2024-02-12 9:00 UTC));
+        let after =
+            FixedClock(datetime!(2024-02-13 0:00 UTC));
+        assert!(issue.is_due(at.now()));
+        assert!(issue.is_due(after.now()));
+    }
+
+    #[test]
+    fn unscheduled_is_never_due() {
+        let clock =
+            FixedClock(datetime!(2030-01-01 0:00 UTC));
+        let issue = scheduled("2024-02-12-some-slug", None);
+        assert!(!issue.is_due(clock.now()));
+    }
+
+    #[test]
+    fn mismatched_slug_is_never_due() {
+        let clock =
+            FixedClock(datetime!(2030-01-01 0:00 UTC));
+        let issue = scheduled(
+            "2024-02-19-some-slug",
+            Some(datetime!(2024-02-12 9:00 UTC)),
+        );
+        assert!(!issue.is_due(clock.now()));
+    }
+
+    #[tokio::test]
+    async fn scheduler_stops_on_shutdown() {
+        use sqlx::mysql::MySqlPoolOptions;
+
+        // nothing listens here, so every run fails
+        // quickly without a database
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://root@127.0.0.1:1/twib")
+            .unwrap();
+        let (shutdown, receiver) = watch::channel(false);
+
+        let handle = spawn(
+            pool,
+            Arc::new(SystemClock),
+            Duration::from_secs(3600),
+            receiver,
+        );
+        shutdown.send(true).unwrap();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            handle,
+        )
+        .await
+        .expect("the loop to stop")
+        .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn publishes_only_due_drafts() {
+        use crate::sql::test_db::DisposableSchema;
+
+        let schema = DisposableSchema::new().await;
+        for (slug, issue_date, publish_at) in [
+            (
+                "2024-02-12-due",
+                date!(2024 - 02 - 12),
+                datetime!(2024-02-12 9:00 UTC),
+            ),
+            (
+                "2024-02-19-later",
+                date!(2024 - 02 - 19),
+                datetime!(2024-02-19 9:00 UTC),
+            ),
+            // doesn't match its issue date
+            (
+                "2024-02-26-mismatched",
+                date!(2024 - 02 - 12),
+                datetime!(2024-02-12 9:00 UTC),
+            ),
+        ] {
+            let id: [u8; 16] =
+                rusty_ulid::Ulid::generate().into();
+            sqlx::query(
+                r#"INSERT INTO issue (id, slug, issue_date, cloudinary_public_id, publish_at, publish_scheduled_by)
+VALUES (?, ?, ?, "og", ?, "alice")"#,
+            )
+            .bind(id.as_slice())
+            .bind(slug)
+            .bind(issue_date)
+            .bind(publish_at)
+            .execute(&schema.pool)
+            .await
+            .unwrap();
+        }
+
+        let clock =
+            FixedClock(datetime!(2024-02-12 9:30 UTC));
+        assert_eq!(
+            publish_due_issues(&schema.pool, &clock)
+                .await
+                .unwrap(),
+            1
+        );
+        // already published, so nothing is left
+        assert_eq!(
+            publish_due_issues(&schema.pool, &clock)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let published: Vec<(String, Option<String>)> =
+            sqlx::query_as(
+                r#"SELECT slug, published_by FROM issue
+WHERE status = "publish" AND publish_at IS NULL"#,
+            )
+            .fetch_all(&schema.pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            published,
+            vec![(
+                "2024-02-12-due".to_string(),
+                Some("alice".to_string())
+            )]
+        );
+        schema.drop().await;
+    }
+}
@@ -1,10 +1,19 @@
 use async_trait::async_trait;
 use sqlx::MySqlPool;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use time::OffsetDateTime;
+use tokio::sync::watch;
 use tower_sessions_core::{
     session::{Id, Record},
     session_store, ExpiredDeletion, SessionStore,
 };
+use tracing::{error, info};
 
 /// A MySQL session store.
 #[derive(Clone, Debug)]
@@ -16,6 +25,101 @@ impl MySqlStore {
     pub fn new(pool: MySqlPool) -> Self {
         Self { pool }
     }
+
+    /// Delete every expired session, returning
+    /// how many rows were removed
+    pub async fn delete_expired_sessions(
+        &self,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM session WHERE expiry_date < utc_timestamp()",
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Running totals for the expired session
+/// cleanup since the server started. They're
+/// logged after every run and shown on the admin
+/// users page.
+#[derive(Debug, Default)]
+pub struct CleanupMetrics {
+    runs: AtomicU64,
+    failures: AtomicU64,
+    deleted: AtomicU64,
+}
+
+impl CleanupMetrics {
+    fn record(&self, result: &Result<u64, sqlx::Error>) {
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(deleted) => {
+                self.deleted
+                    .fetch_add(*deleted, Ordering::Relaxed);
+            }
+            Err(_) => {
+                self.failures
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Sessions deleted across every run
+    pub fn deleted(&self) -> u64 {
+        self.deleted.load(Ordering::Relaxed)
+    }
+}
+
+/// Spawn a loop that deletes expired sessions
+/// every `interval`, starting straight away. The
+/// loop stops once `shutdown` changes (or its
+/// sender is dropped), letting a run that's in
+/// progress finish first.
+pub fn spawn_expired_deletion(
+    store: MySqlStore,
+    interval: Duration,
+    metrics: Arc<CleanupMetrics>,
+    mut shutdown: watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.changed() => {
+                    info!("stopped expired session cleanup");
+                    return;
+                }
+            }
+            let result =
+                store.delete_expired_sessions().await;
+            metrics.record(&result);
+            match result {
+                Ok(deleted) => info!(
+                    deleted,
+                    total_deleted = metrics.deleted(),
+                    runs = metrics.runs(),
+                    "deleted expired sessions"
+                ),
+                Err(e) => error!(
+                    ?e,
+                    failures = metrics.failures(),
+                    runs = metrics.runs(),
+                    "failed to delete expired sessions"
+                ),
+            }
+        }
+    })
 }
 
 #[async_trait]
@@ -23,8 +127,7 @@ impl ExpiredDeletion for MySqlStore {
     async fn delete_expired(
         &self,
     ) -> session_store::Result<()> {
-        sqlx::query("DELETE FROM session WHERE expiry_date < utc_timestamp()")
-            .execute(&self.pool)
+        self.delete_expired_sessions()
             .await
             .map_err(SqlxStoreError::Sqlx)?;
         Ok(())
@@ -136,3 +239,82 @@ impl From<SqlxStoreError> for session_store::Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    #[test]
+    fn metrics_total_every_run() {
+        let metrics = CleanupMetrics::default();
+        metrics.record(&Ok(3));
+        metrics.record(&Err(sqlx::Error::PoolTimedOut));
+        metrics.record(&Ok(2));
+        assert_eq!(metrics.runs(), 3);
+        assert_eq!(metrics.failures(), 1);
+        assert_eq!(metrics.deleted(), 5);
+    }
+
+    #[tokio::test]
+    async fn cleanup_stops_on_shutdown() {
+        // nothing listens here, so every run fails
+        // quickly without a database
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://root@127.0.0.1:1/twib")
+            .unwrap();
+        let metrics = Arc::new(CleanupMetrics::default());
+        let (shutdown, receiver) = watch::channel(false);
+
+        let handle = spawn_expired_deletion(
+            MySqlStore::new(pool),
+            Duration::from_secs(3600),
+            metrics.clone(),
+            receiver,
+        );
+        // the first run starts straight away
+        while metrics.runs() == 0 {
+            tokio::time::sleep(Duration::from_millis(10))
+                .await;
+        }
+        shutdown.send(true).unwrap();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            handle,
+        )
+        .await
+        .expect("the loop to stop")
+        .unwrap();
+        assert_eq!(metrics.runs(), 1);
+        assert_eq!(metrics.failures(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn deletes_only_expired_sessions() {
+        use crate::sql::test_db::DisposableSchema;
+
+        let schema = DisposableSchema::new().await;
+        sqlx::query(
+            r#"INSERT INTO session (id, data, expiry_date) VALUES
+    ("expired", "", utc_timestamp() - INTERVAL 1 DAY),
+    ("current", "", utc_timestamp() + INTERVAL 1 DAY)"#,
+        )
+        .execute(&schema.pool)
+        .await
+        .unwrap();
+
+        let store = MySqlStore::new(schema.pool.clone());
+        assert_eq!(
+            store.delete_expired_sessions().await.unwrap(),
+            1
+        );
+        assert_eq!(
+            store.delete_expired_sessions().await.unwrap(),
+            0
+        );
+
+        schema.drop().await;
+    }
+}
@@ -0,0 +1,506 @@
+//! Shorthand for the links that come up in every
+//! issue, expanded after markdown is parsed.
+//!
+//! | written                     | becomes                   |
+//! |-----------------------------|---------------------------|
+//! | `#12345`                    | bevyengine/bevy PR 12345  |
+//! | `crate:bevy_rapier`         | the crate on crates.io    |
+//! | `docs:bevy::prelude::Query` | a docs.rs search for it   |
+//! | `@username`                 | a GitHub profile          |
+//! | `{{youtube ID}}`            | an embedded YouTube video |
+//!
+//! References only start at the beginning of a
+//! text or after whitespace or an opening
+//! bracket, so urls and email addresses are left
+//! alone. Text in code, links and images is never
+//! expanded, and neither is a reference whose
+//! first character is escaped, like `\#12345`.
+//! Telling those apart needs the document parsed
+//! with `render.escaped_char_spans` on.
+use comrak::{
+    nodes::{AstNode, NodeCode, NodeLink, NodeValue},
+    Arena,
+};
+
+/// GitHub doesn't allow longer usernames
+const MAX_USERNAME: usize = 39;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shortcode<'s> {
+    PullRequest(&'s str),
+    Crate(&'s str),
+    Docs(&'s str),
+    User(&'s str),
+    YouTube(&'s str),
+}
+
+impl<'s> Shortcode<'s> {
+    /// The shortcode at the start of `text` and
+    /// how many bytes of `text` it takes up
+    fn parse(text: &'s str) -> Option<(usize, Self)> {
+        if let Some(rest) = text.strip_prefix('#') {
+            let number =
+                take_while(rest, |c| c.is_ascii_digit());
+            let followed_by_word = rest[number.len()..]
+                .starts_with(|c: char| {
+                    c.is_alphanumeric() || c == '_'
+                });
+            return (!number.is_empty()
+                && !followed_by_word)
+                .then_some((
+                    1 + number.len(),
+                    Shortcode::PullRequest(number),
+                ));
+        }
+        if let Some(rest) = text.strip_prefix("crate:") {
+            let name = take_while(rest, |c| {
+                c.is_ascii_alphanumeric()
+                    || c == '_'
+                    || c == '-'
+            })
+            .trim_end_matches('-');
+            return name
+                .starts_with(|c: char| {
+                    c.is_ascii_alphabetic()
+                })
+                .then_some((
+                    "crate:".len() + name.len(),
+                    Shortcode::Crate(name),
+                ));
+        }
+        if let Some(rest) = text.strip_prefix("docs:") {
+            let path = take_while(rest, |c| {
+                c.is_ascii_alphanumeric()
+                    || c == '_'
+                    || c == ':'
+            })
+            .trim_end_matches(':');
+            return path
+                .starts_with(|c: char| {
+                    c.is_ascii_alphabetic()
+                })
+                .then_some((
+                    "docs:".len() + path.len(),
+                    Shortcode::Docs(path),
+                ));
+        }
+        if let Some(rest) = text.strip_prefix('@') {
+            let name = take_while(rest, |c| {
+                c.is_ascii_alphanumeric() || c == '-'
+            })
+            .trim_end_matches('-');
+            return (name.starts_with(|c: char| {
+                c.is_ascii_alphanumeric()
+            }) && name.len() <= MAX_USERNAME)
+                .then_some((
+                    1 + name.len(),
+                    Shortcode::User(name),
+                ));
+        }
+        if let Some(rest) = text.strip_prefix("{{youtube ")
+        {
+            let spaces = take_while(rest, |c| c == ' ');
+            let id =
+                take_while(&rest[spaces.len()..], |c| {
+                    c.is_ascii_alphanumeric()
+                        || c == '_'
+                        || c == '-'
+                });
+            let after = &rest[spaces.len() + id.len()..];
+            let closing = take_while(after, |c| c == ' ');
+            return (!id.is_empty()
+                && after[closing.len()..]
+                    .starts_with("}}"))
+            .then_some((
+                "{{youtube ".len()
+                    + spaces.len()
+                    + id.len()
+                    + closing.len()
+                    + "}}".len(),
+                Shortcode::YouTube(id),
+            ));
+        }
+        None
+    }
+
+    fn url(&self) -> String {
+        match self {
+            Shortcode::PullRequest(number) => format!(
+                "https://github.com/bevyengine/bevy/pull/{number}"
+            ),
+            Shortcode::Crate(name) => {
+                format!("https://crates.io/crates/{name}")
+            }
+            Shortcode::Docs(path) => {
+                let krate =
+                    path.split("::").next().unwrap_or(path);
+                if krate == *path {
+                    format!("https://docs.rs/{krate}")
+                } else {
+                    format!(
+                        "https://docs.rs/{krate}/latest/{}/?search={path}",
+                        krate.replace('-', "_")
+                    )
+                }
+            }
+            Shortcode::User(name) => {
+                format!("https://github.com/{name}")
+            }
+            Shortcode::YouTube(id) => format!(
+                "https://www.youtube-nocookie.com/embed/{id}"
+            ),
+        }
+    }
+
+    /// The node that replaces `source`, the
+    /// shortcode as it was written
+    fn node<'a>(
+        &self,
+        arena: &'a Arena<AstNode<'a>>,
+        source: &str,
+    ) -> &'a AstNode<'a> {
+        let url = self.url();
+        let text = match self {
+            // the url only has characters that
+            // `parse` checked, so it's safe to
+            // write into the attribute
+            Shortcode::YouTube(_) => {
+                return arena.alloc(
+                    NodeValue::HtmlInline(format!(
+                        r#"<iframe class="aspect-video w-full" src="{url}" title="YouTube video player" allowfullscreen></iframe>"#
+                    ))
+                    .into(),
+                );
+            }
+            Shortcode::Docs(path) => {
+                NodeValue::Code(NodeCode {
+                    num_backticks: 1,
+                    literal: path.to_string(),
+                })
+            }
+            Shortcode::Crate(name) => {
+                NodeValue::Text(name.to_string())
+            }
+            _ => NodeValue::Text(source.to_string()),
+        };
+        let link = arena.alloc(
+            NodeValue::Link(NodeLink {
+                url,
+                title: String::new(),
+            })
+            .into(),
+        );
+        link.append(arena.alloc(text.into()));
+        link
+    }
+}
+
+fn take_while(
+    text: &str,
+    f: impl Fn(char) -> bool,
+) -> &str {
+    &text[..text.find(|c| !f(c)).unwrap_or(text.len())]
+}
+
+/// Where the first shortcode in `text` starts,
+/// how long it is, and what it is. Nothing starts
+/// at the offsets that are `escaped`.
+fn find(
+    text: &str,
+    escaped: impl Fn(usize) -> bool,
+) -> Option<(usize, usize, Shortcode)> {
+    let mut previous = None;
+    for (start, c) in text.char_indices() {
+        let at_boundary =
+            previous.map_or(true, |p: char| {
+                p.is_whitespace() || matches!(p, '(' | '[')
+            });
+        if at_boundary && !escaped(start) {
+            if let Some((len, shortcode)) =
+                Shortcode::parse(&text[start..])
+            {
+                return Some((start, len, shortcode));
+            }
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+/// The text of a text node, or of an escaped
+/// character and whether it was escaped
+fn text_of<'a>(
+    node: &'a AstNode<'a>,
+) -> Option<(String, bool)> {
+    match &node.data.borrow().value {
+        NodeValue::Text(text) => {
+            Some((text.clone(), false))
+        }
+        NodeValue::Escaped => {
+            let (text, _) = text_of(node.first_child()?)?;
+            Some((text, true))
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the shortcodes in every text node
+/// under `root` with links and embeds
+pub fn expand<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    root: &'a AstNode<'a>,
+) {
+    // collected first, because the walk would
+    // otherwise visit the nodes being added
+    let texts = root
+        .descendants()
+        .filter(|node| {
+            text_of(node).is_some()
+                && !node.ancestors().skip(1).any(
+                    |ancestor| {
+                        matches!(
+                            ancestor.data.borrow().value,
+                            NodeValue::Link(_)
+                                | NodeValue::Image(_)
+                                | NodeValue::Escaped
+                        )
+                    },
+                )
+        })
+        .collect::<Vec<_>>();
+
+    for node in texts {
+        // merged into the text before it
+        if node.parent().is_none() {
+            continue;
+        }
+        // the parser splits text at characters
+        // like `_`, which crate names are full of
+        let mut text = String::new();
+        let mut escaped = vec![];
+        let mut next = Some(node);
+        while let Some(piece) = next {
+            let Some((more, was_escaped)) = text_of(piece)
+            else {
+                break;
+            };
+            if was_escaped {
+                escaped.push(text.len());
+            }
+            text.push_str(&more);
+            next = piece.next_sibling();
+            // an escaped first piece is about to
+            // become the text itself
+            if piece.same_node(node) {
+                if let Some(child) = node.first_child() {
+                    child.detach();
+                }
+            } else {
+                piece.detach();
+            }
+        }
+
+        let mut rest = text.as_str();
+        let mut offset = 0;
+        while let Some((start, len, shortcode)) =
+            find(rest, |at| {
+                escaped.contains(&(offset + at))
+            })
+        {
+            if start > 0 {
+                node.insert_before(
+                    arena.alloc(
+                        NodeValue::Text(
+                            rest[..start].to_string(),
+                        )
+                        .into(),
+                    ),
+                );
+            }
+            node.insert_before(
+                shortcode
+                    .node(arena, &rest[start..start + len]),
+            );
+            rest = &rest[start + len..];
+            offset += start + len;
+        }
+        if rest.is_empty() {
+            node.detach();
+        } else {
+            node.data.borrow_mut().value =
+                NodeValue::Text(rest.to_string());
+        }
+    }
+
+    // the rest, in links and images, go back to
+    // being plain text
+    let escapes = root
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.data.borrow().value,
+                NodeValue::Escaped
+            )
+        })
+        .collect::<Vec<_>>();
+    for escape in escapes {
+        while let Some(child) = escape.first_child() {
+            child.detach();
+            escape.insert_before(child);
+        }
+        escape.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use comrak::{format_html, parse_document, Options};
+
+    fn render(input: &str) -> String {
+        let mut options = Options::default();
+        options.render.unsafe_ = true;
+        options.render.escaped_char_spans = true;
+        let arena = Arena::new();
+        let root = parse_document(&arena, input, &options);
+        expand(&arena, root);
+        let mut html = vec![];
+        format_html(root, &options, &mut html).unwrap();
+        String::from_utf8(html).unwrap()
+    }
+
+    #[test]
+    fn parses_each_shortcode() {
+        for (text, expected) in [
+            (
+                "#12345.",
+                Some((6, Shortcode::PullRequest("12345"))),
+            ),
+            ("#12345a", None),
+            ("#", None),
+            (
+                "crate:bevy_rapier3d, and",
+                Some((
+                    "crate:bevy_rapier3d".len(),
+                    Shortcode::Crate("bevy_rapier3d"),
+                )),
+            ),
+            ("crate:-x", None),
+            (
+                "docs:bevy::prelude::Query.",
+                Some((
+                    "docs:bevy::prelude::Query".len(),
+                    Shortcode::Docs("bevy::prelude::Query"),
+                )),
+            ),
+            (
+                "@cart-.",
+                Some((5, Shortcode::User("cart"))),
+            ),
+            ("@-cart", None),
+            (
+                "{{youtube  dQw4w9WgXcQ }}",
+                Some((
+                    25,
+                    Shortcode::YouTube("dQw4w9WgXcQ"),
+                )),
+            ),
+            (
+                "{{youtube x\" onload=\"alert(1)}}",
+                None,
+            ),
+            ("{{youtube}}", None),
+        ] {
+            assert_eq!(
+                Shortcode::parse(text),
+                expected,
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            Shortcode::Docs("bevy::prelude::Query").url(),
+            "https://docs.rs/bevy/latest/bevy/?search=bevy::prelude::Query"
+        );
+        assert_eq!(
+            Shortcode::Docs("bevy-inspector-egui::quick")
+                .url(),
+            "https://docs.rs/bevy-inspector-egui/latest/bevy_inspector_egui/?search=bevy-inspector-egui::quick"
+        );
+        assert_eq!(
+            Shortcode::Docs("bevy_ecs").url(),
+            "https://docs.rs/bevy_ecs"
+        );
+    }
+
+    #[test]
+    fn expands_references_in_text() {
+        assert_eq!(
+            render(
+                "Fixed in #12345 by @alice, see crate:bevy_rapier and docs:bevy::prelude::Query."
+            ),
+            concat!(
+                "<p>Fixed in ",
+                r#"<a href="https://github.com/bevyengine/bevy/pull/12345">#12345</a>"#,
+                r#" by <a href="https://github.com/alice">@alice</a>"#,
+                r#", see <a href="https://crates.io/crates/bevy_rapier">bevy_rapier</a>"#,
+                r#" and <a href="https://docs.rs/bevy/latest/bevy/?search=bevy::prelude::Query"><code>bevy::prelude::Query</code></a>"#,
+                ".</p>\n"
+            )
+        );
+    }
+
+    #[test]
+    fn embeds_youtube_videos() {
+        assert_eq!(
+            render("{{youtube dQw4w9WgXcQ}}"),
+            concat!(
+                "<p>",
+                r#"<iframe class="aspect-video w-full" src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ" title="YouTube video player" allowfullscreen></iframe>"#,
+                "</p>\n"
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_other_text_alone() {
+        for input in [
+            "`#123 @alice crate:bevy`",
+            "[#123](https://example.com)",
+            "someone@example.com and C#10",
+            "https://example.com/#123",
+        ] {
+            let html = render(input);
+            assert!(
+                !html.contains("github.com")
+                    && !html.contains("crates.io"),
+                "{input} became {html}"
+            );
+        }
+    }
+
+    #[test]
+    fn escaped_references_stay_text() {
+        assert_eq!(
+            render(r"Not \#123 or \@alice"),
+            "<p>Not #123 or @alice</p>\n"
+        );
+        assert_eq!(
+            render(r"See crate:bevy\_rapier and \(#12)"),
+            concat!(
+                "<p>See ",
+                r#"<a href="https://crates.io/crates/bevy_rapier">bevy_rapier</a>"#,
+                " and (",
+                r#"<a href="https://github.com/bevyengine/bevy/pull/12">#12</a>"#,
+                ")</p>\n"
+            )
+        );
+        assert_eq!(
+            render(r"[\#1](https://example.com)"),
+            "<p><a href=\"https://example.com\">#1</a></p>\n"
+        );
+    }
+}
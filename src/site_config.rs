@@ -0,0 +1,537 @@
+//! Settings that differ between deployments, so a
+//! fork or staging instance can run without code
+//! edits.
+//!
+//! The config is read once at startup from an
+//! optional TOML file (the path in `SITE_CONFIG`)
+//! and then env vars, which take precedence. Any
+//! value left unset falls back to the
+//! thisweekinbevy.com defaults. See
+//! `docs/developing.md` for every setting.
+use crate::string_enum::string_enum;
+use serde::{Deserialize, Serialize};
+
+/// The canonical url the site is served from,
+/// used wherever an absolute url is needed:
+/// canonical links, feeds and the sitemap.
+#[derive(
+    Debug, Clone, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(from = "String")]
+pub struct SiteUrl(String);
+
+impl SiteUrl {
+    pub fn new(url: &str) -> Self {
+        SiteUrl(url.trim_end_matches('/').to_string())
+    }
+
+    /// ex: `https://thisweekinbevy.com`, without a
+    /// trailing slash
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// An absolute url for `path`, which should
+    /// start with a `/`
+    pub fn join(&self, path: &str) -> String {
+        format!("{}{path}", self.0)
+    }
+}
+
+impl From<String> for SiteUrl {
+    fn from(url: String) -> Self {
+        SiteUrl::new(&url)
+    }
+}
+
+impl Default for SiteUrl {
+    fn default() -> Self {
+        SiteUrl::new("https://thisweekinbevy.com")
+    }
+}
+
+#[derive(
+    Debug, Clone, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct Author {
+    pub name: String,
+    pub url: String,
+}
+
+impl Default for Author {
+    fn default() -> Self {
+        Author {
+            name: "Chris Biscardi".to_string(),
+            url: "https://www.christopherbiscardi.com/"
+                .to_string(),
+        }
+    }
+}
+
+/// Where GitHub logins are sent, and where the
+/// logged in user is looked up afterwards
+#[derive(
+    Debug, Clone, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct GitHubOAuth {
+    pub authorize_url: String,
+    pub token_url: String,
+    /// The authenticated user is fetched from
+    /// `{api_url}/user`
+    pub api_url: String,
+}
+
+impl Default for GitHubOAuth {
+    fn default() -> Self {
+        GitHubOAuth {
+            authorize_url:
+                "https://github.com/login/oauth/authorize"
+                    .to_string(),
+            token_url:
+                "https://github.com/login/oauth/access_token"
+                    .to_string(),
+            api_url: "https://api.github.com".to_string(),
+        }
+    }
+}
+
+string_enum! {
+    /// Which [`ImageBackend`](crate::images::ImageBackend)
+    /// image urls are built with
+    #[derive(Default, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ImageBackendKind {
+        #[default]
+        Cloudinary = "cloudinary",
+        /// Files in `local_images_dir`, served from
+        /// `/images`
+        Local = "local",
+    }
+    #[error("unknown image backend `{0}`")]
+    pub struct UnknownImageBackend;
+}
+
+#[derive(
+    Debug, Clone, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct SiteConfig {
+    pub site_url: SiteUrl,
+    /// The Cloudinary cloud every uploaded image
+    /// lives in
+    pub cloudinary_cloud_name: String,
+    /// Images uploaded from the admin panel are
+    /// sent to the `image/upload` endpoint of
+    /// the cloud under this url
+    pub cloudinary_api_url: String,
+    pub image_backend: ImageBackendKind,
+    /// Where the `local` image backend reads
+    /// images from
+    pub local_images_dir: String,
+    /// Credited as the author of the feeds
+    pub author: Author,
+    /// This GitHub user is made an admin when
+    /// they log in, so a fresh database has
+    /// someone who can hand out roles. Nobody is
+    /// unless it's set.
+    pub admin_username: Option<String>,
+    pub github: GitHubOAuth,
+    /// Where crate releases are imported from
+    pub crates_io_api_url: String,
+    /// How often expired sessions are deleted
+    pub session_cleanup_interval_secs: u64,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        SiteConfig {
+            site_url: SiteUrl::default(),
+            cloudinary_cloud_name: "dilgcuzda".to_string(),
+            cloudinary_api_url:
+                "https://api.cloudinary.com/v1_1"
+                    .to_string(),
+            image_backend: ImageBackendKind::default(),
+            local_images_dir: "images".to_string(),
+            author: Author::default(),
+            admin_username: None,
+            github: GitHubOAuth::default(),
+            crates_io_api_url: "https://crates.io/api/v1"
+                .to_string(),
+            session_cleanup_interval_secs: 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[cfg(any(feature = "ssr", feature = "gen-email"))]
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        path: String,
+        source: toml::de::Error,
+    },
+
+    #[error("{0} must be an absolute http or https url")]
+    InvalidUrl(&'static str),
+
+    #[error("{0} must not be empty")]
+    Empty(&'static str),
+
+    #[error("{0} must be a whole number of seconds greater than zero")]
+    InvalidInterval(&'static str),
+
+    #[error(transparent)]
+    ImageBackend(#[from] UnknownImageBackend),
+}
+
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+impl SiteConfig {
+    /// Load the config for this process. This is
+    /// only called at startup, afterwards the
+    /// config is passed around in `AppState` and
+    /// the Leptos context.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = match std::env::var("SITE_CONFIG")
+        {
+            Ok(path) => SiteConfig::from_file(&path)?,
+            Err(_) => SiteConfig::default(),
+        };
+        config.apply_env(|key| std::env::var(key).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(
+        path: &str,
+    ) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|source| ConfigError::Read {
+                path: path.to_string(),
+                source,
+            })?;
+        SiteConfig::from_toml(&contents).map_err(|source| {
+            ConfigError::Parse {
+                path: path.to_string(),
+                source,
+            }
+        })
+    }
+
+    pub fn from_toml(
+        contents: &str,
+    ) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Override any setting that has an env var.
+    /// `var` is `std::env::var` outside of tests.
+    fn apply_env(
+        &mut self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        if let Some(url) = var("SITE_URL") {
+            self.site_url = SiteUrl::new(&url);
+        }
+        if let Some(name) = var("CLOUDINARY_CLOUD_NAME") {
+            self.cloudinary_cloud_name = name;
+        }
+        if let Some(url) = var("CLOUDINARY_API_URL") {
+            self.cloudinary_api_url = url;
+        }
+        if let Some(backend) = var("IMAGE_BACKEND") {
+            self.image_backend = backend.parse()?;
+        }
+        if let Some(dir) = var("LOCAL_IMAGES_DIR") {
+            self.local_images_dir = dir;
+        }
+        if let Some(name) = var("SITE_AUTHOR_NAME") {
+            self.author.name = name;
+        }
+        if let Some(url) = var("SITE_AUTHOR_URL") {
+            self.author.url = url;
+        }
+        if let Some(username) = var("ADMIN_USERNAME") {
+            // an empty value turns the bootstrap admin
+            // off
+            self.admin_username = Some(username)
+                .filter(|username| !username.is_empty());
+        }
+        if let Some(url) = var("GITHUB_AUTHORIZE_URL") {
+            self.github.authorize_url = url;
+        }
+        if let Some(url) = var("GITHUB_TOKEN_URL") {
+            self.github.token_url = url;
+        }
+        if let Some(url) = var("GITHUB_API_URL") {
+            self.github.api_url = url;
+        }
+        if let Some(url) = var("CRATES_IO_API_URL") {
+            self.crates_io_api_url = url;
+        }
+        if let Some(secs) =
+            var("SESSION_CLEANUP_INTERVAL_SECS")
+        {
+            // anything unparseable is rejected by
+            // `validate`
+            self.session_cleanup_interval_secs =
+                secs.parse().unwrap_or(0);
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (field, value) in [
+            ("site_url", self.site_url.as_str()),
+            (
+                "cloudinary_api_url",
+                &self.cloudinary_api_url,
+            ),
+            ("author.url", &self.author.url),
+            (
+                "github.authorize_url",
+                &self.github.authorize_url,
+            ),
+            (
+                "github.token_url",
+                &self.github.token_url,
+            ),
+            ("github.api_url", &self.github.api_url),
+            (
+                "crates_io_api_url",
+                &self.crates_io_api_url,
+            ),
+        ] {
+            let valid =
+                url::Url::parse(value).is_ok_and(|url| {
+                    matches!(url.scheme(), "http" | "https")
+                });
+            if !valid {
+                return Err(ConfigError::InvalidUrl(field));
+            }
+        }
+        if self.cloudinary_cloud_name.trim().is_empty() {
+            return Err(ConfigError::Empty(
+                "cloudinary_cloud_name",
+            ));
+        }
+        if self.image_backend == ImageBackendKind::Local
+            && self.local_images_dir.trim().is_empty()
+        {
+            return Err(ConfigError::Empty(
+                "local_images_dir",
+            ));
+        }
+        if self.author.name.trim().is_empty() {
+            return Err(ConfigError::Empty("author.name"));
+        }
+        if self.session_cleanup_interval_secs == 0 {
+            return Err(ConfigError::InvalidInterval(
+                "session_cleanup_interval_secs",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Builds every image url on the site, in
+    /// the feeds and in the newsletter
+    pub fn image_backend(
+        &self,
+    ) -> Box<dyn crate::images::ImageBackend> {
+        use crate::images::{Cloudinary, LocalDisk};
+
+        match self.image_backend {
+            ImageBackendKind::Cloudinary => {
+                Box::new(Cloudinary {
+                    cloud_name: self
+                        .cloudinary_cloud_name
+                        .clone(),
+                })
+            }
+            ImageBackendKind::Local => {
+                Box::new(LocalDisk::new(self))
+            }
+        }
+    }
+
+    /// Whether `username` should be made an admin
+    /// when they log in
+    pub fn is_bootstrap_admin(
+        &self,
+        username: &str,
+    ) -> bool {
+        self.admin_username.as_deref() == Some(username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_paths_without_doubled_slashes() {
+        let site_url =
+            SiteUrl::new("https://staging.example.com/");
+        assert_eq!(
+            site_url.as_str(),
+            "https://staging.example.com"
+        );
+        assert_eq!(
+            site_url.join("/issue/2024-02-12-slug"),
+            "https://staging.example.com/issue/2024-02-12-slug"
+        );
+    }
+}
+
+#[cfg(any(feature = "ssr", feature = "gen-email"))]
+#[cfg(test)]
+mod tests_server {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn defaults_are_valid() {
+        SiteConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn nobody_is_an_admin_by_default() {
+        let config = SiteConfig::default();
+        assert_eq!(config.admin_username, None);
+        assert!(!config
+            .is_bootstrap_admin("ChristopherBiscardi"));
+    }
+
+    #[test]
+    fn toml_overrides_only_what_it_sets() {
+        let config = SiteConfig::from_toml(
+            r#"
+site_url = "https://staging.example.com/"
+cloudinary_cloud_name = "staging"
+
+[github]
+api_url = "http://localhost:9000"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.site_url.as_str(),
+            "https://staging.example.com"
+        );
+        assert_eq!(config.cloudinary_cloud_name, "staging");
+        assert_eq!(
+            config.github.api_url,
+            "http://localhost:9000"
+        );
+        assert_eq!(
+            config.github.token_url,
+            GitHubOAuth::default().token_url
+        );
+        assert_eq!(config.author, Author::default());
+    }
+
+    #[test]
+    fn env_takes_precedence() {
+        let env = HashMap::from([
+            ("SITE_URL", "https://fork.example.com"),
+            ("SITE_AUTHOR_NAME", "Someone Else"),
+            ("ADMIN_USERNAME", ""),
+        ]);
+        let mut config = SiteConfig::from_toml(
+            r#"site_url = "https://staging.example.com""#,
+        )
+        .unwrap();
+        config
+            .apply_env(|key| {
+                env.get(key).map(|value| value.to_string())
+            })
+            .unwrap();
+        assert_eq!(
+            config.site_url.as_str(),
+            "https://fork.example.com"
+        );
+        assert_eq!(config.author.name, "Someone Else");
+        assert_eq!(config.admin_username, None);
+        assert!(!config.is_bootstrap_admin(""));
+    }
+
+    #[test]
+    fn validate_rejects_bad_values() {
+        let config = SiteConfig {
+            site_url: SiteUrl::new("thisweekinbevy.com"),
+            ..SiteConfig::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidUrl("site_url"))
+        ));
+
+        let config = SiteConfig {
+            cloudinary_cloud_name: " ".to_string(),
+            ..SiteConfig::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::Empty(
+                "cloudinary_cloud_name"
+            ))
+        ));
+
+        let mut config = SiteConfig::default();
+        config
+            .apply_env(|key| {
+                (key == "SESSION_CLEANUP_INTERVAL_SECS")
+                    .then(|| "hourly".to_string())
+            })
+            .unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidInterval(_))
+        ));
+    }
+
+    #[test]
+    fn images_use_the_configured_cloud() {
+        let config = SiteConfig {
+            cloudinary_cloud_name: "staging".to_string(),
+            ..SiteConfig::default()
+        };
+        assert_eq!(
+            config.image_backend().url("thisweekinbevy/og", None, None),
+            "https://res.cloudinary.com/staging/image/upload/thisweekinbevy/og"
+        );
+    }
+
+    #[test]
+    fn images_can_be_served_locally() {
+        let mut config = SiteConfig {
+            site_url: SiteUrl::new("http://localhost:3000"),
+            ..SiteConfig::default()
+        };
+        config
+            .apply_env(|key| {
+                (key == "IMAGE_BACKEND")
+                    .then(|| "local".to_string())
+            })
+            .unwrap();
+        assert_eq!(
+            config.image_backend().url("thisweekinbevy/og", Some(600), None),
+            "http://localhost:3000/images/thisweekinbevy/og?w=600"
+        );
+
+        assert!(matches!(
+            config.apply_env(|key| {
+                (key == "IMAGE_BACKEND")
+                    .then(|| "s3".to_string())
+            }),
+            Err(ConfigError::ImageBackend(_))
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! `/sitemap.xml` and `/robots.txt`, so search
+//! engines can find every published issue
+//! without crawling for them.
+use crate::{
+    issue_content::escape,
+    site_config::{SiteConfig, SiteUrl},
+    state::AppState,
+};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::fmt::Write;
+use time::{
+    format_description::well_known::Rfc3339, OffsetDateTime,
+};
+use tracing::error;
+
+struct SqlSitemapIssue {
+    slug: String,
+    updated_at: OffsetDateTime,
+}
+
+pub async fn sitemap(
+    State(app_state): State<AppState>,
+    State(config): State<SiteConfig>,
+) -> Response {
+    let issues = sqlx::query_as!(
+        SqlSitemapIssue,
+        r#"SELECT
+    slug,
+    updated_at
+FROM issue
+WHERE status = "publish"
+ORDER BY issue_date DESC"#
+    )
+    .fetch_all(&app_state.pool)
+    .await;
+
+    match issues {
+        Ok(issues) => (
+            [(
+                header::CONTENT_TYPE,
+                "application/xml; charset=utf-8",
+            )],
+            render_sitemap(&config.site_url, &issues),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(?e, "failed to load the sitemap");
+            StatusCode::INTERNAL_SERVER_ERROR
+                .into_response()
+        }
+    }
+}
+
+fn render_sitemap(
+    site_url: &SiteUrl,
+    issues: &[SqlSitemapIssue],
+) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+"#,
+    );
+    let lastmod = |date: OffsetDateTime| {
+        date.format(&Rfc3339)
+            .map(|date| {
+                format!("<lastmod>{date}</lastmod>")
+            })
+            .unwrap_or_default()
+    };
+
+    // the homepage changes whenever an issue does
+    writeln!(
+        xml,
+        "<url><loc>{}</loc>{}</url>",
+        escape(&site_url.join("/")),
+        issues
+            .iter()
+            .map(|issue| issue.updated_at)
+            .max()
+            .map(lastmod)
+            .unwrap_or_default()
+    )
+    .unwrap();
+    for issue in issues {
+        writeln!(
+            xml,
+            "<url><loc>{}</loc>{}</url>",
+            escape(
+                &site_url.join(&format!(
+                    "/issue/{}",
+                    issue.slug
+                ))
+            ),
+            lastmod(issue.updated_at)
+        )
+        .unwrap();
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+pub async fn robots(
+    State(config): State<SiteConfig>,
+) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; charset=utf-8",
+        )],
+        render_robots(&config.site_url),
+    )
+}
+
+fn render_robots(site_url: &SiteUrl) -> String {
+    format!(
+        "User-agent: *
+Disallow: /admin
+Disallow: /api
+
+Sitemap: {}
+",
+        site_url.join("/sitemap.xml")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn sitemap_lists_issues_with_lastmod() {
+        let xml = render_sitemap(
+            &SiteUrl::new("https://staging.example.com"),
+            &[
+                SqlSitemapIssue {
+                    slug: "2024-02-12-bevy-0-13"
+                        .to_string(),
+                    updated_at: datetime!(2024-02-14 09:00 UTC),
+                },
+                SqlSitemapIssue {
+                    slug: "2024-02-05-a&b".to_string(),
+                    updated_at: datetime!(2024-02-05 12:00 UTC),
+                },
+            ],
+        );
+        assert!(xml.contains(
+            "<url><loc>https://staging.example.com/</loc><lastmod>2024-02-14T09:00:00Z</lastmod></url>"
+        ));
+        assert!(xml.contains(
+            "<url><loc>https://staging.example.com/issue/2024-02-12-bevy-0-13</loc><lastmod>2024-02-14T09:00:00Z</lastmod></url>"
+        ));
+        assert!(xml.contains(
+            "<loc>https://staging.example.com/issue/2024-02-05-a&amp;b</loc>"
+        ));
+        assert!(xml.ends_with("</urlset>\n"));
+    }
+
+    #[test]
+    fn empty_sitemap_still_has_the_homepage() {
+        let xml = render_sitemap(&SiteUrl::default(), &[]);
+        assert!(xml.contains(
+            "<url><loc>https://thisweekinbevy.com/</loc></url>"
+        ));
+    }
+
+    #[test]
+    fn robots_disallows_admin_and_api() {
+        let robots = render_robots(&SiteUrl::default());
+        assert!(robots.contains("Disallow: /admin\n"));
+        assert!(robots.contains("Disallow: /api\n"));
+        assert!(robots.contains(
+            "Sitemap: https://thisweekinbevy.com/sitemap.xml"
+        ));
+    }
+}
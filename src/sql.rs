@@ -6,7 +6,7 @@ use sqlx::MySqlPool;
 use std::time::Duration;
 
 #[cfg(feature = "ssr")]
-use crate::Username;
+use crate::{roles::Role, Username};
 
 #[cfg(feature = "ssr")]
 pub fn pool() -> Result<MySqlPool, ServerFnError> {
@@ -15,24 +15,45 @@ pub fn pool() -> Result<MySqlPool, ServerFnError> {
     })
 }
 
+/// The [`SiteConfig`](crate::site_config::SiteConfig)
+/// provided at startup, or the defaults outside
+/// of a request.
+#[cfg(feature = "ssr")]
+pub fn site_config() -> crate::site_config::SiteConfig {
+    use_context::<crate::site_config::SiteConfig>()
+        .unwrap_or_default()
+}
+
 #[cfg(feature = "ssr")]
 pub fn with_admin_access() -> Result<Username, ServerFnError>
 {
-    let Some(Username(username)) =
-        use_context::<Option<crate::Username>>().flatten()
-    else {
-        return Err(ServerFnError::ServerError(
-            "not allowed to submit via admin panel"
-                .to_string(),
-        ));
-    };
-    if username != "ChristopherBiscardi" {
-        return Err(ServerFnError::ServerError(
+    with_role(crate::roles::has_admin_access)
+}
+
+/// Like [`with_admin_access`], for actions that
+/// only admins can take, such as changing roles.
+#[cfg(feature = "ssr")]
+pub fn with_role_management_access(
+) -> Result<Username, ServerFnError> {
+    with_role(|role| {
+        role.is_some_and(|role| role.can_manage_roles())
+    })
+}
+
+#[cfg(feature = "ssr")]
+fn with_role(
+    allowed: impl Fn(Option<Role>) -> bool,
+) -> Result<Username, ServerFnError> {
+    let username =
+        use_context::<Option<Username>>().flatten();
+    let role = use_context::<Option<Role>>().flatten();
+    match username {
+        Some(username) if allowed(role) => Ok(username),
+        _ => Err(ServerFnError::ServerError(
             "not allowed to submit via admin panel"
                 .to_string(),
-        ));
+        )),
     }
-    Ok(Username(username))
 }
 
 #[cfg(feature = "ssr")]
@@ -280,3 +301,81 @@ impl From<SqlOpenedIssueInfo> for ClientOpenedIssueInfo {
         }
     }
 }
+
+/// A throwaway database with every migration
+/// applied by [`crate::migrate::run`], for tests
+/// that need a real MySQL. `TEST_DATABASE_URL`
+/// should point at a server where the tests can
+/// create and drop databases, for example
+/// `mysql://root@127.0.0.1:3306`.
+#[cfg(all(test, feature = "ssr"))]
+pub mod test_db {
+    use sqlx::{
+        mysql::MySqlPoolOptions, Executor, MySqlPool,
+    };
+
+    pub struct DisposableSchema {
+        server: MySqlPool,
+        name: String,
+        pub pool: MySqlPool,
+    }
+
+    impl DisposableSchema {
+        pub async fn new() -> Self {
+            let url = std::env::var("TEST_DATABASE_URL")
+                .expect(
+                    "TEST_DATABASE_URL to be set for database tests",
+                );
+            let server = MySqlPoolOptions::new()
+                .max_connections(1)
+                .connect(&url)
+                .await
+                .expect("to connect to the test server");
+
+            let name = format!(
+                "twib_test_{}",
+                rusty_ulid::Ulid::generate()
+                    .to_string()
+                    .to_lowercase()
+            );
+            server
+                .execute(
+                    format!("CREATE DATABASE {name}")
+                        .as_str(),
+                )
+                .await
+                .expect("to create a disposable schema");
+
+            let mut schema_url = url::Url::parse(&url)
+                .expect("a valid TEST_DATABASE_URL");
+            schema_url.set_path(&name);
+            let pool = MySqlPoolOptions::new()
+                .connect(schema_url.as_str())
+                .await
+                .expect(
+                    "to connect to the disposable schema",
+                );
+
+            crate::migrate::run(&pool)
+                .await
+                .unwrap_or_else(|e| panic!("{e}"));
+
+            Self { server, name, pool }
+        }
+
+        /// Drop the schema. Call this at the end
+        /// of each test; a panicking test
+        /// leaves its `twib_test_*`
+        /// schema behind for inspection.
+        pub async fn drop(self) {
+            self.pool.close().await;
+            self.server
+                .execute(
+                    format!("DROP DATABASE {}", self.name)
+                        .as_str(),
+                )
+                .await
+                .expect("to drop the disposable schema");
+        }
+    }
+}
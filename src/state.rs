@@ -1,8 +1,13 @@
+use crate::{
+    crates_io_ingest::BackgroundImport,
+    session_store::CleanupMetrics, site_config::SiteConfig,
+};
 use axum::extract::FromRef;
 use leptos::prelude::LeptosOptions;
 use leptos_axum::AxumRouteListing;
 // use leptos_router::RouteListing;
 use sqlx::MySqlPool;
+use std::sync::Arc;
 
 /// This takes advantage of Axum's SubStates
 /// feature by deriving FromRef. This is the only
@@ -15,4 +20,7 @@ pub struct AppState {
     pub leptos_options: LeptosOptions,
     pub pool: MySqlPool,
     pub routes: Vec<AxumRouteListing>,
+    pub config: SiteConfig,
+    pub crates_io_import: BackgroundImport,
+    pub session_cleanup: Arc<CleanupMetrics>,
 }
@@ -0,0 +1,107 @@
+//! Enums stored and sent as a fixed set of
+//! strings, like the `role` columns or a form's
+//! `direction` field.
+
+/// Declares an enum where every variant has a
+/// string, with `ALL`, `as_str`, `Display`, an
+/// error for unknown strings and `FromStr`.
+///
+/// ```ignore
+/// string_enum! {
+///     #[derive(Serialize, Deserialize)]
+///     pub enum Direction {
+///         Up = "up",
+///         Down = "down",
+///     }
+///     #[error("unknown direction `{0}`")]
+///     pub struct UnknownDirection;
+/// }
+/// ```
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $string:literal
+            ),+ $(,)?
+        }
+        #[error($message:literal)]
+        $error_vis:vis struct $error:ident;
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+        }
+
+        impl $name {
+            pub const ALL: [$name; [$($string),+].len()] =
+                [$($name::$variant),+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $string,)+
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(
+                &self,
+                f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        #[derive(Debug, thiserror::Error, PartialEq)]
+        #[error($message)]
+        $error_vis struct $error(pub String);
+
+        impl std::str::FromStr for $name {
+            type Err = $error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($string => Ok($name::$variant),)+
+                    other => Err($error(other.to_string())),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use string_enum;
+
+#[cfg(test)]
+mod tests {
+    string_enum! {
+        enum Fruit {
+            Apple = "apple",
+            Pear = "pear",
+        }
+        #[error("unknown fruit `{0}`")]
+        struct UnknownFruit;
+    }
+
+    #[test]
+    fn round_trips_through_strings() {
+        assert_eq!(Fruit::ALL, [Fruit::Apple, Fruit::Pear]);
+        for fruit in Fruit::ALL {
+            assert_eq!(fruit.as_str().parse(), Ok(fruit));
+            assert_eq!(fruit.to_string(), fruit.as_str());
+        }
+        assert_eq!(
+            "plum".parse::<Fruit>(),
+            Err(UnknownFruit("plum".to_string()))
+        );
+        assert_eq!(
+            UnknownFruit("plum".to_string()).to_string(),
+            "unknown fruit `plum`"
+        );
+    }
+}
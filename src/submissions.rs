@@ -0,0 +1,269 @@
+//! Community submissions of showcases, crate
+//! releases, devlogs and educationals.
+//!
+//! Submissions are stored in the same tables as
+//! admin created content with a `pending`
+//! `review_status`. They can only be added to an
+//! issue once an admin approves them.
+use crate::string_enum::string_enum;
+use serde::{Deserialize, Serialize};
+
+string_enum! {
+    /// The strings match the table names
+    #[derive(Serialize, Deserialize)]
+    pub enum SubmissionKind {
+        Showcase = "showcase",
+        CrateRelease = "crate_release",
+        Devlog = "devlog",
+        Educational = "educational",
+    }
+    #[error("unknown submission kind `{0}`")]
+    pub struct UnknownSubmissionKind;
+}
+
+impl SubmissionKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SubmissionKind::Showcase => "Showcase",
+            SubmissionKind::CrateRelease => "Crate Release",
+            SubmissionKind::Devlog => "Devlog",
+            SubmissionKind::Educational => "Educational",
+        }
+    }
+
+    /// The admin page for editing a submission
+    pub fn admin_path(&self, id: &str) -> String {
+        format!("/admin/{}/{id}", self.as_str())
+    }
+
+    /// Devlogs and educationals have separate
+    /// video and post urls instead of a single
+    /// url
+    pub fn has_video_url(&self) -> bool {
+        matches!(
+            self,
+            SubmissionKind::Devlog
+                | SubmissionKind::Educational
+        )
+    }
+}
+
+string_enum! {
+    #[derive(Serialize, Deserialize)]
+    pub enum ReviewStatus {
+        Pending = "pending",
+        Approved = "approved",
+        Rejected = "rejected",
+    }
+    #[error("unknown review status `{0}`")]
+    pub struct UnknownReviewStatus;
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SubmissionError {
+    #[error(transparent)]
+    UnknownKind(#[from] UnknownSubmissionKind),
+    #[error(transparent)]
+    UnknownStatus(#[from] UnknownReviewStatus),
+    #[error("{0} is required")]
+    Missing(&'static str),
+    #[error("{0} must be at most {1} characters")]
+    TooLong(&'static str, usize),
+    #[error("{0} must be an http or https url")]
+    InvalidUrl(&'static str),
+}
+
+/// A community submission as entered in the
+/// public form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    pub kind: SubmissionKind,
+    pub title: String,
+    /// The project url for showcases and crate
+    /// releases, the written post for devlogs and
+    /// educationals
+    pub url: String,
+    /// Only used by devlogs and educationals
+    pub video_url: String,
+    pub discord_url: String,
+    pub description: String,
+}
+
+// these match the column sizes in
+// sql/001-tables.sql
+const TITLE_MAX: usize = 500;
+const URL_MAX: usize = 500;
+const DESCRIPTION_MAX: usize = 5000;
+
+impl Submission {
+    /// Trim whitespace and check the fields fit
+    /// the columns they're stored in.
+    pub fn validate(self) -> Result<Self, SubmissionError> {
+        let submission = Submission {
+            kind: self.kind,
+            title: self.title.trim().to_string(),
+            url: self.url.trim().to_string(),
+            video_url: if self.kind.has_video_url() {
+                self.video_url.trim().to_string()
+            } else {
+                String::new()
+            },
+            discord_url: self
+                .discord_url
+                .trim()
+                .to_string(),
+            description: self
+                .description
+                .trim()
+                .to_string(),
+        };
+
+        if submission.title.is_empty() {
+            return Err(SubmissionError::Missing("title"));
+        }
+        if submission.description.is_empty() {
+            return Err(SubmissionError::Missing(
+                "description",
+            ));
+        }
+        if submission.url.is_empty()
+            && submission.video_url.is_empty()
+        {
+            return Err(SubmissionError::Missing("url"));
+        }
+        check_length(
+            "title",
+            &submission.title,
+            TITLE_MAX,
+        )?;
+        check_length(
+            "description",
+            &submission.description,
+            DESCRIPTION_MAX,
+        )?;
+        for (field, url) in [
+            ("url", &submission.url),
+            ("video url", &submission.video_url),
+            ("discord url", &submission.discord_url),
+        ] {
+            check_length(field, url, URL_MAX)?;
+            if !url.is_empty()
+                && !url.starts_with("https://")
+                && !url.starts_with("http://")
+            {
+                return Err(SubmissionError::InvalidUrl(
+                    field,
+                ));
+            }
+        }
+
+        Ok(submission)
+    }
+}
+
+fn check_length(
+    field: &'static str,
+    value: &str,
+    max: usize,
+) -> Result<(), SubmissionError> {
+    if value.chars().count() > max {
+        Err(SubmissionError::TooLong(field, max))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(kind: SubmissionKind) -> Submission {
+        Submission {
+            kind,
+            title: " Hexagon procedural generation "
+                .to_string(),
+            url: "https://example.com".to_string(),
+            video_url: "https://youtube.com/watch?v=1"
+                .to_string(),
+            discord_url: String::new(),
+            description: "a description".to_string(),
+        }
+    }
+
+    #[test]
+    fn kinds_round_trip_through_strings() {
+        for kind in SubmissionKind::ALL {
+            assert_eq!(kind.as_str().parse(), Ok(kind));
+        }
+        assert_eq!(
+            "issue".parse::<SubmissionKind>(),
+            Err(UnknownSubmissionKind(
+                "issue".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn validate_trims_and_drops_unused_fields() {
+        let showcase = submission(SubmissionKind::Showcase)
+            .validate()
+            .unwrap();
+        assert_eq!(
+            showcase.title,
+            "Hexagon procedural generation"
+        );
+        assert_eq!(showcase.video_url, "");
+
+        let devlog = submission(SubmissionKind::Devlog)
+            .validate()
+            .unwrap();
+        assert_eq!(
+            devlog.video_url,
+            "https://youtube.com/watch?v=1"
+        );
+    }
+
+    #[test]
+    fn validate_requires_fields() {
+        let mut s = submission(SubmissionKind::Showcase);
+        s.title = "  ".to_string();
+        assert_eq!(
+            s.validate(),
+            Err(SubmissionError::Missing("title"))
+        );
+
+        let mut s = submission(SubmissionKind::Showcase);
+        s.url = String::new();
+        assert_eq!(
+            s.validate(),
+            Err(SubmissionError::Missing("url"))
+        );
+
+        // a video is enough for a devlog
+        let mut s = submission(SubmissionKind::Devlog);
+        s.url = String::new();
+        assert!(s.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_urls_and_long_fields() {
+        let mut s = submission(SubmissionKind::Showcase);
+        s.discord_url = "javascript:alert(1)".to_string();
+        assert_eq!(
+            s.validate(),
+            Err(SubmissionError::InvalidUrl(
+                "discord url"
+            ))
+        );
+
+        let mut s = submission(SubmissionKind::Educational);
+        s.description = "a".repeat(DESCRIPTION_MAX + 1);
+        assert_eq!(
+            s.validate(),
+            Err(SubmissionError::TooLong(
+                "description",
+                DESCRIPTION_MAX
+            ))
+        );
+    }
+}
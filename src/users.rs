@@ -9,15 +9,29 @@ use oauth2::{
 };
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, MySqlPool};
-use tracing::{error, instrument};
+use tracing::{error, instrument, warn};
+
+use crate::{roles::Role, site_config::SiteConfig};
 
 #[derive(Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub github_id: String,
     pub username: String,
+    pub role: String,
     pub access_token: String,
 }
 
+impl User {
+    /// Unknown values in the `role` column are
+    /// treated as the least privileged role.
+    pub fn role(&self) -> Role {
+        self.role.parse().unwrap_or_else(|e| {
+            warn!(?e, username = self.username);
+            Role::Contributor
+        })
+    }
+}
+
 // Here we've implemented `Debug` manually to
 // avoid accidentally logging the access token.
 impl std::fmt::Debug for User {
@@ -28,6 +42,7 @@ impl std::fmt::Debug for User {
         f.debug_struct("User")
             // .field("github_id", &self.github_id)
             .field("username", &self.username)
+            .field("role", &self.role)
             .field("access_token", &"[redacted]")
             .finish()
     }
@@ -74,12 +89,17 @@ pub enum BackendError {
 pub struct Backend {
     db: MySqlPool,
     client: BasicClient,
+    config: SiteConfig,
 }
 
 impl Backend {
     #[instrument(skip(db, client))]
-    pub fn new(db: MySqlPool, client: BasicClient) -> Self {
-        Self { db, client }
+    pub fn new(
+        db: MySqlPool,
+        client: BasicClient,
+        config: SiteConfig,
+    ) -> Self {
+        Self { db, client, config }
     }
     #[instrument(skip(self))]
     pub fn authorize_url(&self) -> (Url, CsrfToken) {
@@ -124,7 +144,10 @@ impl AuthnBackend for Backend {
 
         // Use access token to request user info.
         let user_info = reqwest::Client::new()
-            .get("https://api.github.com/user")
+            .get(format!(
+                "{}/user",
+                self.config.github.api_url
+            ))
             .header(
                 USER_AGENT.as_str(),
                 "this-week-in-bevy",
@@ -144,19 +167,27 @@ impl AuthnBackend for Backend {
             .map_err(Self::Error::Reqwest)?;
 
         // Persist user in our database so we can use
-        // `get_user`.
+        // `get_user`. The configured admin is
+        // promoted on every login so a fresh
+        // database always has someone who can hand
+        // out roles.
+        let is_admin = self
+            .config
+            .is_bootstrap_admin(&user_info.login);
         let _num_rows_affected = sqlx::query(
             r#"
-            insert into github_users (github_id, username, access_token)
-            values (?, ?, ?)
+            insert into github_users (github_id, username, access_token, role)
+            values (?, ?, ?, IF(?, 'admin', 'contributor'))
             ON DUPLICATE KEY
-            UPDATE access_token = ?;
+            UPDATE access_token = ?, role = IF(?, 'admin', role);
             "#,
         )
         .bind(&user_info.id.to_string())
         .bind(&user_info.login)
         .bind(token_res.access_token().secret())
+        .bind(is_admin)
         .bind(token_res.access_token().secret())
+        .bind(is_admin)
         .execute(&self.db)
         .await
         .map_err(Self::Error::Sqlx)?;